@@ -1,85 +1,414 @@
-#![no_std]
-
-use crate::memory::MemoryError;
-use crate::uart::Uart0; // initialize_peripherals icinde UART init cagrisi icin (veya global UART)
-
-#[derive(Debug)]
-pub enum BootError {
-    EarlyHardwareInitError,
-    MemoryInitError(MemoryError),
-    PeripheralInitError,
-}
-
-impl From<MemoryError> for BootError {
-    fn from(err: MemoryError) -> Self {
-        BootError::MemoryInitError(err)
-    }
-}
-
-
-/// Sistemdeki en erken donanım başlatma adımlarını gerçekleştirir.
-/// Bu, genellikle saat kaynaklarını ve temel güç ayarlarını yapılandırmayı içerir.
-/// # Safety
-/// Donanım yazmaçlarına doğrudan erişim gerektirir, "unsafe"dir.
-pub unsafe fn perform_early_hardware_init() -> Result<(), BootError> {
-    // --- GERÇEK BAŞLATMA KODU BURAYA GELECEK ---
-    // SiFive S21'in Clock Generator ve Power Management birimlerini yapılandırın.
-
-    // Örnek placeholder: Sistem Saat Kaynağını ve PLL'leri Yapılandırma
-    // İşlemcinin ve çevre birimlerinin doğru frekansta çalışması için kritik.
-    // Buradaki adresler ve değerler tamamen varsayımsaldir!
-     const CLOCK_GEN_BASE: usize = 0xXXXX_0000; // SiFive S21 Clock Generator Base Adresi - VERİ SAYFASINDAN BULUN!
-     const CLOCK_PLL_CFG: usize = 0x04; // PLL Yapılandırma Register Ofseti - VERİ SAYFASINDAN BULUN!
-     const CLOCK_ENABLE_REG: usize = 0x08; // Saat Etkinleştirme Register Ofseti - VERİ SAYFASINDAN BULUN!
-
-     let pll_cfg_ptr = (CLOCK_GEN_BASE + CLOCK_PLL_CFG) as *mut u32;
-     let enable_reg_ptr = (CLOCK_GEN_BASE + CLOCK_ENABLE_REG) as *mut u32;
-
-    // // Örnek: PLL'i yapılandır (Değerler frekans hesabına göre değişir)
-     pll_cfg_ptr.write_volatile(0x12345678); // Varsayımsal PLL yapılandırma değeri
-
-    // // Örnek: Ana saatleri etkinleştir
-     enable_reg_ptr.write_volatile(0xFF); // Varsayımsal etkinleştirme değeri
-
-    // Örnek placeholder: Temel Güç Yönetimi Ayarları
-    // Gerekirse voltaj regülatörlerini ayarlama veya düşük güç modlarını yapılandırma.
-     const POWER_MGMT_BASE: usize = 0xYYYY_0000; // SiFive S21 Power Management Base Adresi - VERİ SAYFASINDAN BULUN!
-    // ... register erişimleri ...
-
-
-    // Başlatma başarılı olursa
-    Ok(())
-
-    // Başlatma sırasında hata oluşursa (donanım register'larından okunabilir)
-     Err(BootError::EarlyHardwareInitError)
-}
-
-/// Bellek sistemini başlatır (LPDDR1).
-/// # Safety
-/// Bellek kontrolcüsüne erişim içerebilir, bu nedenle "unsafe"dir.
-pub unsafe fn initialize_memory() -> Result<(), BootError> {
-    // --- GERÇEK BAŞLATMA KODU BURAYA GELECEK ---
-    // `memory.rs` dosyasındaki LPDDR1 başlatma fonksiyonunu çağır.
-    // Bu fonksiyonun içi artık gerçek başlatma adımlarını içermelidir.
-    crate::memory::init_memory().map_err(BootError::MemoryInitError)
-}
-
-/// Temel çevre birimlerini başlatır (UART gibi).
-/// # Safety
-/// Çevre birimi donanımına erişim içerebilir, bu nedenle "unsafe"dir.
-pub unsafe fn initialize_peripherals() -> Result<(), BootError> {
-    // --- GERÇEK BAŞLATMA KODU BURAYA GELECEK ---
-    // UART0 gibi temel çevre birimlerini başlat.
-    // UART başlatma fonksiyonu artık gerçek register ayarlamalarını içermelidir.
-    // UART'ın init fonksiyonu Result döndürmediği için başarı varsayılıyor:
-    crate::uart::UART0_GLOBAL.init();
-    // Eğer UART init Result döndürseydi:
-    // crate::uart::UART0_GLOBAL.init().map_err(|_| BootError::PeripheralInitError)?;
-
-    // Diğer temel çevre birimleri (örneğin, basit bir Timer veya Watchdog Timer)
-    // ... timer_module::init()?; ...
-
-
-    // Başarılı sayalım
-    Ok(())
-}
\ No newline at end of file
+#![no_std]
+
+use crate::crc::Crc32;
+use crate::fat::{self, FatError, FatVolume};
+use crate::image::{ImageError, ImageHeader, IMAGE_HEADER_MAX_SIZE, IMAGE_VERSION};
+use crate::measured::{self, BootStage, EVENT_FLAG_SIGNATURE_VERIFIED};
+use crate::memory::{self, MemoryError};
+#[cfg(not(feature = "hosted"))]
+use crate::mmio::Mmio;
+use crate::mmio::RegisterBus;
+use crate::partition::{self, PartitionError, PartitionView};
+use crate::secureboot::{self, SecureBootError};
+use crate::storage::{BlockDevice, StorageError, BLOCK_SIZE};
+use crate::uart::Uart0; // initialize_peripherals icinde UART init cagrisi icin (veya global UART)
+use crate::xmodem::{self, Protocol, TransferError};
+
+#[derive(Debug)]
+pub enum BootError {
+    EarlyHardwareInitError,
+    MemoryInitError(MemoryError),
+    PeripheralInitError,
+    InvalidBootPath,            // "<cihaz>:<bölüm>/<yol>" biçimine uymuyor
+    Storage(StorageError),      // Aygıt yok veya okunamadı
+    Partition(PartitionError),  // Bölüm bulunamadı / tablo bozuk
+    Filesystem(FatError),       // Dosya sistemi bağlanamadı veya dosya bulunamadı
+    Image(ImageError),          // İmaj başlığı/yükü geçersiz veya yükleme aralığı reddedildi
+    SecureBoot(SecureBootError),  // İmza doğrulaması başarısız ve politika 'enforce'
+    Transfer(TransferError),    // Seri hattan alma (loady/loadx) başarısız
+}
+
+impl From<MemoryError> for BootError {
+    fn from(err: MemoryError) -> Self {
+        BootError::MemoryInitError(err)
+    }
+}
+
+impl From<StorageError> for BootError {
+    fn from(err: StorageError) -> Self {
+        BootError::Storage(err)
+    }
+}
+
+impl From<PartitionError> for BootError {
+    fn from(err: PartitionError) -> Self {
+        BootError::Partition(err)
+    }
+}
+
+impl From<FatError> for BootError {
+    fn from(err: FatError) -> Self {
+        BootError::Filesystem(err)
+    }
+}
+
+impl From<ImageError> for BootError {
+    fn from(err: ImageError) -> Self {
+        BootError::Image(err)
+    }
+}
+
+impl From<SecureBootError> for BootError {
+    fn from(err: SecureBootError) -> Self {
+        BootError::SecureBoot(err)
+    }
+}
+
+impl From<TransferError> for BootError {
+    fn from(err: TransferError) -> Self {
+        BootError::Transfer(err)
+    }
+}
+
+/// Boot kaynağını tanımlayan yol: `<cihaz>:<bölüm>[/<dosya yolu>]`, örn. `sd:1/sahnebox/kernel.bin`.
+/// Dosya yolu boşsa hedef bölümün kendisidir.
+#[derive(Debug, Copy, Clone)]
+pub struct BootPath<'a> {
+    pub device: &'a str,
+    pub partition: usize,
+    pub path: &'a str,
+}
+
+impl<'a> BootPath<'a> {
+    pub fn parse(spec: &'a str) -> Result<Self, BootError> {
+        let (device, rest) = spec.split_once(':').ok_or(BootError::InvalidBootPath)?;
+        let (partition, path) = match rest.find('/') {
+            Some(slash) => (&rest[..slash], &rest[slash..]),
+            None => (rest, ""),
+        };
+        if device.is_empty() {
+            return Err(BootError::InvalidBootPath);
+        }
+        let partition = partition.parse::<usize>().map_err(|_| BootError::InvalidBootPath)?;
+        Ok(BootPath { device, partition, path })
+    }
+
+    /// Yol bir dosyayı mı (true) yoksa bölümün tamamını mı gösteriyor.
+    pub fn is_file(&self) -> bool {
+        !self.path.trim_matches('/').is_empty()
+    }
+}
+
+/// İmajın okunduğu kaynak (FAT dosyası veya ham bölüm).
+pub trait ImageSource {
+    /// `offset`'ten itibaren `buffer`'ı doldurmaya çalışır; kaynağın sonunda daha az bayt dönebilir.
+    fn read_at(&mut self, offset: u64, buffer: &mut [u8]) -> Result<usize, BootError>;
+}
+
+/// FAT bölümündeki bir dosya.
+pub struct FileSource<D: BlockDevice> {
+    volume: FatVolume<D>,
+    file: fat::File,
+}
+
+impl<D: BlockDevice> ImageSource for FileSource<D> {
+    fn read_at(&mut self, offset: u64, buffer: &mut [u8]) -> Result<usize, BootError> {
+        if offset >= self.file.size() as u64 {
+            return Ok(0);
+        }
+        self.volume.seek(&mut self.file, offset as u32);
+        let mut done = 0;
+        while done < buffer.len() {
+            let n = self.volume.read(&mut self.file, &mut buffer[done..])?;
+            if n == 0 {
+                break;
+            }
+            done += n;
+        }
+        Ok(done)
+    }
+}
+
+/// Ham bölüm: imaj bölümün ilk bloğundan başlar.
+pub struct BlockSource<D: BlockDevice> {
+    device: D,
+}
+
+impl<D: BlockDevice> ImageSource for BlockSource<D> {
+    fn read_at(&mut self, offset: u64, buffer: &mut [u8]) -> Result<usize, BootError> {
+        let block_count = self.device.block_count().unwrap_or(0);
+        let mut block = [0u8; BLOCK_SIZE];
+        let mut done = 0;
+        while done < buffer.len() {
+            let position = offset + done as u64;
+            let lba = position / BLOCK_SIZE as u64;
+            if lba >= block_count {
+                break;
+            }
+            let in_block = (position % BLOCK_SIZE as u64) as usize;
+            let n = core::cmp::min(BLOCK_SIZE - in_block, buffer.len() - done);
+            self.device.read_block(lba, &mut block)?;
+            buffer[done..done + n].copy_from_slice(&block[in_block..in_block + n]);
+            done += n;
+        }
+        Ok(done)
+    }
+}
+
+/// Bir aygıttaki aktif (bootable) işaretli ilk bölümün indeksini döndürür.
+/// # Safety
+/// Global depolama aygıtlarına erişir.
+pub unsafe fn find_bootable_partition(device_name: &str) -> Result<usize, BootError> {
+    let device = crate::storage::device_by_name(device_name)?;
+    let table = partition::read_partition_table(device)?;
+    let info = table.first_bootable().ok_or(PartitionError::NoBootablePartition)?;
+    Ok(info.index)
+}
+
+/// Boot kaynağını çözer: `<cihaz>` tek başına verilirse o aygıttaki aktif bölüm, aksi halde
+/// `<cihaz>:<bölüm>[/<yol>]` olarak ayrıştırılır.
+/// # Safety
+/// Global depolama aygıtlarına erişir.
+pub unsafe fn resolve_boot_source(spec: &str) -> Result<BootPath<'_>, BootError> {
+    if spec.contains(':') {
+        return BootPath::parse(spec);
+    }
+    if spec.is_empty() {
+        return Err(BootError::InvalidBootPath);
+    }
+    let partition = find_bootable_partition(spec)?;
+    Ok(BootPath { device: spec, partition, path: "" })
+}
+
+/// RAM'e yüklenmiş ve doğrulanmış imaj.
+#[derive(Debug, Copy, Clone)]
+pub struct LoadedImage {
+    pub header: ImageHeader,
+}
+
+impl LoadedImage {
+    pub fn entry_address(&self) -> usize {
+        self.header.entry_address()
+    }
+}
+
+/// Kaynaktaki imajın başlığını doğrular, yükünü başlıktaki adrese kopyalar, yük CRC'sini ve
+/// güvenli boot politikasına göre imzasını kontrol eder. Kabul edilen imaj (başlık + yük) `stage`
+/// aşaması olarak ölçüm kaydına eklenir.
+/// Başlık, yükleme aralığı RAM dışındaysa veya firmware bölgesiyle çakışıyorsa kopyalamadan önce reddedilir.
+/// # Safety
+/// Başlıkta belirtilen bellek bölgesine doğrudan yazar.
+pub unsafe fn load_image<S: ImageSource + ?Sized>(source: &mut S, stage: BootStage, description: core::fmt::Arguments<'_>) -> Result<LoadedImage, BootError> {
+    let mut header_bytes = [0u8; IMAGE_HEADER_MAX_SIZE];
+    let n = source.read_at(0, &mut header_bytes)?;
+    let header = ImageHeader::parse(&header_bytes[..n])?;
+    if header.is_firmware() {
+        return Err(ImageError::FirmwareImage.into()); // Çalışan firmware'in üzerine yazılmamalı
+    }
+
+    let size = header.payload_size();
+    let dest_ptr = crate::memory::phys_to_mut_ptr(header.load_address(), size)?;
+    let dest = core::slice::from_raw_parts_mut(dest_ptr, size);
+    if source.read_at(header.header_size as u64, dest)? != size {
+        return Err(ImageError::Truncated.into());
+    }
+
+    let mut crc = Crc32::new();
+    crc.update(dest);
+    if crc.finish() != header.payload_crc32 {
+        return Err(ImageError::PayloadCrcMismatch.into());
+    }
+    let header_bytes = &header_bytes[..header.header_size as usize];
+    let verified = secureboot::check(&header, header_bytes, dest)?;
+    let flags = if verified { EVENT_FLAG_SIGNATURE_VERIFIED } else { 0 };
+    measured::measure(stage, flags, header.load_address, &[header_bytes, dest], description);
+    Ok(LoadedImage { header })
+}
+
+/// Boot yolundaki imajı yükler: yol bir dosya gösteriyorsa FAT üzerinden, yoksa bölümün başından okur.
+/// # Safety
+/// Global depolama aygıtlarına erişir ve başlıktaki bellek bölgesine yazar.
+pub unsafe fn load_from_path(boot_path: &BootPath) -> Result<LoadedImage, BootError> {
+    let device = crate::storage::device_by_name(boot_path.device)?;
+    let view: PartitionView<'static, dyn BlockDevice> = partition::open_partition(device, boot_path.partition)?;
+    if boot_path.is_file() {
+        let mut volume = FatVolume::mount(view)?;
+        let file = volume.open(boot_path.path)?;
+        load_image(&mut FileSource { volume, file }, BootStage::Kernel,
+            format_args!("{}:{}{}", boot_path.device, boot_path.partition, boot_path.path))
+    } else {
+        load_image(&mut BlockSource { device: view }, BootStage::Bootloader,
+            format_args!("{}:{}", boot_path.device, boot_path.partition))
+    }
+}
+
+/// Seri hattan alınan ham ikiliyi `address`'ten itibaren RAM'e yükler (U-Boot `loady`/`loadx` gibi).
+/// İkili PacketBox başlığı taşımaz: giriş noktası `address`'tir ve imza doğrulanamaz. Ölçüm kaydına
+/// imzasız `kernel` aşaması olarak eklenir; çalıştırılması güvenli boot politikasına tabidir (`run_serial_image`).
+/// # Safety
+/// UART'a erişir ve `address`'ten LPDDR1'in sonuna kadar olan bölgeye yazar.
+pub unsafe fn load_serial(uart: &mut Uart0, protocol: Protocol, address: usize) -> Result<LoadedImage, BootError> {
+    let capacity = memory::LPDDR1_END_ADDRESS.saturating_sub(address);
+    memory::validate_load_range(address, capacity).map_err(ImageError::LoadRange)?;
+    let dest = core::slice::from_raw_parts_mut(memory::phys_to_mut_ptr(address, capacity)?, capacity);
+    let transfer = xmodem::receive(uart, protocol, dest)?;
+    if transfer.size == 0 {
+        return Err(ImageError::EmptyPayload.into());
+    }
+
+    let payload = &dest[..transfer.size];
+    let mut crc = Crc32::new();
+    crc.update(payload);
+    measured::measure(BootStage::Kernel, 0, address as u64, &[payload], format_args!("{} {:#x} {}", protocol.name(), address, transfer.name()));
+    // Başlıksız ikili için eşdeğer başlık: handoff tablosu ve bellek haritası bunu kullanır.
+    let header = ImageHeader {
+        version: IMAGE_VERSION,
+        header_size: 0,
+        flags: 0,
+        payload_size: transfer.size as u32,
+        payload_crc32: crc.finish(),
+        load_address: address as u64,
+        entry_offset: 0,
+        target_hart: 0,
+        signature: None,
+    };
+    Ok(LoadedImage { header })
+}
+
+/// `load_serial` ile yüklenen ikiliyi `boot` ile aynı handoff ile başlatır. Başarılıysa geri dönmez.
+/// İkili imzasız olduğu için `enforce` politikasında reddedilir.
+/// # Safety
+/// İkili `load_serial` ile yüklenmiş olmalıdır; kontrol ona devredilir.
+pub unsafe fn run_serial_image(image: &LoadedImage, command_line: &str) -> BootError {
+    if let Err(e) = secureboot::check_unsigned() {
+        return e.into();
+    }
+    jump_to_image(image, &BootPath { device: "uart", partition: 0, path: "" }, command_line)
+}
+
+/// Host simülasyonunda saat/güç birimi yoktur, erken başlatma her zaman başarılıdır.
+/// # Safety
+/// Simülasyon dışında saat ve güç yazmaçlarına yazılacağı için "unsafe"dir.
+#[cfg(feature = "hosted")]
+pub unsafe fn perform_early_hardware_init() -> Result<(), BootError> {
+    Ok(())
+}
+
+/// Sistemdeki en erken donanım başlatma adımlarını gerçekleştirir.
+/// Bu, genellikle saat kaynaklarını ve temel güç ayarlarını yapılandırmayı içerir.
+/// # Safety
+/// Donanım yazmaçlarına doğrudan erişim gerektirir, "unsafe"dir.
+#[cfg(not(feature = "hosted"))]
+pub unsafe fn perform_early_hardware_init() -> Result<(), BootError> {
+    perform_early_hardware_init_with(&mut Mmio)
+}
+
+/// Erken donanım başlatmayı verilen yazmaç arka ucu üzerinden gerçekleştirir.
+/// # Safety
+/// Gerçek donanım arka ucu ile yazmaçlara doğrudan erişir, "unsafe"dir.
+pub unsafe fn perform_early_hardware_init_with<B: RegisterBus>(bus: &mut B) -> Result<(), BootError> {
+    // --- GERÇEK BAŞLATMA KODU BURAYA GELECEK ---
+    // SiFive S21'in Clock Generator ve Power Management birimlerini yapılandırın.
+
+    // Örnek placeholder: Sistem Saat Kaynağını ve PLL'leri Yapılandırma
+    // İşlemcinin ve çevre birimlerinin doğru frekansta çalışması için kritik.
+    // Buradaki adresler ve değerler tamamen varsayımsaldir!
+     const CLOCK_GEN_BASE: usize = 0xAAAA_0000; // SiFive S21 Clock Generator Base Adresi - VERİ SAYFASINDAN BULUN!
+     const CLOCK_PLL_CFG: usize = 0x04; // PLL Yapılandırma Register Ofseti - VERİ SAYFASINDAN BULUN!
+     const CLOCK_ENABLE_REG: usize = 0x08; // Saat Etkinleştirme Register Ofseti - VERİ SAYFASINDAN BULUN!
+
+    // // Örnek: PLL'i yapılandır (Değerler frekans hesabına göre değişir)
+     bus.write32(CLOCK_GEN_BASE + CLOCK_PLL_CFG, 0x12345678); // Varsayımsal PLL yapılandırma değeri
+
+    // // Örnek: Ana saatleri etkinleştir
+     bus.write32(CLOCK_GEN_BASE + CLOCK_ENABLE_REG, 0xFF); // Varsayımsal etkinleştirme değeri
+
+    // Örnek placeholder: Temel Güç Yönetimi Ayarları
+    // Gerekirse voltaj regülatörlerini ayarlama veya düşük güç modlarını yapılandırma.
+    // const POWER_MGMT_BASE: usize = 0xAAAB_0000; // SiFive S21 Power Management Base Adresi - VERİ SAYFASINDAN BULUN!
+    // ... register erişimleri ...
+    // Başlatma sırasında hata oluşursa (donanım register'larından okunabilir):
+    // return Err(BootError::EarlyHardwareInitError);
+
+    // Başlatma başarılı olursa
+    Ok(())
+}
+
+/// Bellek sistemini başlatır (LPDDR1).
+/// # Safety
+/// Bellek kontrolcüsüne erişim içerebilir, bu nedenle "unsafe"dir.
+pub unsafe fn initialize_memory() -> Result<(), BootError> {
+    // --- GERÇEK BAŞLATMA KODU BURAYA GELECEK ---
+    // `memory.rs` dosyasındaki LPDDR1 başlatma fonksiyonunu çağır.
+    // Bu fonksiyonun içi artık gerçek başlatma adımlarını içermelidir.
+    crate::memory::init_memory().map_err(BootError::MemoryInitError)
+}
+
+/// Temel çevre birimlerini başlatır (UART gibi).
+/// # Safety
+/// Çevre birimi donanımına erişim içerebilir, bu nedenle "unsafe"dir.
+pub unsafe fn initialize_peripherals() -> Result<(), BootError> {
+    // --- GERÇEK BAŞLATMA KODU BURAYA GELECEK ---
+    // UART0 gibi temel çevre birimlerini başlat.
+    // UART başlatma fonksiyonu artık gerçek register ayarlamalarını içermelidir.
+    // UART'ın init fonksiyonu Result döndürmediği için başarı varsayılıyor:
+    crate::uart::UART0_GLOBAL.init();
+    // Eğer UART init Result döndürseydi:
+    // crate::uart::UART0_GLOBAL.init().map_err(|_| BootError::PeripheralInitError)?;
+
+    // UART kesme modunda çalışır (RX/TX halka tamponları, bkz. uart.rs).
+    crate::uart::UART0_GLOBAL.enable_interrupts();
+    #[cfg(not(feature = "hosted"))]
+    crate::plic::init();
+
+    // Diğer temel çevre birimleri (örneğin, basit bir Timer veya Watchdog Timer)
+    // ... timer_module::init()?; ...
+
+
+    // Başarılı sayalım
+    Ok(())
+}
+
+/// Device tree'yi üretir, handoff tablosunu hazırlar ve kontrolü yüklenmiş imajın başlığındaki
+/// giriş noktasına devreder. Geri dönmez. Device tree üretilemezse imaj yine de başlatılır (a2 = 0).
+/// # Safety
+/// İmaj `load_image` ile yüklenmiş ve doğrulanmış olmalıdır.
+pub unsafe fn jump_to_image(image: &LoadedImage, boot_path: &BootPath, command_line: &str) -> ! {
+    // Komut satırı çekirdeğin davranışını belirler; handoff tablosu ölçüm kaydını göstermeden önce ölçülür.
+    measured::measure(BootStage::Config, 0, 0, &[command_line.as_bytes()], format_args!("command line"));
+    let hart_id = crate::handoff::current_hart_id();
+    let blob = &mut crate::fdt::FDT_BLOB.0;
+    let fdt = match crate::fdt::build_device_tree(blob, command_line, hart_id as u32) {
+        Ok(size) => Some((blob.as_ptr() as usize, size)),
+        Err(e) => {
+            log!("Warning: device tree generation failed: {:?}", e);
+            None
+        }
+    };
+    let handoff_address = crate::handoff::prepare(image, boot_path, command_line, fdt);
+    // İşletim sistemi firmware'in kesme yapılandırmasını devralmamalı; bekleyen çıktı yoklamayla gönderilir.
+    crate::uart::UART0_GLOBAL.disable_interrupts();
+    #[cfg(not(feature = "hosted"))]
+    crate::plic::shutdown();
+    jump_to_entry(image.entry_address(), hart_id, handoff_address, fdt.map(|(address, _)| address).unwrap_or(0))
+}
+
+/// Kontrolü belleğe yüklenmiş imajın giriş noktasına devreder. Geri dönmez.
+/// RISC-V çağrı kuralına göre a0 = hart id, a1 = handoff tablosunun adresi, a2 = device tree adresi.
+/// # Safety
+/// `entry_address` geçerli, çalıştırılabilir bir kodu göstermelidir.
+#[cfg(not(feature = "hosted"))]
+pub unsafe fn jump_to_entry(entry_address: usize, hart_id: usize, handoff_address: usize, fdt_address: usize) -> ! {
+    let entry: extern "C" fn(usize, usize, usize) -> ! = core::mem::transmute(entry_address);
+    entry(hart_id, handoff_address, fdt_address)
+}
+
+/// Host simülasyonu: yüklenen kod çalıştırılamaz, atlama adresi ve argümanlar raporlanır ve simülasyon sonlanır.
+/// Entegrasyon testleri bu çıktıyı "boot başarılı" işareti olarak kullanabilir.
+/// # Safety
+/// Simülasyon dışında kontrol `entry_address`'e devredilir; orada yüklenmiş ve doğrulanmış bir imaj olmalıdır.
+#[cfg(feature = "hosted")]
+pub unsafe fn jump_to_entry(entry_address: usize, hart_id: usize, handoff_address: usize, fdt_address: usize) -> ! {
+    log!("[hosted] Jumping to entry point {:#x} (a0={}, a1={:#x}, a2={:#x}), simulation ends here.", entry_address, hart_id, handoff_address, fdt_address);
+    crate::hosted::exit(0)
+}
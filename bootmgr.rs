@@ -0,0 +1,182 @@
+#![no_std]
+
+// Boot yöneticisi: boot hedeflerini sırayla dener.
+// Hedef listesi `bootdevice` değişkeni ve ardından `bootorder` listesindeki girdilerden oluşur
+// (tekrarlar atlanır). Her hedef `boot::resolve_boot_source` biçimindedir: "sd", "emic:2",
+// "sd:1/sahnebox/kernel.bin". Her hedef imaj başlığı, CRC ve güvenli boot doğrulamasından geçer;
+// başarısız olan hedefin nedeni loglanır ve bir sonrakine geçilir. Hepsi başarısız olursa çağırana
+// (CLI'ya) dönülür.
+//
+// `slot.enabled` açıksa önce A/B slotları denenir (bkz. slots.rs).
+//
+// Açılışta `autoboot` `bootdelay` saniye geri sayar; bu sürede bir tuşa basılırsa CLI'ya geçilir.
+// Geri sayım kesilmezse önce `bootscript` betiği çalıştırılır (bkz. script.rs).
+
+use core::fmt::Write;
+
+use crate::boot::{self, BootError};
+use crate::timer::Deadline;
+use crate::uart::Uart0;
+
+pub const MAX_BOOT_TARGETS: usize = 8;
+pub const BOOT_ORDER_SEPARATOR: char = ',';
+
+/// Hedef listesini oluşturur ve hedef sayısını döndürür.
+/// # Safety
+/// Global NVRAM'i okur.
+pub unsafe fn boot_targets(targets: &mut [&'static str; MAX_BOOT_TARGETS]) -> usize {
+    collect_targets(crate::env::get("bootdevice"), crate::env::get("bootorder").unwrap_or(""), targets)
+}
+
+/// `primary` ve ardından `order` girdilerini boşlukları kırpıp boşları ve tekrarları atlayarak toplar;
+/// en fazla MAX_BOOT_TARGETS hedef alınır.
+pub fn collect_targets<'a>(primary: Option<&'a str>, order: &'a str, targets: &mut [&'a str; MAX_BOOT_TARGETS]) -> usize {
+    let mut count = 0;
+    let order = order.split(BOOT_ORDER_SEPARATOR);
+    for target in primary.into_iter().chain(order).map(str::trim).filter(|t| !t.is_empty()) {
+        if count == MAX_BOOT_TARGETS {
+            break;
+        }
+        if !targets[..count].contains(&target) {
+            targets[count] = target;
+            count += 1;
+        }
+    }
+    count
+}
+
+/// `bootorder` değeri için doğrulama: virgülle ayrılmış, her biri geçerli boot kaynağı olan en fazla
+/// MAX_BOOT_TARGETS girdi.
+pub fn is_valid_boot_order(value: &str) -> bool {
+    let mut count = 0;
+    for target in value.split(BOOT_ORDER_SEPARATOR).map(str::trim).filter(|t| !t.is_empty()) {
+        if !crate::env::is_valid_boot_source(target) {
+            return false;
+        }
+        count += 1;
+    }
+    count <= MAX_BOOT_TARGETS
+}
+
+/// Tek bir hedefi dener. Başarılıysa geri dönmez.
+/// `command_line` None ise `bootargs` değişkeni kullanılır.
+/// # Safety
+/// Depolama aygıtlarına erişir, RAM'e imaj yükler ve başarılıysa kontrolü imaja devreder.
+pub unsafe fn try_target(target: &str, command_line: Option<&str>) -> BootError {
+    let boot_path = match boot::resolve_boot_source(target) {
+        Ok(p) => p,
+        Err(e) => return e,
+    };
+    log!("Loading image from {}:{}{}...", boot_path.device, boot_path.partition, boot_path.path);
+    match boot::load_from_path(&boot_path) {
+        Ok(image) => {
+            log!("Loaded {} bytes to {:#x}, entry {:#x}.", image.header.payload_size, image.header.load_address, image.entry_address());
+            let command_line = command_line.unwrap_or_else(|| crate::env::get("bootargs").unwrap_or(""));
+            boot::jump_to_image(&image, &boot_path, command_line) // Buradan sonra kontrol yüklenen imaja gecer
+        }
+        Err(e) => e,
+    }
+}
+
+/// Hedefleri sırayla dener. Bir hedef boot ederse geri dönmez; hepsi başarısız olursa son hatayı döndürür.
+/// # Safety
+/// Depolama aygıtlarına erişir, RAM'e imaj yükler ve başarılıysa kontrolü imaja devreder.
+pub unsafe fn run_boot_sequence(command_line: Option<&str>) -> BootError {
+    let mut targets = [""; MAX_BOOT_TARGETS];
+    let count = boot_targets(&mut targets);
+    let mut last_error = BootError::InvalidBootPath;
+    if crate::slots::enabled() {
+        last_error = crate::slots::boot_slots(command_line);
+    }
+    for (i, target) in targets[..count].iter().enumerate() {
+        log!("Boot target {}/{}: {}", i + 1, count, target);
+        let e = try_target(target, command_line);
+        log!("Boot target '{}' failed: {:?}", target, e);
+        last_error = e;
+    }
+    log!("All boot targets failed.");
+    last_error
+}
+
+/// Açılıştaki otomatik boot: `bootdelay` saniye geri sayar, bu sürede UART'tan bir bayt gelirse durur.
+/// Süre dolarsa açılış betiğini ve boot sırasını çalıştırır. Geri dönerse CLI'ya geçilmelidir
+/// (boot iptal edildi, kapalı veya tüm hedefler başarısız).
+/// # Safety
+/// UART'a erişir; boot başarılı olursa kontrolü imaja devreder.
+pub unsafe fn autoboot(uart: &mut Uart0) {
+    let delay = crate::env::get_int("bootdelay");
+    if delay < 0 {
+        log!("Autoboot disabled (bootdelay={}).", delay);
+        return;
+    }
+
+    let mut remaining = delay;
+    loop {
+        let _ = write!(uart, "\rBooting in {} seconds, press any key to stop ", remaining);
+        let second = Deadline::after_ms(if remaining > 0 { 1000 } else { 0 });
+        loop {
+            if uart.read_byte().is_some() {
+                let _ = write!(uart, "\r\nAutoboot interrupted.\r\n");
+                return;
+            }
+            if second.expired() {
+                break;
+            }
+            #[cfg(feature = "hosted")]
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        if remaining == 0 {
+            break;
+        }
+        remaining -= 1;
+    }
+    let _ = write!(uart, "\r\n");
+
+    crate::script::run_boot_script(uart);
+    run_boot_sequence(None);
+    log!("Autoboot failed, entering CLI.");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn targets<'a>(primary: Option<&'a str>, order: &'a str) -> Vec<&'a str> {
+        let mut targets = [""; MAX_BOOT_TARGETS];
+        let count = collect_targets(primary, order, &mut targets);
+        targets[..count].to_vec()
+    }
+
+    #[test]
+    fn bootdevice_comes_first_and_duplicates_are_skipped() {
+        assert_eq!(targets(Some("sd"), "emic,sd,emic:2,emic"), ["sd", "emic", "emic:2"]);
+        assert_eq!(targets(None, "emic:1"), ["emic:1"]);
+    }
+
+    #[test]
+    fn whitespace_and_empty_entries_are_ignored() {
+        assert_eq!(targets(Some(" "), " sd ,, ,emic:1 ,"), ["sd", "emic:1"]);
+        assert_eq!(targets(Some("emic"), " emic "), ["emic"]);
+        assert!(targets(Some(""), "").is_empty());
+    }
+
+    #[test]
+    fn targets_are_cut_off_at_the_maximum() {
+        let order = "sd:1,sd:2,sd:3,sd:4,sd:5,sd:6,sd:7,sd:8";
+        let found = targets(Some("emic"), order);
+        assert_eq!(found.len(), MAX_BOOT_TARGETS);
+        assert_eq!(found[0], "emic");
+        assert_eq!(found[MAX_BOOT_TARGETS - 1], "sd:7");
+    }
+
+    #[test]
+    fn boot_order_validation() {
+        assert!(is_valid_boot_order(""));
+        assert!(is_valid_boot_order(" sd , emic:2 ,, sd:1/boot/kernel.bin "));
+        assert!(is_valid_boot_order("sd:1,sd:2,sd:3,sd:4,sd:5,sd:6,sd:7,sd:8"));
+        assert!(!is_valid_boot_order("sd:1,sd:2,sd:3,sd:4,sd:5,sd:6,sd:7,sd:8,sd:9"));
+        // Tek bir geçersiz girdi bütün listeyi reddeder.
+        assert!(!is_valid_boot_order("sd,floppy,emic"));
+        assert!(!is_valid_boot_order("emic:x"));
+    }
+}
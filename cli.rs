@@ -1,332 +1,1707 @@
-#![no_std]
-
-use core::fmt::Write;
-use crate::uart::Uart0; // UART0_GLOBAL'i kullanmak için
-
-// Diğer modüllerdeki global statiklere erişim için use bildirimleri
-use crate::storage::{EMIC_STORAGE_GLOBAL, SD_CARD_STORAGE_GLOBAL, BlockDevice}; // Global storage
-use crate::memory::{LPDDR1_SIZE_BYTES /*, ALLOCATOR */}; // Global memory bilgisi/allocator
-use crate::psu::PSU_MONITOR_GLOBAL; // Global PSU
-use crate::refrigerator::FRIDGE_CONTROLLER_GLOBAL; // Global Buzdolabi
-
-// Maximum size of the input buffer for a single command line.
-const INPUT_BUFFER_SIZE: usize = 128;
-// Maximum number of arguments a command can have.
-const MAX_ARGS: usize = 10;
-// The prompt string displayed by the CLI.
-const PROMPT: &str = "> ";
-
-// Errors specific to the CLI module.
-#[derive(Debug)]
-pub enum CliError {
-    BufferFull,
-    UnknownCommand,
-    TooManyArguments,
-    InvalidDataFormat, // UTF-8 decode hatasi gibi
-    CommandFailed,
-    UartWriteError,
-    // Diğer modullerden sarilmis hatalar (istege bagli, firmware_common::Error zaten var)
-     Storage(crate::storage::StorageError),
-    // ...
-}
-
-// Represents a registered CLI command.
-struct Command {
-    name: &'static str,
-    help: &'static str,
-    // Komut fonksiyonu: UART yazıcısını ve argüman dilimini alır.
-    execute: fn(&mut Uart0, &[&str]) -> Result<(), CliError>,
-}
-
-// The main CLI state structure.
-pub struct Cli {
-    buffer: [u8; INPUT_BUFFER_SIZE],
-    index: usize,
-}
-
-impl Cli {
-    pub const fn new() -> Self {
-        Cli { buffer: [0u8; INPUT_BUFFER_SIZE], index: 0 }
-    }
-
-    pub fn init(&mut self) {
-        self.index = 0;
-    }
-
-    /// Gelen baytı işler.
-    /// # Safety
-    /// Global UART'a yazma gerektirir.
-    pub unsafe fn process_byte(&mut self, byte: u8, uart: &mut Uart0) -> Result<(), CliError> {
-        match byte {
-            0x08 | 0x7F => { // Backspace veya Delete
-                if self.index > 0 {
-                    self.index -= 1;
-                    uart.write_str("\x08 \x08").map_err(|_| CliError::UartWriteError)?;
-                }
-            }
-            0x0D => { // Carriage Return
-                uart.write_str("\r\n").map_err(|_| CliError::UartWriteError)?;
-                self.parse_and_execute(uart)?;
-                self.index = 0;
-                self.print_prompt(uart)?;
-            }
-            0x0A => { // Newline
-                // CR'den sonra geldiyse yoksay veya CR'den sonra gelmediyse işle
-                 if self.index > 0 && self.buffer[self.index - 1] != 0x0D {
-                     uart.write_str("\r\n").map_err(|_| CliError::UartWriteError)?;
-                     self.parse_and_execute(uart)?;
-                     self.index = 0;
-                     self.print_prompt(uart)?;
-                 } else if self.index == 0 {
-                     self.print_prompt(uart)?;
-                 }
-            }
-            _ if byte < 0x20 => { /* Kontrol karakterlerini yoksay */ }
-            _ => { // Yazdırılabilir karakter
-                if self.index < INPUT_BUFFER_SIZE {
-                    self.buffer[self.index] = byte;
-                    self.index += 1;
-                    uart.write_str(core::str::from_utf8(core::slice::from_ref(&byte)).map_err(|_| CliError::InvalidDataFormat)?).map_err(|_| CliError::UartWriteError)?;
-                } else {
-                    uart.write_str("\x07").map_err(|_| CliError::UartWriteError)?; // BEL
-                    return Err(CliError::BufferFull);
-                }
-            }
-        }
-        Ok(())
-    }
-
-    /// Arabellekteki komutu ayrıştırıp çalıştırır.
-    /// # Safety
-    /// Komut fonksiyonları global statiklere erişebilir. UART'a yazma gerektirir.
-    unsafe fn parse_and_execute(&mut self, uart: &mut Uart0) -> Result<(), CliError> {
-        if self.index == 0 { return Ok(()); }
-
-        let line_bytes = &self.buffer[..self.index];
-        let line_str = core::str::from_utf8(line_bytes).map_err(|_| CliError::InvalidDataFormat)?;
-        let line_str = line_str.trim();
-
-        if line_str.is_empty() { return Ok(()); }
-
-        let mut parts = line_str.split_whitespace();
-        let command_name = parts.next().unwrap();
-        let mut args: [&str; MAX_ARGS] = [""; MAX_ARGS];
-        let mut arg_count = 0;
-        for arg in parts {
-            if arg_count < MAX_ARGS { args[arg_count] = arg; arg_count += 1; }
-            else { writeln!(uart, "Error: Too many arguments.").map_err(|_| CliError::UartWriteError)?; return Err(CliError::TooManyArguments); }
-        }
-        let args_slice = &args[..arg_count];
-
-        let commands = get_commands();
-        for command in commands {
-            if command.name == command_name {
-                return (command.execute)(uart, args_slice); // Komutu çalıştır
-            }
-        }
-
-        writeln!(uart, "Error: Unknown command '{}'. Type 'help'.", command_name).map_err(|_| CliError::UartWriteError)?;
-        Err(CliError::UnknownCommand)
-    }
-
-    /// Prompt yazdırır.
-    /// # Safety
-    /// Global UART'a yazma gerektirir.
-    unsafe fn print_prompt(&self, uart: &mut Uart0) -> Result<(), CliError> {
-        uart.write_str(PROMPT).map_err(|_| CliError::UartWriteError)
-    }
-}
-
-// --- Komut Implementasyonları ---
-
-fn get_commands() -> &'static [Command] {
-    &[
-        Command { name: "help", help: "Show help.", execute: help_command },
-        Command { name: "status", help: "Show system status.", execute: status_command },
-        Command { name: "storage", help: "Interact with storage (read/write/info). Usage: storage info <emic|sd>", execute: storage_command },
-        Command { name: "boot", help: "Attempt to boot from a device.", execute: boot_command },
-        // Diğer komutlar buraya eklenecek
-         Command { name: "fridge", help: "Control refrigerator.", execute: fridge_command },
-         Command { name: "psu", help: "PSU status.", execute: psu_command },
-    ]
-}
-
-// help komutu (Zaten hazırdı)
-unsafe fn help_command(uart: &mut Uart0, args: &[&str]) -> Result<(), CliError> {
-     let commands = get_commands();
-    if args.is_empty() {
-        writeln!(uart, "Available commands:").map_err(|_| CliError::UartWriteError)?;
-        for cmd in commands {
-            writeln!(uart, "  {}: {}", cmd.name, cmd.help).map_err(|_| CliError::UartWriteError)?;
-        }
-    } else {
-        let target_command = args[0];
-        let mut found = false;
-        for cmd in commands {
-            if cmd.name == target_command {
-                writeln!(uart, "{}: {}", cmd.name, cmd.help).map_err(|_| CliError::UartWriteError)?;
-                found = true;
-                break;
-            }
-        }
-        if !found {
-             writeln!(uart, "Error: Command '{}' not found.", target_command).map_err(|_| CliError::UartWriteError)?;
-        }
-    }
-    Ok(())
-}
-
-// status komutu (Global statiklere erişim eklendi)
-unsafe fn status_command(uart: &mut Uart0, _args: &[&str]) -> Result<(), CliError> {
-    writeln!(uart, "PacketBox System Status:").map_err(|_| CliError::UartWriteError)?;
-    writeln!(uart, "  Firmware: PacketBox v{} ({})", "1.0", "BuildDate").map_err(|_| CliError::UartWriteError)?; // Versiyon/BuildDate sabitleri eklenebilir
-    writeln!(uart, "  State: {:?}", firmware_common::get_system_state()).map_err(|_| CliError::UartWriteError)?;
-    writeln!(uart, "  Memory: {} Bytes LPDDR1", LPDDR1_SIZE_BYTES).map_err(|_| CliError::UartWriteError)?;
-
-    // Global Storage durumunu raporla
-    writeln!(uart, "  Storage:").map_err(|_| CliError::UartWriteError)?;
-    let emic_status = if EMIC_STORAGE_GLOBAL.is_some() { "Initialized" } else { "Not Initialized" };
-    let emic_blocks = if let Some(emic) = &mut EMIC_STORAGE_GLOBAL { emic.block_count().unwrap_or(0) } else { 0 }; // unsafe erişim
-    writeln!(uart, "    eMMC 1.0: {} ({} blocks)", emic_status, emic_blocks).map_err(|_| CliError::UartWriteError)?;
-
-    let sd_status = if SD_CARD_STORAGE_GLOBAL.is_some() { "Initialized" } else { "Not Initialized" };
-    let sd_blocks = if let Some(sd) = &mut SD_CARD_STORAGE_GLOBAL { sd.block_count().unwrap_or(0) } else { 0 }; // unsafe erişim
-    writeln!(uart, "    SD Card: {} ({} blocks)", sd_status, sd_blocks).map_err(|_| CliError::UartWriteError)?;
-
-    // Global PSU durumunu raporla
-    writeln!(uart, "  PSU:").map_err(|_| CliError::UartWriteError)?;
-    let psu_status = if PSU_MONITOR_GLOBAL.is_some() { "Initialized" } else { "Not Initialized" };
-    // Eğer is_power_good fonksiyonu varsa kullanılabilir:
-     let power_good = if let Some(psu) = &mut PSU_MONITOR_GLOBAL { psu.is_power_good().unwrap_or(false) } else { false };
-     writeln!(uart, "    Monitor: {} (Power Good: {})", psu_status, power_good).map_err(|_| CliError::UartWriteError)?;
-    writeln!(uart, "    Monitor: {}", psu_status).map_err(|_| CliError::UartWriteError)?;
-
-
-    // Global Refrigerator durumunu raporla
-    writeln!(uart, "  Refrigerator:").map_err(|_| CliError::UartWriteError)?;
-    let fridge_status = if FRIDGE_CONTROLLER_GLOBAL.is_some() { "Initialized" } else { "Not Initialized" };
-    writeln!(uart, "    Controller: {}", fridge_status).map_err(|_| CliError::UartWriteError)?;
-
-
-    Ok(())
-}
-
-// storage komutu (Sadece info kısmı implement edildi)
-unsafe fn storage_command(uart: &mut Uart0, args: &[&str]) -> Result<(), CliError> {
-    if args.len() < 2 || args[0] != "info" {
-        writeln!(uart, "Usage: storage info <emic|sd>").map_err(|_| CliError::UartWriteError)?;
-        // read/write implementasyonlari buraya eklenecek
-         writeln!(uart, "Usage: storage <emic|sd> <read|write> <lba> [data_byte]").map_err(|_| CliError::UartWriteError)?;
-        return Ok(());
-    }
-
-    let device_type = args[1];
-
-    match device_type {
-        "emic" => {
-            writeln!(uart, "eMMC 1.0 Info:").map_err(|_| CliError::UartWriteError)?;
-            let emic_status = if EMIC_STORAGE_GLOBAL.is_some() { "Initialized" } else { "Not Initialized" };
-            writeln!(uart, "  Status: {}", emic_status).map_err(|_| CliError::UartWriteError)?;
-             if let Some(emic) = &mut EMIC_STORAGE_GLOBAL { // unsafe erişim
-                 let blocks = emic.block_count().unwrap_or(0);
-                 let bytes = blocks * BLOCK_SIZE as u64;
-                 writeln!(uart, "  Total Blocks: {}", blocks).map_err(|_| CliError::UartWriteError)?;
-                 writeln!(uart, "  Capacity: {} Bytes (approx {} MB)", bytes, bytes / 1024 / 1024).map_err(|_| CliError::UartWriteError)?;
-             }
-        },
-        "sd" => {
-             writeln!(uart, "SD Card Info:").map_err(|_| CliError::UartWriteError)?;
-             let sd_status = if SD_CARD_STORAGE_GLOBAL.is_some() { "Initialized" } else { "Not Initialized" };
-             writeln!(uart, "  Status: {}", sd_status).map_err(|_| CliError::UartWriteError)?;
-             if let Some(sd) = &mut SD_CARD_STORAGE_GLOBAL { // unsafe erişim
-                 let blocks = sd.block_count().unwrap_or(0);
-                 let bytes = blocks * BLOCK_SIZE as u64;
-                 writeln!(uart, "  Total Blocks: {}", blocks).map_err(|_| CliError::UartWriteError)?;
-                 // 2.2TB limitini belirtmek faydali olabilir
-                 writeln!(uart, "  Capacity: {} Bytes (approx {} MB / {} GB / {} TB - capped by MBR at ~2.2TB)", bytes, bytes / 1024 / 1024, bytes / 1024 / 1024 / 1024, bytes / 1024 / 1024 / 1024 / 1024).map_err(|_| CliError::UartWriteError)?;
-             }
-        },
-        _ => {
-            writeln!(uart, "Error: Unknown device type '{}'. Use 'emic' or 'sd'.", device_type).map_err(|_| CliError::UartWriteError)?;
-        }
-    }
-
-    Ok(())
-}
-
-
-// boot komutu (Placeholder)
-unsafe fn boot_command(uart: &mut Uart0, args: &[&str]) -> Result<(), CliError> {
-    writeln!(uart, "Attempting to boot... (Placeholder)").map_err(|_| CliError::UartWriteError)?;
-
-    // Burada boot sirasini belirleme (SD karttan mi, eMMC'den mi?)
-    // Partition tablosunu okuma (MBR)
-    // Bootable partition'i bulma
-    // Bootloader'i bellege yukleme
-    // Bootloader'a atlama
-
-    if args.len() > 0 {
-        writeln!(uart, "Ignoring arguments: {:?}", args).map_err(|_| CliError::UartWriteError)?;
-    }
-
-    // Örnek: SD karttan boot etmeye çalışma mantığı
-    
-    unsafe {
-        if let Some(sd_card) = &mut SD_CARD_STORAGE_GLOBAL {
-            log!("Checking SD card for bootloader...");
-            // SD kartin ilk sektorunu oku (MBR)
-            let mut mbr_buffer = [0u8; BLOCK_SIZE];
-            match sd_card.read_block(0, &mut mbr_buffer) {
-                Ok(_) => {
-                    // MBR'yi parse etme logic'i (zor kisim!)
-                    log!("MBR read OK. Parsing MBR...");
-                    // Bootable partisyonu bul ve baslangic LBA'sini al
-                    // Varsayalim ki bootloader 100. LBA'da
-                    let bootloader_lba = 100;
-                    let bootloader_size_blocks = 16; // Varsayim: 16 blok bootloader
-                    let bootloader_dest_address = 0x9000_0000; // Varsayim: RAM'de yuklenecek yer
-
-                    log!("Loading bootloader from LBA {}...", bootloader_lba);
-                    // Bootloader bloklarini RAM'e oku
-                    let mut bootloader_buffer = [0u8; BLOCK_SIZE * bootloader_size_blocks];
-                    // Bu kismın BlockDevice trait'ine toplu okuma eklenmesi veya
-                    // her blogun ayri ayri okunmasi gerekir.
-                     for i in 0..bootloader_size_blocks {
-                         match sd_card.read_block(bootloader_lba + i as u64, &mut bootloader_buffer[i*BLOCK_SIZE.. (i+1)*BLOCK_SIZE]) {
-                             Ok(_) => {},
-                             Err(e) => { log!("Failed to read bootloader block {}: {:?}", i, e); return Err(CliError::CommandFailed); }
-                         }
-                     }
-
-                    // Bellege kopyala (varsayim)
-                     core::ptr::copy_nonoverlapping(bootloader_buffer.as_ptr(), bootloader_dest_address as *mut u8, bootloader_buffer.len());
-                    log!("Bootloader loaded to {:#x}.", bootloader_dest_address);
-
-                    // Bootloader'a atla (unsafe!)
-                     let bootloader_entry: extern "C" fn() = core::mem::transmute(bootloader_dest_address);
-                     bootloader_entry(); // Buradan sonra kontrol bootloader'a gecer
-
-                },
-                Err(e) => {
-                    log!("Failed to read MBR from SD card: {:?}", e);
-                     writeln!(uart, "Failed to read MBR from SD card: {:?}", e).map_err(|_| CliError::UartWriteError)?;
-                    return Err(CliError::CommandFailed);
-                }
-            }
-        } else {
-            log!("SD card not available for boot.");
-             writeln!(uart, "SD card not available for boot.").map_err(|_| CliError::UartWriteError)?;
-        }
-    }
-    
-
-    // Boot basarili olduysa buraya donulmez.
-    // Hata durumunda veya boot cihazi bulunamazsa buraya donulur.
-    writeln!(uart, "Boot process finished or failed.").map_err(|_| CliError::UartWriteError)?;
-    Ok(())
-}
-
-// --- Global CLI Instance ---
-// Firmware'in ana döngüsünden erişim için.
-// # Safety: Global mutable static kullanimi unsafe'dir.
-pub static mut CLI_GLOBAL: Cli = Cli::new();
\ No newline at end of file
+#![no_std]
+
+use core::fmt::Write;
+use crate::uart::Uart0; // UART0_GLOBAL'i kullanmak için
+use crate::firmware_common;
+use crate::lexer::{Lexer, ParseError, Tokens};
+
+// Diğer modüllerdeki global statiklere erişim için use bildirimleri
+use crate::storage::{EMIC_STORAGE_GLOBAL, SD_CARD_STORAGE_GLOBAL, BlockDevice, BLOCK_SIZE}; // Global storage
+use crate::partition;
+use crate::fat::FatVolume;
+use crate::memory::{LPDDR1_SIZE_BYTES /*, ALLOCATOR */}; // Global memory bilgisi/allocator
+use crate::psu::PSU_MONITOR_GLOBAL; // Global PSU
+use crate::refrigerator::FRIDGE_CONTROLLER_GLOBAL; // Global Buzdolabi
+
+// Maximum size of the input buffer for a single command line.
+const INPUT_BUFFER_SIZE: usize = 128;
+// Maximum number of arguments a command can have (the command name is the first token).
+const MAX_ARGS: usize = crate::lexer::MAX_TOKENS - 1;
+// The prompt string displayed by the CLI.
+const PROMPT: &str = "> ";
+
+// Errors specific to the CLI module.
+#[derive(Debug)]
+pub enum CliError {
+    BufferFull,
+    UnknownCommand,
+    TooManyArguments,
+    InvalidDataFormat, // UTF-8 decode hatasi gibi
+    CommandFailed,
+    UartWriteError,
+    // Diğer modullerden sarilmis hatalar (istege bagli, firmware_common::Error zaten var)
+     Storage(crate::storage::StorageError),
+    Boot(crate::boot::BootError), // Boot yolu çözümleme / yükleme hataları
+    Parse(ParseError), // Komut satırı sözdizimi hatası (tırnak, kaçış, $değişken)
+    ArgumentsTooLong, // Birleştirilen argümanlar hedef arabelleğe sığmıyor
+    // ...
+}
+
+// Represents a registered CLI command.
+struct Command {
+    name: &'static str,
+    help: &'static str,
+    // Komut fonksiyonu: UART yazıcısını ve argüman dilimini alır.
+    execute: unsafe fn(&mut Uart0, &[&str]) -> Result<(), CliError>,
+    // Tab tamamlama: tamamlanan kelimeden önceki argümanlara göre adayları ekler.
+    complete: Option<Completer>,
+}
+
+// Argüman tamamlayıcı: `args` komut adından sonraki, tamamlanmış argümanlardır.
+type Completer = unsafe fn(&[&str], &mut Completions<'_>);
+
+// Number of previous command lines kept for up/down recall.
+const HISTORY_SIZE: usize = 8;
+
+// VT100 kaçış dizisi çözümleyici durumu: ESC, ESC [ <sayı> <son> (CSI) veya ESC O <son> (SS3).
+#[derive(Copy, Clone, PartialEq)]
+enum Escape {
+    None,
+    Start,
+    Csi { param: u8, more: bool }, // more: ';' sonrası parametreler (değiştirici tuşlar) yok sayılır
+    Ss3,
+}
+
+// Satır düzenleyicinin tanıdığı girişler (kontrol karakterleri ve kaçış dizileri bunlara çevrilir).
+#[derive(Copy, Clone, PartialEq)]
+enum EditKey {
+    Insert(u8),
+    Enter,
+    Backspace,
+    Delete,      // İmlecin altındaki karakter (Del, Ctrl-D)
+    Left,        // Sol ok, Ctrl-B
+    Right,       // Sağ ok, Ctrl-F
+    Home,        // Home, Ctrl-A
+    End,         // End, Ctrl-E
+    HistoryPrev, // Yukarı ok, Ctrl-P
+    HistoryNext, // Aşağı ok, Ctrl-N
+    KillToStart, // Ctrl-U
+    KillToEnd,   // Ctrl-K
+    KillWord,    // Ctrl-W: imleçten önceki kelime
+    Cancel,      // Ctrl-C: satırı at
+    Complete,    // Tab
+}
+
+// The main CLI state structure.
+// Satır `buffer[..len]`, ekrandaki imleç `cursor` konumundadır. Geçmiş, son HISTORY_SIZE satırı tutan
+// sabit boyutlu bir halkadır; geçmişte gezinirken yazılmakta olan satır `draft`'ta saklanır.
+pub struct Cli {
+    buffer: [u8; INPUT_BUFFER_SIZE],
+    len: usize,
+    cursor: usize,
+    escape: Escape,
+    last_was_cr: bool, // CR LF gönderen terminallerde LF ikinci bir Enter sayılmaz
+    last_was_tab: bool, // Art arda ikinci Tab adayları listeler
+    history: [[u8; INPUT_BUFFER_SIZE]; HISTORY_SIZE],
+    history_lens: [usize; HISTORY_SIZE],
+    history_next: usize,  // Bir sonraki kaydın yazılacağı halka konumu
+    history_count: usize, // Saklanan kayıt sayısı (en fazla HISTORY_SIZE)
+    history_pos: Option<usize>, // Gösterilen kayıt (0: en yeni); None: düzenlenen satır
+    draft: [u8; INPUT_BUFFER_SIZE],
+    draft_len: usize,
+}
+
+impl Cli {
+    pub const fn new() -> Self {
+        Cli {
+            buffer: [0u8; INPUT_BUFFER_SIZE],
+            len: 0,
+            cursor: 0,
+            escape: Escape::None,
+            last_was_cr: false,
+            last_was_tab: false,
+            history: [[0u8; INPUT_BUFFER_SIZE]; HISTORY_SIZE],
+            history_lens: [0; HISTORY_SIZE],
+            history_next: 0,
+            history_count: 0,
+            history_pos: None,
+            draft: [0u8; INPUT_BUFFER_SIZE],
+            draft_len: 0,
+        }
+    }
+
+    pub fn init(&mut self) {
+        self.reset_line();
+    }
+
+    fn reset_line(&mut self) {
+        self.len = 0;
+        self.cursor = 0;
+        self.escape = Escape::None;
+        self.history_pos = None;
+    }
+
+    /// Gelen baytı işler.
+    /// # Safety
+    /// Global UART'a yazma gerektirir.
+    pub unsafe fn process_byte(&mut self, byte: u8, uart: &mut Uart0) -> Result<(), CliError> {
+        let after_cr = core::mem::replace(&mut self.last_was_cr, byte == 0x0D);
+        let key = match self.decode(byte) {
+            Some(EditKey::Enter) if byte == 0x0A && after_cr => return Ok(()),
+            Some(key) => key,
+            None => return Ok(()),
+        };
+        self.edit(key, uart)
+    }
+
+    // Baytı kaçış dizisi durumuna göre bir düzenleme tuşuna çevirir; dizi tamamlanmadıysa None.
+    fn decode(&mut self, byte: u8) -> Option<EditKey> {
+        match self.escape {
+            Escape::None => {}
+            Escape::Start => {
+                self.escape = match byte {
+                    b'[' => Escape::Csi { param: 0, more: false },
+                    b'O' => Escape::Ss3,
+                    _ => Escape::None, // Alt+tuş vb. desteklenmiyor
+                };
+                return None;
+            }
+            Escape::Csi { param, more } => {
+                match byte {
+                    b'0'..=b'9' if !more => self.escape = Escape::Csi { param: param.saturating_mul(10).saturating_add(byte - b'0'), more },
+                    b'0'..=b'9' | b';' => self.escape = Escape::Csi { param, more: true },
+                    0x40..=0x7E => {
+                        self.escape = Escape::None;
+                        return match (byte, param) {
+                            (b'A', _) => Some(EditKey::HistoryPrev),
+                            (b'B', _) => Some(EditKey::HistoryNext),
+                            (b'C', _) => Some(EditKey::Right),
+                            (b'D', _) => Some(EditKey::Left),
+                            (b'H', _) | (b'~', 1) | (b'~', 7) => Some(EditKey::Home),
+                            (b'F', _) | (b'~', 4) | (b'~', 8) => Some(EditKey::End),
+                            (b'~', 3) => Some(EditKey::Delete),
+                            _ => None,
+                        };
+                    }
+                    _ => self.escape = Escape::None, // Bozuk dizi
+                }
+                return None;
+            }
+            Escape::Ss3 => {
+                self.escape = Escape::None;
+                return match byte {
+                    b'A' => Some(EditKey::HistoryPrev),
+                    b'B' => Some(EditKey::HistoryNext),
+                    b'C' => Some(EditKey::Right),
+                    b'D' => Some(EditKey::Left),
+                    b'H' => Some(EditKey::Home),
+                    b'F' => Some(EditKey::End),
+                    _ => None,
+                };
+            }
+        }
+
+        match byte {
+            0x1B => {
+                self.escape = Escape::Start;
+                None
+            }
+            0x0D | 0x0A => Some(EditKey::Enter),
+            0x08 | 0x7F => Some(EditKey::Backspace), // Backspace veya Delete
+            0x09 => Some(EditKey::Complete),
+            0x01 => Some(EditKey::Home),
+            0x02 => Some(EditKey::Left),
+            0x03 => Some(EditKey::Cancel),
+            0x04 => Some(EditKey::Delete),
+            0x05 => Some(EditKey::End),
+            0x06 => Some(EditKey::Right),
+            0x0B => Some(EditKey::KillToEnd),
+            0x0E => Some(EditKey::HistoryNext),
+            0x10 => Some(EditKey::HistoryPrev),
+            0x15 => Some(EditKey::KillToStart),
+            0x17 => Some(EditKey::KillWord),
+            0x20..=0x7E => Some(EditKey::Insert(byte)),
+            _ => None, // Diğer kontrol karakterleri ve ASCII dışı baytlar yok sayılır
+        }
+    }
+
+    unsafe fn edit(&mut self, key: EditKey, uart: &mut Uart0) -> Result<(), CliError> {
+        let repeated_tab = core::mem::replace(&mut self.last_was_tab, key == EditKey::Complete);
+        match key {
+            EditKey::Insert(byte) => self.insert(&[byte], uart)?,
+            EditKey::Complete => self.complete(repeated_tab, uart)?,
+            EditKey::Enter => {
+                uart.write_str("\r\n").map_err(|_| CliError::UartWriteError)?;
+                self.add_history();
+                let result = self.parse_and_execute(uart);
+                self.reset_line();
+                self.print_prompt(uart)?;
+                return result;
+            }
+            EditKey::Cancel => {
+                uart.write_str("^C\r\n").map_err(|_| CliError::UartWriteError)?;
+                self.reset_line();
+                self.print_prompt(uart)?;
+            }
+            EditKey::Backspace if self.cursor > 0 => self.delete(self.cursor - 1, self.cursor, uart)?,
+            EditKey::Delete if self.cursor < self.len => self.delete(self.cursor, self.cursor + 1, uart)?,
+            EditKey::KillToStart => self.delete(0, self.cursor, uart)?,
+            EditKey::KillToEnd => self.delete(self.cursor, self.len, uart)?,
+            EditKey::KillWord => {
+                let mut start = self.cursor;
+                while start > 0 && self.buffer[start - 1] == b' ' {
+                    start -= 1;
+                }
+                while start > 0 && self.buffer[start - 1] != b' ' {
+                    start -= 1;
+                }
+                self.delete(start, self.cursor, uart)?;
+            }
+            EditKey::Left if self.cursor > 0 => self.move_to(self.cursor - 1, uart)?,
+            EditKey::Right if self.cursor < self.len => self.move_to(self.cursor + 1, uart)?,
+            EditKey::Home => self.move_to(0, uart)?,
+            EditKey::End => self.move_to(self.len, uart)?,
+            EditKey::HistoryPrev => {
+                let position = self.history_pos.map_or(0, |p| p + 1);
+                if position < self.history_count {
+                    if self.history_pos.is_none() {
+                        self.draft[..self.len].copy_from_slice(&self.buffer[..self.len]);
+                        self.draft_len = self.len;
+                    }
+                    self.history_pos = Some(position);
+                    self.recall(position, uart)?;
+                }
+            }
+            EditKey::HistoryNext => match self.history_pos {
+                Some(0) => {
+                    self.history_pos = None;
+                    let draft = self.draft;
+                    self.replace_line(&draft[..self.draft_len], uart)?;
+                }
+                Some(position) => {
+                    self.history_pos = Some(position - 1);
+                    self.recall(position - 1, uart)?;
+                }
+                None => {}
+            },
+            _ => {} // Satır başında/sonunda imleç hareketi veya silme
+        }
+        Ok(())
+    }
+
+    // Ekran imleci `from` konumundayken satırın oradan sonrasını yeniden yazar, eski satırdan kalanı
+    // siler ve imleci `cursor`'a geri götürür.
+    unsafe fn redraw_from(&self, from: usize, uart: &mut Uart0) -> Result<(), CliError> {
+        let tail = core::str::from_utf8(&self.buffer[from..self.len]).map_err(|_| CliError::InvalidDataFormat)?;
+        write!(uart, "{}\x1b[K", tail).map_err(|_| CliError::UartWriteError)?;
+        if self.len > self.cursor {
+            write!(uart, "\x1b[{}D", self.len - self.cursor).map_err(|_| CliError::UartWriteError)?;
+        }
+        Ok(())
+    }
+
+    unsafe fn move_to(&mut self, position: usize, uart: &mut Uart0) -> Result<(), CliError> {
+        let written = if position < self.cursor {
+            write!(uart, "\x1b[{}D", self.cursor - position)
+        } else if position > self.cursor {
+            write!(uart, "\x1b[{}C", position - self.cursor)
+        } else {
+            Ok(())
+        };
+        written.map_err(|_| CliError::UartWriteError)?;
+        self.cursor = position;
+        Ok(())
+    }
+
+    // Metni imleç konumuna ekler; imleç eklenen metnin sonuna gelir.
+    unsafe fn insert(&mut self, text: &[u8], uart: &mut Uart0) -> Result<(), CliError> {
+        if self.len + text.len() > INPUT_BUFFER_SIZE {
+            uart.write_str("\x07").map_err(|_| CliError::UartWriteError)?; // BEL
+            return Err(CliError::BufferFull);
+        }
+        let from = self.cursor;
+        self.buffer.copy_within(from..self.len, from + text.len());
+        self.buffer[from..from + text.len()].copy_from_slice(text);
+        self.len += text.len();
+        self.cursor += text.len();
+        self.redraw_from(from, uart)
+    }
+
+    // `start..end` aralığını siler; imleç `start`'a gelir.
+    unsafe fn delete(&mut self, start: usize, end: usize, uart: &mut Uart0) -> Result<(), CliError> {
+        if start == end {
+            return Ok(());
+        }
+        self.move_to(start, uart)?;
+        self.buffer.copy_within(end..self.len, start);
+        self.len -= end - start;
+        self.redraw_from(start, uart)
+    }
+
+    unsafe fn replace_line(&mut self, line: &[u8], uart: &mut Uart0) -> Result<(), CliError> {
+        self.move_to(0, uart)?;
+        self.buffer[..line.len()].copy_from_slice(line);
+        self.len = line.len();
+        self.cursor = self.len;
+        self.redraw_from(0, uart)
+    }
+
+    // İmleçteki kelimeyi tamamlar. Kelime tek adaya uyuyorsa tamamlanır ve ardına boşluk eklenir;
+    // birden fazla adaya uyuyorsa ortak öneke kadar uzatılır, ilerleme yoksa ikinci Tab adayları listeler.
+    unsafe fn complete(&mut self, list: bool, uart: &mut Uart0) -> Result<(), CliError> {
+        let line = self.buffer;
+        let before = core::str::from_utf8(&line[..self.cursor]).map_err(|_| CliError::InvalidDataFormat)?;
+        let word_start = before.rfind(' ').map_or(0, |i| i + 1);
+        let word = &before[word_start..];
+        let mut words = before[..word_start].split_whitespace();
+        let completer: Completer = match words.next() {
+            None => complete_command_names,
+            Some(name) => match get_commands().iter().find(|c| c.name == name).and_then(|c| c.complete) {
+                Some(completer) => completer,
+                None => return uart.write_str("\x07").map_err(|_| CliError::UartWriteError),
+            },
+        };
+        let mut args: [&str; MAX_ARGS] = [""; MAX_ARGS];
+        let mut arg_count = 0;
+        for arg in words {
+            if arg_count == MAX_ARGS {
+                return uart.write_str("\x07").map_err(|_| CliError::UartWriteError);
+            }
+            args[arg_count] = arg;
+            arg_count += 1;
+        }
+        let args = &args[..arg_count];
+
+        let mut completions = Completions::new(word, None);
+        completer(args, &mut completions);
+        let common = &completions.common[..completions.common_len];
+        if completions.count == 0 {
+            uart.write_str("\x07").map_err(|_| CliError::UartWriteError)?;
+        } else if self.len - word.len() + common.len() > INPUT_BUFFER_SIZE {
+            // Tamamlama satıra sığmıyor; kelime silinmeden önce reddedilir ki kullanıcının yazdığı kaybolmasın.
+            uart.write_str("\x07").map_err(|_| CliError::UartWriteError)?;
+        } else if common != word.as_bytes() {
+            self.delete(word_start, self.cursor, uart)?;
+            self.insert(common, uart)?;
+            if completions.count == 1 && !common.ends_with(b"/") && !common.ends_with(b":") && self.len < INPUT_BUFFER_SIZE {
+                self.insert(b" ", uart)?;
+            }
+        } else if completions.count == 1 {
+            self.insert(b" ", uart)?;
+        } else if list {
+            uart.write_str("\r\n").map_err(|_| CliError::UartWriteError)?;
+            completer(args, &mut Completions::new(word, Some(&mut *uart)));
+            uart.write_str("\r\n").map_err(|_| CliError::UartWriteError)?;
+            self.print_prompt(uart)?;
+            self.redraw_from(0, uart)?;
+        } else {
+            uart.write_str("\x07").map_err(|_| CliError::UartWriteError)?;
+        }
+        Ok(())
+    }
+
+    fn history_index(&self, position: usize) -> usize {
+        (self.history_next + HISTORY_SIZE - 1 - position) % HISTORY_SIZE
+    }
+
+    unsafe fn recall(&mut self, position: usize, uart: &mut Uart0) -> Result<(), CliError> {
+        let index = self.history_index(position);
+        let entry = self.history[index];
+        self.replace_line(&entry[..self.history_lens[index]], uart)
+    }
+
+    // Boş satırlar ve en yeni kayıtla aynı olan satır geçmişe eklenmez.
+    fn add_history(&mut self) {
+        let line = self.buffer[..self.len].trim_ascii();
+        if line.is_empty() {
+            return;
+        }
+        if self.history_count > 0 {
+            let newest = self.history_index(0);
+            if &self.history[newest][..self.history_lens[newest]] == line {
+                return;
+            }
+        }
+        let slot = self.history_next;
+        self.history[slot][..line.len()].copy_from_slice(line);
+        self.history_lens[slot] = line.len();
+        self.history_next = (slot + 1) % HISTORY_SIZE;
+        self.history_count = core::cmp::min(self.history_count + 1, HISTORY_SIZE);
+    }
+
+    /// Arabellekteki satırı ayrıştırıp çalıştırır.
+    /// # Safety
+    /// Komut fonksiyonları global statiklere erişebilir. UART'a yazma gerektirir.
+    unsafe fn parse_and_execute(&mut self, uart: &mut Uart0) -> Result<(), CliError> {
+        let line = core::str::from_utf8(&self.buffer[..self.len]).map_err(|_| CliError::InvalidDataFormat)?;
+        run_line(uart, line)
+    }
+
+    /// Prompt yazdırır.
+    /// # Safety
+    /// Global UART'a yazma gerektirir.
+    pub unsafe fn print_prompt(&self, uart: &mut Uart0) -> Result<(), CliError> {
+        uart.write_str(PROMPT).map_err(|_| CliError::UartWriteError)
+    }
+}
+
+/// Bir komut satırını çalıştırır: `;` ile ayrılmış komutlar sırayla, bir önceki başarısız olsa da
+/// çalışır; son komutun sonucu döner. Satırın tamamı önce denetlenir, sözdizimi hatası varsa hiçbir
+/// komut çalışmaz ve hatanın yeri gösterilir.
+/// Değişkenler her komut çalışmadan hemen önce yeniden genişletilir. Bu yüzden değere bağlı tek hata
+/// olan `LineTooLong`, önceki bir komut değişkeni uzattıysa ancak o komuta gelindiğinde ortaya çıkar;
+/// bu durumda önceki komutlar çalışmış olur, hatalı komut ve sonrakiler çalışmaz.
+/// # Safety
+/// Komut fonksiyonları global statiklere erişebilir. UART'a yazma gerektirir.
+pub unsafe fn run_line(uart: &mut Uart0, line: &str) -> Result<(), CliError> {
+    let mut tokens = Tokens::new();
+    let mut lexer = Lexer::new(line);
+    loop {
+        match lexer.next_command(&mut tokens) {
+            Ok(true) => {}
+            Ok(false) => break,
+            Err(e) => return report_parse_error(uart, line, e),
+        }
+    }
+
+    let mut lexer = Lexer::new(line);
+    let mut result = Ok(());
+    loop {
+        match lexer.next_command(&mut tokens) {
+            Ok(true) => result = execute_tokens(uart, &tokens),
+            Ok(false) => break,
+            // Sözdizimi ilk geçişte doğrulandı; burada sadece değeri uzayan bir değişken
+            // (LineTooLong) hata verebilir.
+            Err(e) => return report_parse_error(uart, line, e),
+        }
+    }
+    result
+}
+
+unsafe fn execute_tokens(uart: &mut Uart0, tokens: &Tokens) -> Result<(), CliError> {
+    let command_name = tokens.get(0).ok_or(CliError::InvalidDataFormat)?;
+    let mut args: [&str; MAX_ARGS] = [""; MAX_ARGS];
+    for (index, arg) in args.iter_mut().enumerate().take(tokens.len() - 1) {
+        *arg = tokens.get(index + 1).ok_or(CliError::InvalidDataFormat)?;
+    }
+    let args_slice = &args[..tokens.len() - 1];
+
+    let commands = get_commands();
+    for command in commands {
+        if command.name == command_name {
+            return (command.execute)(uart, args_slice); // Komutu çalıştır
+        }
+    }
+
+    writeln!(uart, "Error: Unknown command '{}'. Type 'help'.", command_name).map_err(|_| CliError::UartWriteError)?;
+    Err(CliError::UnknownCommand)
+}
+
+// Hatayı, satırı ve hatanın konumunu gösteren bir işaretle yazar.
+unsafe fn report_parse_error(uart: &mut Uart0, line: &str, error: ParseError) -> Result<(), CliError> {
+    writeln!(uart, "Error: {} at column {}.", error.kind.description(), error.position + 1).map_err(|_| CliError::UartWriteError)?;
+    writeln!(uart, "  {}", line).map_err(|_| CliError::UartWriteError)?;
+    writeln!(uart, "  {:>1$}", "^", error.position + 1).map_err(|_| CliError::UartWriteError)?;
+    Err(CliError::Parse(error))
+}
+
+// --- Tab Tamamlama ---
+
+const DEVICE_NAMES: [&str; 2] = ["emic", "sd"];
+// Aday listesinin satır genişliği.
+const LIST_WIDTH: usize = 78;
+
+// Tamamlayıcıların eklediği adaylardan kelimeye uyanları toplar: sayıları ve en uzun ortak önekleri.
+// Listeleme modunda uyan adaylar ayrıca UART'a yazılır.
+struct Completions<'a> {
+    word: &'a str,
+    ignore_case: bool, // FAT adları büyük/küçük harf duyarsız eşleşir
+    common: [u8; INPUT_BUFFER_SIZE],
+    common_len: usize,
+    count: usize,
+    list: Option<&'a mut Uart0>,
+    column: usize,
+}
+
+impl<'a> Completions<'a> {
+    fn new(word: &'a str, list: Option<&'a mut Uart0>) -> Self {
+        Completions { word, ignore_case: false, common: [0; INPUT_BUFFER_SIZE], common_len: 0, count: 0, list, column: 0 }
+    }
+
+    fn add(&mut self, candidate: &str) {
+        let ignore_case = self.ignore_case;
+        let word = self.word.as_bytes();
+        let matches = candidate.len() >= word.len() && candidate.len() <= INPUT_BUFFER_SIZE && if ignore_case {
+            candidate.as_bytes()[..word.len()].eq_ignore_ascii_case(word)
+        } else {
+            candidate.as_bytes().starts_with(word)
+        };
+        if !matches {
+            return;
+        }
+        if let Some(uart) = self.list.as_deref_mut() {
+            if self.column > 0 && self.column + candidate.len() + 2 > LIST_WIDTH {
+                let _ = uart.write_str("\r\n");
+                self.column = 0;
+            }
+            let _ = write!(uart, "{}  ", candidate);
+            self.column += candidate.len() + 2;
+        }
+        if self.count == 0 {
+            self.common[..candidate.len()].copy_from_slice(candidate.as_bytes());
+            self.common_len = candidate.len();
+        } else {
+            self.common_len = self.common[..self.common_len]
+                .iter()
+                .zip(candidate.bytes())
+                .take_while(|(a, b)| if ignore_case { a.eq_ignore_ascii_case(b) } else { **a == *b })
+                .count();
+        }
+        self.count += 1;
+    }
+
+    fn add_fmt(&mut self, args: core::fmt::Arguments) {
+        let mut buffer = [0u8; INPUT_BUFFER_SIZE + 1]; // Sığmayan aday kesilmek yerine elensin diye +1
+        let mut cursor = ByteCursor { buffer: &mut buffer, len: 0 };
+        let _ = cursor.write_fmt(args);
+        let len = cursor.len;
+        if let Ok(candidate) = core::str::from_utf8(&buffer[..len]) {
+            self.add(candidate);
+        }
+    }
+
+    fn add_all(&mut self, candidates: &[&str]) {
+        for candidate in candidates {
+            self.add(candidate);
+        }
+    }
+}
+
+unsafe fn complete_command_names(args: &[&str], out: &mut Completions<'_>) {
+    if args.is_empty() {
+        for command in get_commands() {
+            out.add(command.name);
+        }
+    }
+}
+
+// Aygıttaki bölüm indeksleri, `prefix` ile (örn. "emic:").
+unsafe fn complete_partitions(out: &mut Completions<'_>, device: &str, prefix: &str) {
+    if let Ok(device) = crate::storage::device_by_name(device) {
+        if let Ok(table) = partition::read_partition_table(device) {
+            for info in table.iter() {
+                out.add_fmt(format_args!("{}{}", prefix, info.index));
+            }
+        }
+    }
+}
+
+// FAT bölümündeki `path`'in son bileşeni; dizinler '/' ile biter. Adaylar `prefix` ile başlar.
+unsafe fn complete_path(out: &mut Completions<'_>, device: &str, partition_index: &str, prefix: &str, path: &str) {
+    let device = match crate::storage::device_by_name(device) {
+        Ok(d) => d,
+        Err(_) => return,
+    };
+    let view = match partition_index.parse().ok().and_then(|index| partition::open_partition(device, index).ok()) {
+        Some(v) => v,
+        None => return,
+    };
+    let mut volume = match FatVolume::mount(view) {
+        Ok(v) => v,
+        Err(_) => return,
+    };
+    let dir_path = &path[..path.rfind('/').map_or(0, |i| i + 1)];
+    let mut dir = match volume.open_dir(dir_path) {
+        Ok(d) => d,
+        Err(_) => return,
+    };
+    out.ignore_case = true;
+    while let Ok(Some(entry)) = volume.next_entry(&mut dir) {
+        let name = entry.name();
+        if name != "." && name != ".." {
+            out.add_fmt(format_args!("{}{}{}{}", prefix, dir_path, name, if entry.is_dir() { "/" } else { "" }));
+        }
+    }
+}
+
+unsafe fn complete_storage(args: &[&str], out: &mut Completions<'_>) {
+    match args {
+        [] => out.add_all(&["info", "part", "read", "write"]),
+        [_] => out.add_all(&DEVICE_NAMES),
+        ["write", _, _] => out.add_all(&["fill", "hex"]),
+        _ => {}
+    }
+}
+
+// boot hedefi: aygıt, "aygıt:bölüm" veya "aygıt:bölüm/yol".
+unsafe fn complete_boot(args: &[&str], out: &mut Completions<'_>) {
+    if !args.is_empty() {
+        return;
+    }
+    let word = out.word;
+    match word.split_once(':') {
+        None => {
+            for device in DEVICE_NAMES {
+                out.add(device);
+                out.add_fmt(format_args!("{}:", device));
+            }
+        }
+        Some((device, rest)) => match rest.split_once('/') {
+            None => complete_partitions(out, device, &word[..device.len() + 1]),
+            Some((index, path)) => complete_path(out, device, index, &word[..device.len() + index.len() + 2], path),
+        },
+    }
+}
+
+unsafe fn complete_getenv(args: &[&str], out: &mut Completions<'_>) {
+    if args.is_empty() {
+        for spec in crate::env::variables() {
+            out.add(spec.name);
+        }
+    }
+}
+
+// setenv: değişken adı, ardından seçenekli ve boolean değişkenlerin değerleri.
+unsafe fn complete_setenv(args: &[&str], out: &mut Completions<'_>) {
+    use crate::env::VarType;
+    match args {
+        [] => complete_getenv(args, out),
+        [name] => match crate::env::find_spec(name).map(|spec| &spec.var_type) {
+            Some(VarType::Choice(choices)) => out.add_all(choices),
+            Some(VarType::Boolean) => out.add_all(&["0", "1"]),
+            _ => {}
+        },
+        _ => {}
+    }
+}
+
+// baud: UART saatiyle üretilebilen standart hızlar.
+unsafe fn complete_baud(args: &[&str], out: &mut Completions<'_>) {
+    if args.is_empty() {
+        for rate in ["9600", "19200", "38400", "57600", "115200", "230400", "460800", "921600"] {
+            if crate::uart::is_valid_baud(rate) {
+                out.add(rate);
+            }
+        }
+    }
+}
+
+unsafe fn complete_secureboot(args: &[&str], out: &mut Completions<'_>) {
+    if args.is_empty() {
+        out.add_all(&["enforce", "warn", "off"]);
+    }
+}
+
+unsafe fn complete_slot(args: &[&str], out: &mut Completions<'_>) {
+    match args {
+        [] => out.add_all(&["status", "set-active", "mark-successful", "mark-unbootable"]),
+        [action] if *action != "status" => out.add_all(&["a", "b"]),
+        _ => {}
+    }
+}
+
+// loady/loadx: isteğe bağlı adresten sonra "go".
+unsafe fn complete_load(args: &[&str], out: &mut Completions<'_>) {
+    if args.len() <= 1 && !args.contains(&"go") {
+        out.add("go");
+    }
+}
+
+unsafe fn complete_update(args: &[&str], out: &mut Completions<'_>) {
+    if args.is_empty() {
+        out.add_all(&["ymodem", "xmodem", "status"]);
+    }
+}
+
+unsafe fn complete_fatls(args: &[&str], out: &mut Completions<'_>) {
+    match args {
+        [] => out.add_all(&DEVICE_NAMES),
+        [device] => complete_partitions(out, device, ""),
+        [device, index] => {
+            let word = out.word;
+            complete_path(out, device, index, "", word);
+        }
+        _ => {}
+    }
+}
+
+// --- Komut Implementasyonları ---
+
+fn get_commands() -> &'static [Command] {
+    &[
+        Command { name: "help", help: "Show help.", execute: help_command, complete: Some(complete_command_names) },
+        Command { name: "status", help: "Show system status.", execute: status_command, complete: None },
+        Command { name: "storage", help: "Interact with storage. Usage: storage <info|part> <emic|sd> | storage read <emic|sd> <lba> [count] | storage write <emic|sd> <lba> <fill <hex bytes> [count] | hex <hex bytes>>", execute: storage_command, complete: Some(complete_storage) },
+        Command { name: "boot", help: "Attempt to boot from a device. Usage: boot [partition_index | <emic|sd> | <emic|sd>:<partition>/<path>] [kernel command line...]", execute: boot_command, complete: Some(complete_boot) },
+        Command { name: "setenv", help: "Set a firmware variable (no value: reset to default). Usage: setenv <name> [value...]", execute: setenv_command, complete: Some(complete_setenv) },
+        Command { name: "getenv", help: "Show a firmware variable. Usage: getenv <name>", execute: getenv_command, complete: Some(complete_getenv) },
+        Command { name: "printenv", help: "List firmware variables with their types and defaults.", execute: printenv_command, complete: None },
+        Command { name: "saveenv", help: "Write changed firmware variables to the eMMC.", execute: saveenv_command, complete: None },
+        Command { name: "baud", help: "Show or change the console baud rate (run 'saveenv' to keep it). Usage: baud [rate]", execute: baud_command, complete: Some(complete_baud) },
+        Command { name: "secureboot", help: "Show or set the boot image signature policy. Usage: secureboot [enforce|warn|off]", execute: secureboot_command, complete: Some(complete_secureboot) },
+        Command { name: "bootlog", help: "Show the measured boot log (SHA-256 PCR chain and events).", execute: bootlog_command, complete: None },
+        Command { name: "slot", help: "Show or change A/B OS slots. Usage: slot [status | set-active <a|b> | mark-successful <a|b> | mark-unbootable <a|b>]", execute: slot_command, complete: Some(complete_slot) },
+        Command { name: "loady", help: "Receive a binary over YMODEM into RAM and optionally run it. Usage: loady [address] [go [kernel command line...]]", execute: loady_command, complete: Some(complete_load) },
+        Command { name: "loadx", help: "Receive a binary over XMODEM into RAM and optionally run it. Usage: loadx [address] [go [kernel command line...]]", execute: loadx_command, complete: Some(complete_load) },
+        Command { name: "update", help: "Receive a firmware update over UART0 and install it. Usage: update [ymodem|xmodem|status]", execute: update_command, complete: Some(complete_update) },
+        Command { name: "source", help: "Run a command script from a FAT partition. Usage: source <emic|sd>:<partition>/<path>", execute: source_command, complete: Some(complete_boot) },
+        Command { name: "echo", help: "Print the arguments. Usage: echo [text...]", execute: echo_command, complete: None },
+        Command { name: "fatls", help: "List a directory on a FAT partition. Usage: fatls <emic|sd> <partition> [path]", execute: fatls_command, complete: Some(complete_fatls) },
+        // Diğer komutlar buraya eklenecek
+        // Command { name: "fridge", help: "Control refrigerator.", execute: fridge_command, complete: None },
+        // Command { name: "psu", help: "PSU status.", execute: psu_command, complete: None },
+    ]
+}
+
+// help komutu (Zaten hazırdı)
+unsafe fn help_command(uart: &mut Uart0, args: &[&str]) -> Result<(), CliError> {
+     let commands = get_commands();
+    if args.is_empty() {
+        writeln!(uart, "Available commands:").map_err(|_| CliError::UartWriteError)?;
+        for cmd in commands {
+            writeln!(uart, "  {}: {}", cmd.name, cmd.help).map_err(|_| CliError::UartWriteError)?;
+        }
+        writeln!(uart, "Line editing: arrows, Home/End, Del, Ctrl-A/E/B/F/D/K/U/W, Ctrl-C; Up/Down (Ctrl-P/N) recall the last {} commands; Tab completes.", HISTORY_SIZE).map_err(|_| CliError::UartWriteError)?;
+    } else {
+        let target_command = args[0];
+        let mut found = false;
+        for cmd in commands {
+            if cmd.name == target_command {
+                writeln!(uart, "{}: {}", cmd.name, cmd.help).map_err(|_| CliError::UartWriteError)?;
+                found = true;
+                break;
+            }
+        }
+        if !found {
+             writeln!(uart, "Error: Command '{}' not found.", target_command).map_err(|_| CliError::UartWriteError)?;
+        }
+    }
+    Ok(())
+}
+
+// status komutu (Global statiklere erişim eklendi)
+unsafe fn status_command(uart: &mut Uart0, _args: &[&str]) -> Result<(), CliError> {
+    writeln!(uart, "PacketBox System Status:").map_err(|_| CliError::UartWriteError)?;
+    writeln!(uart, "  Firmware: PacketBox v{} (BuildDate)", firmware_common::FIRMWARE_VERSION).map_err(|_| CliError::UartWriteError)?; // Versiyon/BuildDate sabitleri eklenebilir
+    writeln!(uart, "  State: {:?}", firmware_common::get_system_state()).map_err(|_| CliError::UartWriteError)?;
+    writeln!(uart, "  Memory: {} Bytes LPDDR1", LPDDR1_SIZE_BYTES).map_err(|_| CliError::UartWriteError)?;
+    writeln!(uart, "  Console: UART0 at {} baud, {} byte(s) dropped (RX buffer full)", uart.baud_config().requested, uart.rx_overruns()).map_err(|_| CliError::UartWriteError)?;
+
+    // Global Storage durumunu raporla
+    writeln!(uart, "  Storage:").map_err(|_| CliError::UartWriteError)?;
+    let emic_status = if EMIC_STORAGE_GLOBAL.is_some() { "Initialized" } else { "Not Initialized" };
+    let emic_blocks = if let Some(emic) = &mut EMIC_STORAGE_GLOBAL { emic.block_count().unwrap_or(0) } else { 0 }; // unsafe erişim
+    writeln!(uart, "    eMMC 1.0: {} ({} blocks)", emic_status, emic_blocks).map_err(|_| CliError::UartWriteError)?;
+
+    let sd_status = if SD_CARD_STORAGE_GLOBAL.is_some() { "Initialized" } else { "Not Initialized" };
+    let sd_blocks = if let Some(sd) = &mut SD_CARD_STORAGE_GLOBAL { sd.block_count().unwrap_or(0) } else { 0 }; // unsafe erişim
+    writeln!(uart, "    SD Card: {} ({} blocks)", sd_status, sd_blocks).map_err(|_| CliError::UartWriteError)?;
+
+    // Global PSU durumunu raporla
+    writeln!(uart, "  PSU:").map_err(|_| CliError::UartWriteError)?;
+    let psu_status = if PSU_MONITOR_GLOBAL.is_some() { "Initialized" } else { "Not Initialized" };
+    // Eğer is_power_good fonksiyonu varsa kullanılabilir:
+     let power_good = if let Some(psu) = &mut PSU_MONITOR_GLOBAL { psu.is_power_good().unwrap_or(false) } else { false };
+     writeln!(uart, "    Monitor: {} (Power Good: {})", psu_status, power_good).map_err(|_| CliError::UartWriteError)?;
+
+
+    // Global Refrigerator durumunu raporla
+    writeln!(uart, "  Refrigerator:").map_err(|_| CliError::UartWriteError)?;
+    let fridge_status = if FRIDGE_CONTROLLER_GLOBAL.is_some() { "Initialized" } else { "Not Initialized" };
+    writeln!(uart, "    Controller: {}", fridge_status).map_err(|_| CliError::UartWriteError)?;
+
+
+    Ok(())
+}
+
+// storage komutu (Sadece info kısmı implement edildi)
+unsafe fn storage_command(uart: &mut Uart0, args: &[&str]) -> Result<(), CliError> {
+    if args.len() >= 2 && args[0] == "part" {
+        return storage_part_command(uart, args[1]);
+    }
+    if !args.is_empty() && args[0] == "read" {
+        return storage_read_command(uart, &args[1..]);
+    }
+    if !args.is_empty() && args[0] == "write" {
+        return storage_write_command(uart, &args[1..]);
+    }
+    if args.len() < 2 || args[0] != "info" {
+        writeln!(uart, "Usage: storage info <emic|sd>").map_err(|_| CliError::UartWriteError)?;
+        writeln!(uart, "Usage: storage part <emic|sd>").map_err(|_| CliError::UartWriteError)?;
+        writeln!(uart, "Usage: storage read <emic|sd> <lba> [count]").map_err(|_| CliError::UartWriteError)?;
+        writeln!(uart, "Usage: storage write <emic|sd> <lba> fill <hex bytes> [count]").map_err(|_| CliError::UartWriteError)?;
+        writeln!(uart, "Usage: storage write <emic|sd> <lba> hex <hex bytes>").map_err(|_| CliError::UartWriteError)?;
+        return Ok(());
+    }
+
+    let device_type = args[1];
+
+    match device_type {
+        "emic" => {
+            writeln!(uart, "eMMC 1.0 Info:").map_err(|_| CliError::UartWriteError)?;
+            let emic_status = if EMIC_STORAGE_GLOBAL.is_some() { "Initialized" } else { "Not Initialized" };
+            writeln!(uart, "  Status: {}", emic_status).map_err(|_| CliError::UartWriteError)?;
+             if let Some(emic) = &mut EMIC_STORAGE_GLOBAL { // unsafe erişim
+                 let blocks = emic.block_count().unwrap_or(0);
+                 let bytes = blocks * BLOCK_SIZE as u64;
+                 writeln!(uart, "  Total Blocks: {}", blocks).map_err(|_| CliError::UartWriteError)?;
+                 writeln!(uart, "  Capacity: {} Bytes (approx {} MB)", bytes, bytes / 1024 / 1024).map_err(|_| CliError::UartWriteError)?;
+             }
+        },
+        "sd" => {
+             writeln!(uart, "SD Card Info:").map_err(|_| CliError::UartWriteError)?;
+             let sd_status = if SD_CARD_STORAGE_GLOBAL.is_some() { "Initialized" } else { "Not Initialized" };
+             writeln!(uart, "  Status: {}", sd_status).map_err(|_| CliError::UartWriteError)?;
+             if let Some(sd) = &mut SD_CARD_STORAGE_GLOBAL { // unsafe erişim
+                 let blocks = sd.block_count().unwrap_or(0);
+                 let bytes = blocks * BLOCK_SIZE as u64;
+                 writeln!(uart, "  Total Blocks: {}", blocks).map_err(|_| CliError::UartWriteError)?;
+                 // 2.2TB limitini belirtmek faydali olabilir (GPT varsa limit uygulanmaz)
+                 let limit_note = if sd.is_mbr_capped() { " - capped by MBR at ~2.2TB" } else if sd.is_gpt() { " - GPT, no MBR cap" } else { "" };
+                 writeln!(uart, "  Capacity: {} Bytes (approx {} MB / {} GB / {} TB{})", bytes, bytes / 1024 / 1024, bytes / 1024 / 1024 / 1024, bytes / 1024 / 1024 / 1024 / 1024, limit_note).map_err(|_| CliError::UartWriteError)?;
+             }
+        },
+        _ => {
+            writeln!(uart, "Error: Unknown device type '{}'. Use 'emic' or 'sd'.", device_type).map_err(|_| CliError::UartWriteError)?;
+        }
+    }
+
+    Ok(())
+}
+
+
+// storage part <emic|sd>: bölüm tablosunu listeler
+unsafe fn storage_part_command(uart: &mut Uart0, device_type: &str) -> Result<(), CliError> {
+    let device = match crate::storage::device_by_name(device_type) {
+        Ok(d) => d,
+        Err(e) => {
+            writeln!(uart, "Error: Device '{}' not available: {:?}", device_type, e).map_err(|_| CliError::UartWriteError)?;
+            return Err(CliError::CommandFailed);
+        }
+    };
+    let table = match partition::read_partition_table(device) {
+        Ok(t) => t,
+        Err(e) => {
+            writeln!(uart, "Error: Failed to read partition table: {:?}", e).map_err(|_| CliError::UartWriteError)?;
+            return Err(CliError::CommandFailed);
+        }
+    };
+    if table.is_empty() {
+        writeln!(uart, "No partitions found.").map_err(|_| CliError::UartWriteError)?;
+        return Ok(());
+    }
+    writeln!(uart, "  #  Boot  Type            Start LBA     Blocks").map_err(|_| CliError::UartWriteError)?;
+    for p in table.iter() {
+        writeln!(uart, "  {:<2} {:<5} {:<15} {:<13} {}", p.index, if p.is_bootable() { "*" } else { "" }, p.type_name(), p.start_lba, p.block_count).map_err(|_| CliError::UartWriteError)?;
+        if let partition::PartitionKind::Gpt { type_guid, unique_guid, name, .. } = &p.kind {
+            writeln!(uart, "     Name: \"{}\"", name).map_err(|_| CliError::UartWriteError)?;
+            writeln!(uart, "     Type GUID: {}  Unique GUID: {}", type_guid, unique_guid).map_err(|_| CliError::UartWriteError)?;
+        }
+    }
+    Ok(())
+}
+
+// storage read/write'ın tek komutta işleyebileceği en fazla blok (4 MiB).
+const STORAGE_MAX_RANGE_BLOCKS: u64 = 8192;
+
+// storage read/write ortak argümanları: aygıt, başlangıç LBA'sı ve blok sayısı (varsayılan 1).
+// Aralık aygıtın sonunu aşamaz.
+unsafe fn open_block_range(uart: &mut Uart0, device_name: &str, lba_text: &str, count_text: Option<&str>) -> Result<(&'static mut dyn BlockDevice, u64, u64), CliError> {
+    let device = match crate::storage::device_by_name(device_name) {
+        Ok(d) => d,
+        Err(e) => {
+            writeln!(uart, "Error: Device '{}' not available: {:?}", device_name, e).map_err(|_| CliError::UartWriteError)?;
+            return Err(CliError::CommandFailed);
+        }
+    };
+    let (lba, count) = match (parse_number(lba_text), count_text.map_or(Some(1), parse_number)) {
+        (Some(lba), Some(count)) if (1..=STORAGE_MAX_RANGE_BLOCKS).contains(&count) => (lba, count),
+        _ => {
+            writeln!(uart, "Error: Invalid LBA or block count (1-{} blocks).", STORAGE_MAX_RANGE_BLOCKS).map_err(|_| CliError::UartWriteError)?;
+            return Err(CliError::InvalidDataFormat);
+        }
+    };
+    let total = device.block_count().unwrap_or(0);
+    if lba >= total || count > total - lba {
+        writeln!(uart, "Error: LBA {}..{} is outside {} ({} blocks).", lba, lba.saturating_add(count - 1), device_name, total).map_err(|_| CliError::UartWriteError)?;
+        return Err(CliError::InvalidDataFormat);
+    }
+    Ok((device, lba, count))
+}
+
+// `hexdump -C` biçimi: ofset, 16 bayt onaltılık, ASCII. Önceki satırla aynı olan satırlar tek bir
+// '*' satırıyla geçilir; döküm bitiş ofsetiyle kapanır.
+struct HexDump {
+    previous: [u8; 16],
+    has_previous: bool,
+    skipping: bool,
+}
+
+impl HexDump {
+    const fn new() -> Self {
+        HexDump { previous: [0; 16], has_previous: false, skipping: false }
+    }
+
+    fn line(&mut self, uart: &mut Uart0, offset: u64, bytes: &[u8]) -> Result<(), CliError> {
+        if self.has_previous && bytes == self.previous {
+            if !self.skipping {
+                writeln!(uart, "*").map_err(|_| CliError::UartWriteError)?;
+                self.skipping = true;
+            }
+            return Ok(());
+        }
+        self.previous.copy_from_slice(bytes);
+        self.has_previous = true;
+        self.skipping = false;
+
+        write!(uart, "{:08x} ", offset).map_err(|_| CliError::UartWriteError)?;
+        for (i, byte) in bytes.iter().enumerate() {
+            write!(uart, "{}{:02x}", if i == 8 { "  " } else { " " }, byte).map_err(|_| CliError::UartWriteError)?;
+        }
+        let mut ascii = [b'.'; 16];
+        for (c, &byte) in ascii.iter_mut().zip(bytes) {
+            if (0x20..0x7F).contains(&byte) {
+                *c = byte;
+            }
+        }
+        writeln!(uart, "  |{}|", core::str::from_utf8(&ascii).unwrap_or("")).map_err(|_| CliError::UartWriteError)
+    }
+
+    fn finish(&self, uart: &mut Uart0, end_offset: u64) -> Result<(), CliError> {
+        writeln!(uart, "{:08x}", end_offset).map_err(|_| CliError::UartWriteError)
+    }
+}
+
+// storage read <emic|sd> <lba> [count]: blokları döker. Bir tuşa basılırsa döküm durur.
+unsafe fn storage_read_command(uart: &mut Uart0, args: &[&str]) -> Result<(), CliError> {
+    if args.len() < 2 || args.len() > 3 {
+        writeln!(uart, "Usage: storage read <emic|sd> <lba> [count]").map_err(|_| CliError::UartWriteError)?;
+        return Err(CliError::InvalidDataFormat);
+    }
+    let (device, lba, count) = open_block_range(uart, args[0], args[1], args.get(2).copied())?;
+    let mut block = [0u8; BLOCK_SIZE];
+    let mut dump = HexDump::new();
+    for current in lba..lba + count {
+        if current > lba && uart.read_byte().is_some() {
+            writeln!(uart, "Interrupted at LBA {}.", current).map_err(|_| CliError::UartWriteError)?;
+            return Ok(());
+        }
+        if let Err(e) = device.read_block(current, &mut block) {
+            writeln!(uart, "Error: Reading LBA {} failed: {:?}", current, e).map_err(|_| CliError::UartWriteError)?;
+            return Err(CliError::CommandFailed);
+        }
+        for (i, line) in block.chunks_exact(16).enumerate() {
+            dump.line(uart, current * BLOCK_SIZE as u64 + (i * 16) as u64, line)?;
+        }
+    }
+    dump.finish(uart, (lba + count) * BLOCK_SIZE as u64)
+}
+
+// Onay gerektiren bölgeler: her aygıtta LBA 0 (MBR / koruyucu MBR) ve eMMC'de firmware'e ayrılmış alan.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum ProtectedRegion {
+    PartitionTable,
+    FirmwareReserved,
+}
+
+impl core::fmt::Display for ProtectedRegion {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        use crate::storage::{EMMC_RESERVED_END_LBA, EMMC_RESERVED_START_LBA};
+        match self {
+            ProtectedRegion::PartitionTable => write!(f, "LBA 0 (partition table)"),
+            // Aralık sabitlerden yazılır; ayrılmış alan değişirse mesaj da değişir.
+            ProtectedRegion::FirmwareReserved => write!(f, "the firmware-reserved area (NVRAM and firmware banks, LBA {}-{})", EMMC_RESERVED_START_LBA, EMMC_RESERVED_END_LBA - 1),
+        }
+    }
+}
+
+fn protected_region(device_name: &str, lba: u64, count: u64) -> Option<ProtectedRegion> {
+    use crate::storage::{EMMC_RESERVED_END_LBA, EMMC_RESERVED_START_LBA};
+    if lba == 0 {
+        Some(ProtectedRegion::PartitionTable)
+    } else if device_name == "emic" && lba < EMMC_RESERVED_END_LBA && lba + count > EMMC_RESERVED_START_LBA {
+        Some(ProtectedRegion::FirmwareReserved)
+    } else {
+        None
+    }
+}
+
+// Soruyu yazar ve bir satır okur; yalnızca "yes" onaydır. Ctrl-C reddeder.
+unsafe fn confirm(uart: &mut Uart0, question: core::fmt::Arguments<'_>) -> Result<bool, CliError> {
+    write!(uart, "{} Type 'yes' to continue: ", question).map_err(|_| CliError::UartWriteError)?;
+    let mut answer = [0u8; 8];
+    let mut len = 0;
+    loop {
+        match uart.getc() {
+            b'\r' | b'\n' => break,
+            0x03 => {
+                writeln!(uart, "^C").map_err(|_| CliError::UartWriteError)?;
+                return Ok(false);
+            }
+            0x08 | 0x7F if len > 0 => {
+                len -= 1;
+                uart.write_str("\x08 \x08").map_err(|_| CliError::UartWriteError)?;
+            }
+            byte @ 0x20..=0x7E if len < answer.len() => {
+                answer[len] = byte;
+                len += 1;
+                uart.putc(byte);
+            }
+            _ => {}
+        }
+    }
+    writeln!(uart).map_err(|_| CliError::UartWriteError)?;
+    Ok(&answer[..len] == b"yes")
+}
+
+// "de ad be ef", "0xdeadbeef" veya "de:ad:be:ef" biçimindeki baytları çözer ('_' ve ':' yok sayılır).
+// Tek sayıda hane veya sığmayan veri için None.
+fn parse_hex_bytes(text: &str, out: &mut [u8]) -> Option<usize> {
+    let text = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")).unwrap_or(text);
+    let mut len = 0;
+    let mut high = None;
+    for c in text.chars().filter(|&c| c != '_' && c != ':') {
+        let nibble = c.to_digit(16)? as u8;
+        match high.take() {
+            None => high = Some(nibble),
+            Some(high) => {
+                *out.get_mut(len)? = high << 4 | nibble;
+                len += 1;
+            }
+        }
+    }
+    if high.is_some() { None } else { Some(len) }
+}
+
+// storage write <emic|sd> <lba> fill <hex bytes> [count]: aralığı bayt deseniyle doldurur (desen bloklar
+// boyunca sürer). storage write <emic|sd> <lba> hex <hex bytes>: baytları bloğun başına yazar, bloğun
+// kalanı korunur. Her blok yazıldıktan sonra geri okunup doğrulanır.
+unsafe fn storage_write_command(uart: &mut Uart0, args: &[&str]) -> Result<(), CliError> {
+    let (device_name, lba_text, mode, data, count_text) = match args {
+        [device, lba, "fill", data] => (*device, *lba, "fill", *data, None),
+        [device, lba, "fill", data, count] => (*device, *lba, "fill", *data, Some(*count)),
+        [device, lba, "hex", data] => (*device, *lba, "hex", *data, None),
+        _ => {
+            writeln!(uart, "Usage: storage write <emic|sd> <lba> fill <hex bytes> [count]").map_err(|_| CliError::UartWriteError)?;
+            writeln!(uart, "Usage: storage write <emic|sd> <lba> hex <hex bytes>").map_err(|_| CliError::UartWriteError)?;
+            return Err(CliError::InvalidDataFormat);
+        }
+    };
+    let mut pattern = [0u8; BLOCK_SIZE];
+    let pattern_len = match parse_hex_bytes(data, &mut pattern) {
+        Some(len) if len > 0 => len,
+        _ => {
+            writeln!(uart, "Error: '{}' is not a hex byte string (even number of digits, at most {} bytes).", data, BLOCK_SIZE).map_err(|_| CliError::UartWriteError)?;
+            return Err(CliError::InvalidDataFormat);
+        }
+    };
+    let (device, lba, count) = open_block_range(uart, device_name, lba_text, count_text)?;
+    let last = lba + count - 1;
+
+    if let Some(region) = protected_region(device_name, lba, count) {
+        if !confirm(uart, format_args!("LBA {}..{} on {} overlaps {}.", lba, last, device_name, region))? {
+            writeln!(uart, "Aborted, nothing was written.").map_err(|_| CliError::UartWriteError)?;
+            return Ok(());
+        }
+    }
+
+    let mut block = [0u8; BLOCK_SIZE];
+    let mut readback = [0u8; BLOCK_SIZE];
+    for (index, current) in (lba..=last).enumerate() {
+        if mode == "hex" {
+            if let Err(e) = device.read_block(current, &mut block) {
+                writeln!(uart, "Error: Reading LBA {} failed: {:?}; nothing was written.", current, e).map_err(|_| CliError::UartWriteError)?;
+                return Err(CliError::CommandFailed);
+            }
+            block[..pattern_len].copy_from_slice(&pattern[..pattern_len]);
+        } else {
+            for (i, byte) in block.iter_mut().enumerate() {
+                *byte = pattern[(index * BLOCK_SIZE + i) % pattern_len];
+            }
+        }
+        let written = match device.write_block(current, &block).and_then(|_| device.read_block(current, &mut readback)) {
+            Ok(()) if readback == block => continue,
+            Ok(()) => writeln!(uart, "Error: LBA {} reads back different data.", current),
+            Err(e) => writeln!(uart, "Error: Writing LBA {} failed: {:?}", current, e),
+        };
+        written.map_err(|_| CliError::UartWriteError)?;
+        writeln!(uart, "{} of {} block(s) written.", index, count).map_err(|_| CliError::UartWriteError)?;
+        return Err(CliError::CommandFailed);
+    }
+    writeln!(uart, "Wrote {} block(s) to {} LBA {}..{}.", count, device_name, lba, last).map_err(|_| CliError::UartWriteError)?;
+    if device_name == "emic" && lba < crate::storage::EMMC_RESERVED_END_LBA && last >= crate::storage::EMMC_RESERVED_START_LBA {
+        writeln!(uart, "Note: the NVRAM/firmware area changed; reset before 'saveenv' or 'update'.").map_err(|_| CliError::UartWriteError)?;
+    }
+    Ok(())
+}
+
+// fatls <emic|sd> <partition> [path]: FAT bölümündeki bir dizini listeler
+unsafe fn fatls_command(uart: &mut Uart0, args: &[&str]) -> Result<(), CliError> {
+    if args.len() < 2 {
+        writeln!(uart, "Usage: fatls <emic|sd> <partition> [path]").map_err(|_| CliError::UartWriteError)?;
+        return Ok(());
+    }
+    let device = match crate::storage::device_by_name(args[0]) {
+        Ok(d) => d,
+        Err(e) => {
+            writeln!(uart, "Error: Device '{}' not available: {:?}", args[0], e).map_err(|_| CliError::UartWriteError)?;
+            return Err(CliError::CommandFailed);
+        }
+    };
+    let index = match args[1].parse::<usize>() {
+        Ok(i) => i,
+        Err(_) => {
+            writeln!(uart, "Error: Invalid partition index '{}'.", args[1]).map_err(|_| CliError::UartWriteError)?;
+            return Err(CliError::InvalidDataFormat);
+        }
+    };
+    let view = match partition::open_partition(device, index) {
+        Ok(v) => v,
+        Err(e) => {
+            writeln!(uart, "Error: Cannot open partition {}: {:?}", index, e).map_err(|_| CliError::UartWriteError)?;
+            return Err(CliError::CommandFailed);
+        }
+    };
+    let mut volume = match FatVolume::mount(view) {
+        Ok(v) => v,
+        Err(e) => {
+            writeln!(uart, "Error: No FAT filesystem on partition {}: {:?}", index, e).map_err(|_| CliError::UartWriteError)?;
+            return Err(CliError::CommandFailed);
+        }
+    };
+    let path = args.get(2).copied().unwrap_or("/");
+    let mut dir = match volume.open_dir(path) {
+        Ok(d) => d,
+        Err(e) => {
+            writeln!(uart, "Error: Cannot open '{}': {:?}", path, e).map_err(|_| CliError::UartWriteError)?;
+            return Err(CliError::CommandFailed);
+        }
+    };
+
+    writeln!(uart, "Volume '{}' ({:?}), directory '{}':", volume.volume_label(), volume.fat_type(), path).map_err(|_| CliError::UartWriteError)?;
+    let (mut files, mut dirs) = (0u32, 0u32);
+    loop {
+        match volume.next_entry(&mut dir) {
+            Ok(Some(entry)) => {
+                if entry.is_dir() {
+                    dirs += 1;
+                    writeln!(uart, "  {:>10}  {}/", "<DIR>", entry.name()).map_err(|_| CliError::UartWriteError)?;
+                } else {
+                    files += 1;
+                    writeln!(uart, "  {:>10}  {}", entry.size, entry.name()).map_err(|_| CliError::UartWriteError)?;
+                }
+            }
+            Ok(None) => break,
+            Err(e) => {
+                writeln!(uart, "Error: Directory read failed: {:?}", e).map_err(|_| CliError::UartWriteError)?;
+                return Err(CliError::CommandFailed);
+            }
+        }
+    }
+    writeln!(uart, "{} file(s), {} dir(s)", files, dirs).map_err(|_| CliError::UartWriteError)?;
+    Ok(())
+}
+
+// boot komutu
+// Argüman yoksa boot yöneticisi `bootdevice` ve `bootorder` hedeflerini sırayla dener.
+// Sayı verilirse SD karttaki o bölüm, "emic"/"sd" verilirse o aygıttaki aktif bölüm,
+// "sd:1/sahnebox/kernel.bin" gibi bir yol verilirse FAT bölümündeki dosya boot edilir.
+// Her durumda imaj başlığı doğrulanır. Sonraki argümanlar boşlukla birleştirilip çekirdek komut satırı
+// olarak aktarılır; verilmezse `bootargs` kullanılır.
+unsafe fn boot_command(uart: &mut Uart0, args: &[&str]) -> Result<(), CliError> {
+    writeln!(uart, "Attempting to boot...").map_err(|_| CliError::UartWriteError)?;
+
+    let mut command_line = [0u8; COMMAND_LINE_MAX_LEN];
+    let command_line = match args.get(1..) {
+        Some(extra) if !extra.is_empty() => Some(join_command_line(uart, &mut command_line, extra)?),
+        _ => None,
+    };
+
+    let error = match args.first() {
+        None => crate::bootmgr::run_boot_sequence(command_line),
+        Some(spec) => {
+            let mut buffer = [0u8; 24];
+            let target = match spec.parse::<usize>() {
+                Ok(index) => format_target(&mut buffer, "sd", index),
+                Err(_) => *spec,
+            };
+            crate::bootmgr::try_target(target, command_line)
+        }
+    };
+
+    // Buraya sadece boot başarısız olduysa gelinir.
+    if let crate::boot::BootError::InvalidBootPath = error {
+        writeln!(uart, "Usage: boot [partition_index | <emic|sd> | <emic|sd>:<partition>/<path>] [kernel command line...]").map_err(|_| CliError::UartWriteError)?;
+        return Err(CliError::InvalidDataFormat);
+    }
+    writeln!(uart, "Boot failed: {:?}", error).map_err(|_| CliError::UartWriteError)?;
+    Err(CliError::Boot(error))
+}
+
+unsafe fn loady_command(uart: &mut Uart0, args: &[&str]) -> Result<(), CliError> {
+    serial_load(uart, crate::xmodem::Protocol::Ymodem, args)
+}
+
+unsafe fn loadx_command(uart: &mut Uart0, args: &[&str]) -> Result<(), CliError> {
+    serial_load(uart, crate::xmodem::Protocol::Xmodem, args)
+}
+
+// loady/loadx: ikiliyi verilen adrese (varsayılan: kullanılabilir RAM'in başı) alır; "go" ile başlatır.
+unsafe fn serial_load(uart: &mut Uart0, protocol: crate::xmodem::Protocol, args: &[&str]) -> Result<(), CliError> {
+    let (address, rest) = match args.first() {
+        Some(&"go") | None => (crate::memory::FIRMWARE_RESERVED_END, args),
+        // Adres usize'a sığmıyorsa (32-bit hedefte > 0xFFFF_FFFF) kesmek yerine kullanım hatası verilir.
+        Some(arg) => match parse_number(arg).and_then(|address| usize::try_from(address).ok()) {
+            Some(address) => (address, &args[1..]),
+            None => {
+                writeln!(uart, "Usage: {} [address] [go [kernel command line...]]", if protocol == crate::xmodem::Protocol::Ymodem { "loady" } else { "loadx" }).map_err(|_| CliError::UartWriteError)?;
+                return Err(CliError::InvalidDataFormat);
+            }
+        },
+    };
+    let run = rest.first() == Some(&"go");
+
+    writeln!(uart, "Ready for {} transfer to {:#x}. Start the sender now, or press Ctrl-X twice to cancel.", protocol.name(), address).map_err(|_| CliError::UartWriteError)?;
+    let image = match crate::boot::load_serial(uart, protocol, address) {
+        Ok(image) => image,
+        Err(e) => {
+            writeln!(uart, "Error: Load failed: {:?}", e).map_err(|_| CliError::UartWriteError)?;
+            return Err(CliError::Boot(e));
+        }
+    };
+    writeln!(uart, "Loaded {} bytes at {:#x} (crc32 {:08x}).", image.header.payload_size, address, image.header.payload_crc32).map_err(|_| CliError::UartWriteError)?;
+    if !run {
+        return Ok(());
+    }
+
+    let mut command_line = [0u8; COMMAND_LINE_MAX_LEN];
+    let command_line = match rest.get(1..) {
+        Some(extra) if !extra.is_empty() => join_command_line(uart, &mut command_line, extra)?,
+        _ => crate::env::get("bootargs").unwrap_or(""),
+    };
+    writeln!(uart, "Starting at {:#x}...", address).map_err(|_| CliError::UartWriteError)?;
+    let error = crate::boot::run_serial_image(&image, command_line);
+    writeln!(uart, "Error: Cannot run the loaded binary: {:?}", error).map_err(|_| CliError::UartWriteError)?;
+    Err(CliError::Boot(error))
+}
+
+// Ondalık veya "0x" önekli onaltılık sayıyı ayrıştırır ('_' ayraçlarına izin verilir).
+fn parse_number(text: &str) -> Option<u64> {
+    let mut digits = [0u8; 24];
+    let mut len = 0;
+    for byte in text.bytes().filter(|&b| b != b'_') {
+        *digits.get_mut(len)? = byte;
+        len += 1;
+    }
+    let text = core::str::from_utf8(&digits[..len]).ok()?;
+    match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => text.parse().ok(),
+    }
+}
+
+// "<cihaz>:<bölüm>" hedefini arabelleğe yazar.
+fn format_target<'a>(buffer: &'a mut [u8], device: &str, partition: usize) -> &'a str {
+    let mut cursor = ByteCursor { buffer, len: 0 };
+    let _ = write!(cursor, "{}:{}", device, partition);
+    let len = cursor.len;
+    core::str::from_utf8(&cursor.buffer[..len]).unwrap_or("")
+}
+
+// Sabit arabelleğe biçimlendirilmiş yazma; sığmayan kısım kesilir.
+struct ByteCursor<'a> {
+    buffer: &'a mut [u8],
+    len: usize,
+}
+
+impl core::fmt::Write for ByteCursor<'_> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        let n = core::cmp::min(s.len(), self.buffer.len() - self.len);
+        self.buffer[self.len..self.len + n].copy_from_slice(&s.as_bytes()[..n]);
+        self.len += n;
+        Ok(())
+    }
+}
+
+// Argümanları tek boşlukla birleştirir. Sonuç arabelleğe sığmıyorsa kesmek yerine hata döner.
+fn join_args<'a>(buffer: &'a mut [u8], args: &[&str]) -> Result<&'a str, CliError> {
+    let mut len = 0;
+    for arg in args {
+        let needed = arg.len() + if len > 0 { 1 } else { 0 };
+        if len + needed > buffer.len() {
+            return Err(CliError::ArgumentsTooLong);
+        }
+        if len > 0 {
+            buffer[len] = b' ';
+            len += 1;
+        }
+        buffer[len..len + arg.len()].copy_from_slice(arg.as_bytes());
+        len += arg.len();
+    }
+    core::str::from_utf8(&buffer[..len]).map_err(|_| CliError::InvalidDataFormat)
+}
+
+// Handoff tablosundaki komut satırı 0 ile biter; bu yüzden bir bayt eksiği kadar metin taşıyabilir.
+const COMMAND_LINE_MAX_LEN: usize = crate::handoff::HANDOFF_COMMAND_LINE_LEN - 1;
+
+// Çekirdek komut satırı argümanlarını birleştirir; sığmıyorsa kullanıcıya bildirir.
+unsafe fn join_command_line<'a>(uart: &mut Uart0, buffer: &'a mut [u8], args: &[&str]) -> Result<&'a str, CliError> {
+    let max_len = buffer.len();
+    join_args(buffer, args).or_else(|e| {
+        writeln!(uart, "Error: Kernel command line is longer than {} bytes.", max_len).map_err(|_| CliError::UartWriteError)?;
+        Err(e)
+    })
+}
+
+// setenv komutu: değeri doğrulayıp RAM'deki kopyaya yazar (kalıcı olması için saveenv).
+// Değer birden fazla argümandan oluşuyorsa boşlukla birleştirilir (örn. bootargs).
+unsafe fn setenv_command(uart: &mut Uart0, args: &[&str]) -> Result<(), CliError> {
+    let name = match args.first() {
+        Some(name) => *name,
+        None => {
+            writeln!(uart, "Usage: setenv <name> [value...]").map_err(|_| CliError::UartWriteError)?;
+            return Err(CliError::InvalidDataFormat);
+        }
+    };
+    if args.len() == 1 {
+        crate::env::unset(name);
+        writeln!(uart, "{} reset to default.", name).map_err(|_| CliError::UartWriteError)?;
+        return Ok(());
+    }
+    let mut buffer = [0u8; crate::nvram::NVRAM_MAX_VALUE_LEN];
+    let result = match join_args(&mut buffer, &args[1..]) {
+        Ok(value) => crate::env::set(name, value),
+        Err(_) => Err(crate::env::EnvError::ValueTooLong(crate::nvram::NVRAM_MAX_VALUE_LEN)),
+    };
+    match result {
+        Ok(()) => Ok(()),
+        Err(crate::env::EnvError::InvalidValue(expected)) => {
+            writeln!(uart, "Error: Invalid value for '{}'. {}", name, expected).map_err(|_| CliError::UartWriteError)?;
+            Err(CliError::InvalidDataFormat)
+        }
+        Err(crate::env::EnvError::ValueTooLong(max_len)) => {
+            writeln!(uart, "Error: Value for '{}' is longer than {} bytes.", name, max_len).map_err(|_| CliError::UartWriteError)?;
+            Err(CliError::InvalidDataFormat)
+        }
+        Err(e) => {
+            writeln!(uart, "Error: Cannot set '{}': {:?}", name, e).map_err(|_| CliError::UartWriteError)?;
+            Err(CliError::CommandFailed)
+        }
+    }
+}
+
+// getenv komutu: etkin değeri (ayarlanmamışsa varsayılanı) yazdırır.
+unsafe fn getenv_command(uart: &mut Uart0, args: &[&str]) -> Result<(), CliError> {
+    let name = match args.first() {
+        Some(name) => *name,
+        None => {
+            writeln!(uart, "Usage: getenv <name>").map_err(|_| CliError::UartWriteError)?;
+            return Err(CliError::InvalidDataFormat);
+        }
+    };
+    match crate::env::get(name) {
+        Some(value) => writeln!(uart, "{}", value).map_err(|_| CliError::UartWriteError),
+        None => {
+            writeln!(uart, "Error: '{}' is not set.", name).map_err(|_| CliError::UartWriteError)?;
+            Err(CliError::CommandFailed)
+        }
+    }
+}
+
+// printenv komutu: bilinen değişkenler (tür ve varsayılan ile) ve serbest değişkenler.
+unsafe fn printenv_command(uart: &mut Uart0, _args: &[&str]) -> Result<(), CliError> {
+    let nvram = &crate::nvram::NVRAM_GLOBAL;
+    for spec in crate::env::variables() {
+        let value = crate::env::get(spec.name).unwrap_or(spec.default);
+        let origin = match nvram.get_str(spec.name) {
+            None => " (default)",
+            Some(stored) if crate::env::validate(spec, stored).is_err() => " (stored value invalid, using default)",
+            Some(_) => "",
+        };
+        writeln!(uart, "{}={}{}", spec.name, value, origin).map_err(|_| CliError::UartWriteError)?;
+        writeln!(uart, "    [{}] {}", spec.var_type.name(), spec.help).map_err(|_| CliError::UartWriteError)?;
+    }
+    for (name, value) in nvram.iter().filter(|(name, _)| crate::env::find_spec(name).is_none()) {
+        writeln!(uart, "{}={}", name, core::str::from_utf8(value).unwrap_or("<binary>")).map_err(|_| CliError::UartWriteError)?;
+    }
+    if nvram.is_dirty() {
+        writeln!(uart, "(unsaved changes, run 'saveenv')").map_err(|_| CliError::UartWriteError)?;
+    }
+    if !nvram.is_loaded() {
+        writeln!(uart, "(NVRAM not available, changes cannot be saved)").map_err(|_| CliError::UartWriteError)?;
+    }
+    Ok(())
+}
+
+// saveenv komutu
+unsafe fn saveenv_command(uart: &mut Uart0, _args: &[&str]) -> Result<(), CliError> {
+    match crate::env::save() {
+        Ok(()) => writeln!(uart, "Saved.").map_err(|_| CliError::UartWriteError),
+        Err(e) => {
+            writeln!(uart, "Error: Save failed: {:?}", e).map_err(|_| CliError::UartWriteError)?;
+            Err(CliError::CommandFailed)
+        }
+    }
+}
+
+// secureboot komutu: politikayı ve güvenilir anahtarları gösterir veya politikayı değiştirir.
+unsafe fn secureboot_command(uart: &mut Uart0, args: &[&str]) -> Result<(), CliError> {
+    if let Some(name) = args.first() {
+        match crate::secureboot::SecureBootPolicy::parse(name) {
+            Some(policy) => {
+                // `secureboot` değişkeni üzerinden ayarlanır; saveenv ile kalıcı olur.
+                if let Err(e) = crate::env::set("secureboot", policy.name()) {
+                    writeln!(uart, "Error: Cannot set 'secureboot': {:?}", e).map_err(|_| CliError::UartWriteError)?;
+                    return Err(CliError::CommandFailed);
+                }
+                writeln!(uart, "Secure boot policy set to '{}' (run 'saveenv' to keep it).", policy.name()).map_err(|_| CliError::UartWriteError)?;
+            }
+            None => {
+                writeln!(uart, "Usage: secureboot [enforce|warn|off]").map_err(|_| CliError::UartWriteError)?;
+                return Err(CliError::InvalidDataFormat);
+            }
+        }
+    }
+    writeln!(uart, "Secure boot policy: {}", crate::secureboot::get_policy().name()).map_err(|_| CliError::UartWriteError)?;
+    writeln!(uart, "Trusted keys:").map_err(|_| CliError::UartWriteError)?;
+    for key in crate::secureboot::TRUSTED_KEYS.iter() {
+        write!(uart, "  {:<3} {:<20} ", key.id, key.name).map_err(|_| CliError::UartWriteError)?;
+        if key.is_provisioned() {
+            write_hex(uart, &key.public_key[..8])?;
+            writeln!(uart, "...").map_err(|_| CliError::UartWriteError)?;
+        } else {
+            writeln!(uart, "(not provisioned)").map_err(|_| CliError::UartWriteError)?;
+        }
+    }
+    if !crate::secureboot::any_key_provisioned() {
+        writeln!(uart, "No trusted key is provisioned: signed images cannot be verified.").map_err(|_| CliError::UartWriteError)?;
+    }
+    Ok(())
+}
+
+// source komutu: FAT bölümündeki bir betiği çalıştırır (bkz. script.rs).
+unsafe fn source_command(uart: &mut Uart0, args: &[&str]) -> Result<(), CliError> {
+    use crate::script::{self, ScriptError};
+    let spec = match args {
+        [spec] => spec,
+        _ => {
+            writeln!(uart, "Usage: source <emic|sd>:<partition>/<path>").map_err(|_| CliError::UartWriteError)?;
+            return Err(CliError::InvalidDataFormat);
+        }
+    };
+    let written = match script::run(uart, spec) {
+        Ok(()) => return Ok(()),
+        Err(ScriptError::Syntax { line, error }) => writeln!(uart, "Error: {}: line {}: {:?}; nothing was run.", spec, line, error),
+        Err(ScriptError::TooLarge(size)) => writeln!(uart, "Error: {}: {} bytes, scripts are limited to {} bytes.", spec, size, script::SCRIPT_MAX_BYTES),
+        Err(e) => writeln!(uart, "Error: Cannot run '{}': {:?}", spec, e),
+    };
+    written.map_err(|_| CliError::UartWriteError)?;
+    Err(CliError::CommandFailed)
+}
+
+// echo komutu: argümanları tek boşlukla ayırarak yazar (betiklerde ilerleme mesajları için).
+unsafe fn echo_command(uart: &mut Uart0, args: &[&str]) -> Result<(), CliError> {
+    for (i, arg) in args.iter().enumerate() {
+        write!(uart, "{}{}", if i > 0 { " " } else { "" }, arg).map_err(|_| CliError::UartWriteError)?;
+    }
+    writeln!(uart).map_err(|_| CliError::UartWriteError)
+}
+
+// baud komutu: konsol hızını gösterir veya `console.baud` üzerinden değiştirir.
+// Yeni hız hemen uygulanır; kullanıcı terminalini yeni hıza alıp orada `saveenv` çalıştırır, böylece
+// yalnızca gerçekten çalışan bir hız kalıcı olur (aksi halde yeniden başlatma eski hıza döner).
+unsafe fn baud_command(uart: &mut Uart0, args: &[&str]) -> Result<(), CliError> {
+    use crate::uart::{BaudConfig, UartError, UART_MAX_BAUD_ERROR_BP};
+    let rate = match args {
+        [] => {
+            writeln!(uart, "Console: {}", uart.baud_config()).map_err(|_| CliError::UartWriteError)?;
+            writeln!(uart, "UART clock: {} Hz", uart.clock_hz()).map_err(|_| CliError::UartWriteError)?;
+            return Ok(());
+        }
+        [rate] => rate,
+        _ => return Err(CliError::TooManyArguments),
+    };
+    let baud = match rate.parse() {
+        Ok(baud) => baud,
+        Err(_) => {
+            writeln!(uart, "Usage: baud [rate]").map_err(|_| CliError::UartWriteError)?;
+            return Err(CliError::InvalidDataFormat);
+        }
+    };
+    let config = match BaudConfig::compute(uart.clock_hz(), baud) {
+        Ok(config) => config,
+        Err(e) => {
+            let written = match e {
+                UartError::InvalidBaudRate => writeln!(uart, "Error: {} baud cannot be produced from a {} Hz UART clock.", baud, uart.clock_hz()),
+                UartError::DivisorOutOfRange(divisor) => writeln!(uart, "Error: {} baud needs divisor {}, which does not fit SCALECFG.", baud, divisor),
+                UartError::BaudErrorTooHigh(error_bp) => {
+                    let sign = if error_bp < 0 { "-" } else { "+" };
+                    let error = error_bp.unsigned_abs();
+                    writeln!(uart, "Error: {} baud would be off by {}{}.{:02}% (limit {}.{:02}%).", baud, sign, error / 100, error % 100,
+                        UART_MAX_BAUD_ERROR_BP / 100, UART_MAX_BAUD_ERROR_BP % 100)
+                }
+            };
+            written.map_err(|_| CliError::UartWriteError)?;
+            return Err(CliError::InvalidDataFormat);
+        }
+    };
+    if config == uart.baud_config() {
+        writeln!(uart, "Console already at {}.", config).map_err(|_| CliError::UartWriteError)?;
+        return Ok(());
+    }
+    writeln!(uart, "Switching console to {}.", config).map_err(|_| CliError::UartWriteError)?;
+    writeln!(uart, "Reconnect at {} baud and run 'saveenv' to keep it.", baud).map_err(|_| CliError::UartWriteError)?;
+    if let Err(e) = crate::env::set("console.baud", rate) {
+        writeln!(uart, "Error: console.baud rejected '{}': {:?}", rate, e).map_err(|_| CliError::UartWriteError)?;
+        return Err(CliError::CommandFailed);
+    }
+    Ok(())
+}
+
+// bootlog komutu: ölçülü boot kaydını ve güncel PCR değerini gösterir.
+unsafe fn bootlog_command(uart: &mut Uart0, _args: &[&str]) -> Result<(), CliError> {
+    let log = &crate::measured::BOOT_LOG;
+    write!(uart, "PCR: ").map_err(|_| CliError::UartWriteError)?;
+    write_hex(uart, &log.pcr)?;
+    writeln!(uart).map_err(|_| CliError::UartWriteError)?;
+    if log.event_count == 0 {
+        writeln!(uart, "No stages measured yet.").map_err(|_| CliError::UartWriteError)?;
+        return Ok(());
+    }
+    writeln!(uart, "  #  Stage       Size       Load addr   Sig  Description").map_err(|_| CliError::UartWriteError)?;
+    for (i, event) in log.events().iter().enumerate() {
+        let stage = event.stage().map(|s| s.name()).unwrap_or("?");
+        let verified = if event.flags & crate::measured::EVENT_FLAG_SIGNATURE_VERIFIED != 0 { "ok" } else { "-" };
+        writeln!(uart, "  {:<2} {:<11} {:<10} {:#010x}  {:<4} {}", i, stage, event.size, event.load_address, verified, event.description()).map_err(|_| CliError::UartWriteError)?;
+        write!(uart, "     sha256: ").map_err(|_| CliError::UartWriteError)?;
+        write_hex(uart, &event.digest)?;
+        writeln!(uart).map_err(|_| CliError::UartWriteError)?;
+    }
+    if log.dropped_events > 0 {
+        writeln!(uart, "  ({} further event(s) extended into the PCR but not logged)", log.dropped_events).map_err(|_| CliError::UartWriteError)?;
+    }
+    Ok(())
+}
+
+// slot komutu: A/B slot durumunu gösterir veya değiştirir. Değişiklikler hemen kaydedilir.
+unsafe fn slot_command(uart: &mut Uart0, args: &[&str]) -> Result<(), CliError> {
+    use crate::slots::{self, Slot};
+    let action = args.first().copied().unwrap_or("status");
+    if action != "status" {
+        let slot = match args.get(1).and_then(|name| Slot::parse(name)) {
+            Some(slot) if args.len() == 2 => slot,
+            _ => {
+                writeln!(uart, "Usage: slot <set-active|mark-successful|mark-unbootable> <a|b>").map_err(|_| CliError::UartWriteError)?;
+                return Err(CliError::InvalidDataFormat);
+            }
+        };
+        let result = match action {
+            "set-active" => slots::set_active(slot),
+            "mark-successful" => slots::mark_successful(slot),
+            "mark-unbootable" => slots::mark_unbootable(slot),
+            _ => {
+                writeln!(uart, "Error: Unknown slot action '{}'.", action).map_err(|_| CliError::UartWriteError)?;
+                return Err(CliError::InvalidDataFormat);
+            }
+        };
+        if let Err(e) = result {
+            writeln!(uart, "Error: Cannot update slot {}: {:?}", slot.name(), e).map_err(|_| CliError::UartWriteError)?;
+            return Err(CliError::CommandFailed);
+        }
+    }
+
+    writeln!(uart, "A/B slots: {}", if slots::enabled() { "enabled" } else { "disabled (setenv slot.enabled 1)" }).map_err(|_| CliError::UartWriteError)?;
+    let next = slots::select();
+    writeln!(uart, "  Slot Source               Prio Tries OK  Bootable").map_err(|_| CliError::UartWriteError)?;
+    for slot in Slot::ALL {
+        let state = slots::state(slot);
+        let marker = if next == Some(slot) { " <- next" } else { "" };
+        writeln!(uart, "  {:<4} {:<20} {:<4} {:<5} {:<3} {}{}", slot.name(), slots::source(slot), state.priority, state.tries,
+            if state.successful { "yes" } else { "no" }, if state.is_bootable() { "yes" } else { "no" }, marker).map_err(|_| CliError::UartWriteError)?;
+    }
+    Ok(())
+}
+
+// update komutu: firmware imajını XMODEM/YMODEM ile alır ve etkin olmayan bankaya kurar.
+unsafe fn update_command(uart: &mut Uart0, args: &[&str]) -> Result<(), CliError> {
+    use crate::update;
+    use crate::xmodem::Protocol;
+    let protocol = match args.first().copied() {
+        None | Some("ymodem") => Protocol::Ymodem,
+        Some("xmodem") => Protocol::Xmodem,
+        Some("status") => {
+            let banks = match update::bank_status() {
+                Ok(banks) => banks,
+                Err(e) => {
+                    writeln!(uart, "Error: Cannot read firmware banks: {:?}", e).map_err(|_| CliError::UartWriteError)?;
+                    return Err(CliError::CommandFailed);
+                }
+            };
+            let active = update::active_bank(&banks);
+            for (i, bank) in banks.iter().enumerate() {
+                let marker = if active == Some(i as u64) { " (active)" } else { "" };
+                let written = match bank {
+                    Some(info) => writeln!(uart, "  Bank {}: sequence {}, {} bytes, crc32 {:08x}{}", i, info.sequence, info.image_size, info.image_crc32, marker),
+                    None => writeln!(uart, "  Bank {}: empty or invalid", i),
+                };
+                written.map_err(|_| CliError::UartWriteError)?;
+            }
+            return Ok(());
+        }
+        Some(_) => {
+            writeln!(uart, "Usage: update [ymodem|xmodem|status]").map_err(|_| CliError::UartWriteError)?;
+            return Err(CliError::InvalidDataFormat);
+        }
+    };
+
+    writeln!(uart, "Send the firmware image with {} (1K, CRC) now, or press Ctrl-X twice to cancel.", protocol.name()).map_err(|_| CliError::UartWriteError)?;
+    match update::receive_and_install(uart, protocol) {
+        Ok(installed) => {
+            writeln!(uart, "Firmware ({} bytes) installed in bank {} with sequence {}. Reset to run it.",
+                installed.header.payload_size, installed.bank, installed.sequence).map_err(|_| CliError::UartWriteError)?;
+            Ok(())
+        }
+        Err(e) => {
+            writeln!(uart, "Error: Firmware update failed: {:?}. The current firmware stays active.", e).map_err(|_| CliError::UartWriteError)?;
+            Err(CliError::CommandFailed)
+        }
+    }
+}
+
+fn write_hex(uart: &mut Uart0, bytes: &[u8]) -> Result<(), CliError> {
+    for byte in bytes {
+        write!(uart, "{:02x}", byte).map_err(|_| CliError::UartWriteError)?;
+    }
+    Ok(())
+}
+
+// --- Global CLI Instance ---
+// Firmware'in ana döngüsünden erişim için.
+// # Safety: Global mutable static kullanimi unsafe'dir.
+pub static mut CLI_GLOBAL: Cli = Cli::new();
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn protected_region_bounds_and_message() {
+        assert_eq!(protected_region("sd", 0, 1), Some(ProtectedRegion::PartitionTable));
+        assert_eq!(protected_region("sd", 1024, 1), None);
+        assert_eq!(protected_region("emic", 1000, 24), None);
+        assert_eq!(protected_region("emic", 1000, 25), Some(ProtectedRegion::FirmwareReserved));
+        assert_eq!(protected_region("emic", 2047, 1), Some(ProtectedRegion::FirmwareReserved));
+        assert_eq!(protected_region("emic", 2048, 8), None);
+        assert_eq!(
+            ProtectedRegion::FirmwareReserved.to_string(),
+            "the firmware-reserved area (NVRAM and firmware banks, LBA 1024-2047)"
+        );
+    }
+    #[test]
+    fn parse_hex_bytes_accepts_separators_and_prefix() {
+        let mut out = [0u8; 4];
+        assert_eq!(parse_hex_bytes("de:ad_BE:ef", &mut out), Some(4));
+        assert_eq!(out, [0xDE, 0xAD, 0xBE, 0xEF]);
+        assert_eq!(parse_hex_bytes("0x0102", &mut out), Some(2));
+        assert_eq!(&out[..2], &[0x01, 0x02]);
+    }
+
+    #[test]
+    fn parse_hex_bytes_rejects_odd_length_non_hex_and_overflow() {
+        let mut out = [0u8; 4];
+        assert_eq!(parse_hex_bytes("abc", &mut out), None);
+        assert_eq!(parse_hex_bytes("0xa", &mut out), None);
+        assert_eq!(parse_hex_bytes("zz", &mut out), None);
+        assert_eq!(parse_hex_bytes("0102030405", &mut out), None);
+    }
+}
@@ -0,0 +1,93 @@
+#![no_std]
+
+// Sağlama (checksum) algoritmaları.
+
+// CRC-32 (IEEE 802.3, yansıtılmış polinom 0xEDB88320). GPT, imaj başlıkları vb. bunu kullanır.
+const CRC32_POLY: u32 = 0xEDB8_8320;
+
+const fn make_crc32_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ CRC32_POLY } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+static CRC32_TABLE: [u32; 256] = make_crc32_table();
+
+/// Parça parça beslenebilen CRC-32 hesaplayıcı (büyük veriler blok blok okunurken).
+#[derive(Debug, Copy, Clone)]
+pub struct Crc32 {
+    state: u32,
+}
+
+impl Crc32 {
+    pub const fn new() -> Self {
+        Crc32 { state: 0xFFFF_FFFF }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        for &byte in data {
+            self.state = CRC32_TABLE[((self.state ^ byte as u32) & 0xFF) as usize] ^ (self.state >> 8);
+        }
+    }
+
+    pub fn finish(&self) -> u32 {
+        !self.state
+    }
+}
+
+/// Tek seferde CRC-32 hesaplar.
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = Crc32::new();
+    crc.update(data);
+    crc.finish()
+}
+
+// CRC-16/XMODEM (CCITT polinomu 0x1021, başlangıç 0, yansıtmasız). XMODEM/YMODEM paketleri bunu kullanır.
+const CRC16_POLY: u16 = 0x1021;
+
+/// Tek seferde CRC-16/XMODEM hesaplar.
+pub fn crc16_xmodem(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0;
+    for &byte in data {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 { (crc << 1) ^ CRC16_POLY } else { crc << 1 };
+        }
+    }
+    crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crc32_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    fn crc32_incremental_matches_one_shot() {
+        let mut crc = Crc32::new();
+        crc.update(b"1234");
+        crc.update(b"");
+        crc.update(b"56789");
+        assert_eq!(crc.finish(), crc32(b"123456789"));
+    }
+
+    #[test]
+    fn crc16_xmodem_check_value() {
+        assert_eq!(crc16_xmodem(b"123456789"), 0x31C3);
+    }
+}
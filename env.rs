@@ -0,0 +1,378 @@
+#![no_std]
+
+// Firmware ortam değişkenleri (U-Boot'taki `setenv`/`saveenv` gibi).
+// Değerler NVRAM'de metin olarak saklanır. Firmware'in bildiği değişkenlerin bir türü ve varsayılanı
+// vardır; yazılırken doğrulanır, okunurken de tekrar doğrulanır ve geçersizse (eski/bozuk kayıt)
+// varsayılan kullanılır. Böylece hatalı bir değer boot yolunu kullanılamaz hale getiremez.
+// Tabloda olmayan adlar serbest metin değişkeni olarak saklanabilir.
+
+use crate::nvram::{self, NvramError, NVRAM_GLOBAL};
+
+#[derive(Debug, Copy, Clone)]
+pub enum VarType {
+    String { max_len: usize },
+    Integer { min: i64, max: i64 },
+    Boolean,
+    Choice(&'static [&'static str]),
+}
+
+impl VarType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            VarType::String { .. } => "string",
+            VarType::Integer { .. } => "integer",
+            VarType::Boolean => "boolean",
+            VarType::Choice(_) => "choice",
+        }
+    }
+}
+
+/// Firmware'in bildiği bir değişkenin tanımı.
+pub struct VarSpec {
+    pub name: &'static str,
+    pub var_type: VarType,
+    pub default: &'static str,
+    pub help: &'static str,
+    // Türün ötesinde ek doğrulama (örn. boot kaynağı biçimi).
+    validate: Option<fn(&str) -> bool>,
+    // Değer değiştiğinde (ve açılışta) çalışma zamanı durumuna uygulanır.
+    apply: Option<unsafe fn(&str)>,
+}
+
+#[derive(Debug)]
+pub enum EnvError {
+    InvalidName,
+    InvalidValue(&'static str), // Açıklama: beklenen biçim
+    ValueTooLong(usize),        // İzin verilen en uzun değer (bayt)
+    Nvram(NvramError),
+}
+
+impl From<NvramError> for EnvError {
+    fn from(err: NvramError) -> Self { EnvError::Nvram(err) }
+}
+
+static VARIABLES: [VarSpec; 16] = [
+    VarSpec {
+        name: "bootdevice",
+        var_type: VarType::String { max_len: 64 },
+        default: "sd",
+        help: "Boot source: <emic|sd> (first bootable partition) or <emic|sd>:<partition>[/<path>]",
+        validate: Some(is_valid_boot_source),
+        apply: None,
+    },
+    VarSpec {
+        name: "bootorder",
+        var_type: VarType::String { max_len: 255 },
+        default: "sd,emic",
+        help: "Comma-separated fallback boot sources tried after bootdevice",
+        validate: Some(crate::bootmgr::is_valid_boot_order),
+        apply: None,
+    },
+    VarSpec {
+        name: "bootdelay",
+        var_type: VarType::Integer { min: -1, max: 60 },
+        default: "3",
+        help: "Autoboot countdown in seconds; -1 disables autoboot",
+        validate: None,
+        apply: None,
+    },
+    VarSpec {
+        name: "bootargs",
+        var_type: VarType::String { max_len: 255 },
+        default: "",
+        help: "Kernel command line passed in the handoff table and /chosen",
+        validate: None,
+        apply: None,
+    },
+    VarSpec {
+        name: "bootscript",
+        var_type: VarType::String { max_len: 64 },
+        default: "sd:1/boot.scr",
+        help: "Script run before autoboot, <emic|sd>:<partition>/<path>; empty disables it",
+        validate: Some(crate::script::is_valid_script_path),
+        apply: None,
+    },
+    VarSpec {
+        name: "console.baud",
+        var_type: VarType::Integer { min: 1200, max: 921_600 },
+        default: "115200",
+        help: "UART0 baud rate; must be reachable from the UART clock within 2.5%",
+        validate: Some(crate::uart::is_valid_baud),
+        apply: Some(apply_console_baud),
+    },
+    VarSpec {
+        name: "secureboot",
+        var_type: VarType::Choice(&["enforce", "warn", "off"]),
+        default: "enforce",
+        help: "Boot image signature policy",
+        validate: None,
+        apply: Some(apply_secureboot),
+    },
+    VarSpec {
+        name: "slot.enabled",
+        var_type: VarType::Boolean,
+        default: "0",
+        help: "Boot from A/B OS slots before bootdevice/bootorder",
+        validate: None,
+        apply: None,
+    },
+    VarSpec {
+        name: "slot.a.source",
+        var_type: VarType::String { max_len: 64 },
+        default: "emic:1",
+        help: "Boot source of OS slot A",
+        validate: Some(is_valid_boot_source),
+        apply: None,
+    },
+    VarSpec {
+        name: "slot.a.priority",
+        var_type: VarType::Integer { min: 0, max: 15 },
+        default: "15",
+        help: "Slot A priority; the highest bootable slot is tried first, 0 disables the slot",
+        validate: None,
+        apply: None,
+    },
+    VarSpec {
+        name: "slot.a.tries",
+        var_type: VarType::Integer { min: 0, max: 7 },
+        default: "3",
+        help: "Boot attempts left for slot A until it is marked successful",
+        validate: None,
+        apply: None,
+    },
+    VarSpec {
+        name: "slot.a.successful",
+        var_type: VarType::Boolean,
+        default: "0",
+        help: "Slot A booted successfully (set by `slot mark-successful`)",
+        validate: None,
+        apply: None,
+    },
+    VarSpec {
+        name: "slot.b.source",
+        var_type: VarType::String { max_len: 64 },
+        default: "emic:2",
+        help: "Boot source of OS slot B",
+        validate: Some(is_valid_boot_source),
+        apply: None,
+    },
+    VarSpec {
+        name: "slot.b.priority",
+        var_type: VarType::Integer { min: 0, max: 15 },
+        default: "14",
+        help: "Slot B priority; the highest bootable slot is tried first, 0 disables the slot",
+        validate: None,
+        apply: None,
+    },
+    VarSpec {
+        name: "slot.b.tries",
+        var_type: VarType::Integer { min: 0, max: 7 },
+        default: "3",
+        help: "Boot attempts left for slot B until it is marked successful",
+        validate: None,
+        apply: None,
+    },
+    VarSpec {
+        name: "slot.b.successful",
+        var_type: VarType::Boolean,
+        default: "0",
+        help: "Slot B booted successfully (set by `slot mark-successful`)",
+        validate: None,
+        apply: None,
+    },
+];
+
+/// Boot kaynağı biçimini (`boot::resolve_boot_source`) aygıta erişmeden doğrular.
+pub fn is_valid_boot_source(value: &str) -> bool {
+    let device = match value.split_once(':') {
+        Some(_) => match crate::boot::BootPath::parse(value) {
+            Ok(path) => path.device,
+            Err(_) => return false,
+        },
+        None => value,
+    };
+    device == "emic" || device == "sd"
+}
+
+unsafe fn apply_secureboot(value: &str) {
+    if let Some(policy) = crate::secureboot::SecureBootPolicy::parse(value) {
+        crate::secureboot::set_policy(policy);
+    }
+}
+
+unsafe fn apply_console_baud(value: &str) {
+    let uart = &mut crate::uart::UART0_GLOBAL;
+    match value.parse() {
+        Ok(baud) if baud != uart.baud_config().requested => {
+            log!("Console switching to {} baud.", baud);
+            if let Ok(config) = uart.set_baud(baud) {
+                log!("Console now at {}.", config);
+            }
+        }
+        _ => {}
+    }
+}
+
+pub fn variables() -> &'static [VarSpec] {
+    &VARIABLES
+}
+
+pub fn find_spec(name: &str) -> Option<&'static VarSpec> {
+    VARIABLES.iter().find(|v| v.name == name)
+}
+
+/// Boolean değerleri kabul edilen yazımlarıyla ayrıştırır.
+pub fn parse_bool(value: &str) -> Option<bool> {
+    match value {
+        "1" | "true" | "yes" | "on" => Some(true),
+        "0" | "false" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+/// Değeri tanımın türüne ve ek doğrulamasına göre kontrol eder.
+pub fn validate(spec: &VarSpec, value: &str) -> Result<(), EnvError> {
+    let ok = match spec.var_type {
+        VarType::String { max_len } if value.len() > max_len => return Err(EnvError::ValueTooLong(max_len)),
+        VarType::String { .. } => true,
+        VarType::Integer { min, max } => value.parse::<i64>().is_ok_and(|v| v >= min && v <= max),
+        VarType::Boolean => parse_bool(value).is_some(),
+        VarType::Choice(choices) => choices.contains(&value),
+    };
+    if !ok || !spec.validate.is_none_or(|f| f(value)) {
+        return Err(EnvError::InvalidValue(spec.help));
+    }
+    Ok(())
+}
+
+/// Değişkenin etkin değeri: NVRAM'deki değer geçerliyse o, değilse varsayılan.
+/// Tabloda olmayan değişkenler için NVRAM'deki değer (yoksa None).
+/// # Safety
+/// Global NVRAM'i okur.
+pub unsafe fn get(name: &str) -> Option<&'static str> {
+    let stored = NVRAM_GLOBAL.get_str(name);
+    match find_spec(name) {
+        Some(spec) => match stored {
+            Some(value) if validate(spec, value).is_ok() => Some(value),
+            _ => Some(spec.default),
+        },
+        None => stored,
+    }
+}
+
+/// Bilinen bir tamsayı değişkeninin etkin değeri.
+/// # Safety
+/// Global NVRAM'i okur.
+pub unsafe fn get_int(name: &str) -> i64 {
+    get(name).and_then(|v| v.parse().ok()).unwrap_or(0)
+}
+
+/// Bilinen bir boolean değişkeninin etkin değeri.
+/// # Safety
+/// Global NVRAM'i okur.
+pub unsafe fn get_bool(name: &str) -> bool {
+    get(name).and_then(parse_bool).unwrap_or(false)
+}
+
+/// Değeri doğrular, NVRAM'e (RAM kopyasına) yazar ve çalışma zamanına uygular.
+/// Kalıcı olması için `save` gerekir.
+/// # Safety
+/// Global NVRAM'i ve uygulanan çalışma zamanı durumunu değiştirir.
+pub unsafe fn set(name: &str, value: &str) -> Result<(), EnvError> {
+    if !nvram::is_valid_key(name) {
+        return Err(EnvError::InvalidName);
+    }
+    if value.len() > nvram::NVRAM_MAX_VALUE_LEN {
+        return Err(EnvError::ValueTooLong(nvram::NVRAM_MAX_VALUE_LEN));
+    }
+    let spec = find_spec(name);
+    if let Some(spec) = spec {
+        validate(spec, value)?;
+    }
+    NVRAM_GLOBAL.set(name, value.as_bytes())?;
+    if let Some(apply) = spec.and_then(|s| s.apply) {
+        apply(value);
+    }
+    Ok(())
+}
+
+/// Değişkeni siler; bilinen değişkenler varsayılanlarına döner.
+/// # Safety
+/// Global NVRAM'i ve uygulanan çalışma zamanı durumunu değiştirir.
+pub unsafe fn unset(name: &str) -> bool {
+    let removed = NVRAM_GLOBAL.remove(name);
+    if let Some(spec) = find_spec(name) {
+        if let Some(apply) = spec.apply {
+            apply(spec.default);
+        }
+    }
+    removed
+}
+
+/// Bilinen değişkenlerin etkin değerlerini çalışma zamanına uygular (açılışta NVRAM yüklendikten sonra).
+/// # Safety
+/// Global NVRAM'i okur ve çalışma zamanı durumunu değiştirir.
+pub unsafe fn apply_all() {
+    for spec in VARIABLES.iter() {
+        if let (Some(apply), Some(value)) = (spec.apply, get(spec.name)) {
+            apply(value);
+        }
+    }
+}
+
+/// Değişiklikleri eMMC'ye yazar.
+/// # Safety
+/// Global NVRAM'e ve eMMC'ye erişir.
+pub unsafe fn save() -> Result<(), EnvError> {
+    nvram::commit().map_err(EnvError::Nvram)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(name: &str) -> &'static VarSpec {
+        find_spec(name).unwrap()
+    }
+
+    #[test]
+    fn integer_bounds_are_inclusive() {
+        let bootdelay = spec("bootdelay");
+        assert!(validate(bootdelay, "-1").is_ok());
+        assert!(validate(bootdelay, "60").is_ok());
+        assert!(matches!(validate(bootdelay, "-2"), Err(EnvError::InvalidValue(_))));
+        assert!(matches!(validate(bootdelay, "61"), Err(EnvError::InvalidValue(_))));
+        assert!(matches!(validate(bootdelay, "3s"), Err(EnvError::InvalidValue(_))));
+    }
+
+    #[test]
+    fn choice_accepts_only_listed_values() {
+        let secureboot = spec("secureboot");
+        assert!(validate(secureboot, "warn").is_ok());
+        assert!(matches!(validate(secureboot, "WARN"), Err(EnvError::InvalidValue(_))));
+        assert!(matches!(validate(secureboot, ""), Err(EnvError::InvalidValue(_))));
+    }
+
+    #[test]
+    fn string_longer_than_max_len_is_too_long() {
+        let bootdevice = spec("bootdevice");
+        let VarType::String { max_len } = bootdevice.var_type else { panic!("bootdevice is a string") };
+        let long = format!("sd:1/{}", "k".repeat(max_len));
+        assert!(matches!(validate(bootdevice, &long), Err(EnvError::ValueTooLong(64))));
+        assert!(validate(bootdevice, &long[..max_len]).is_ok());
+    }
+
+    #[test]
+    fn invalid_stored_value_reads_as_default() {
+        // Global NVRAM'e dokunan tek test; diğer testler yalnızca `validate` kullanır.
+        unsafe {
+            NVRAM_GLOBAL.set("bootdelay", b"61").unwrap();
+            assert_eq!(get("bootdelay"), Some("3"));
+            assert_eq!(get_int("bootdelay"), 3);
+            NVRAM_GLOBAL.set("bootdelay", b"-1").unwrap();
+            assert_eq!(get_int("bootdelay"), -1);
+            NVRAM_GLOBAL.remove("bootdelay");
+            assert_eq!(get("bootdelay"), Some("3"));
+        }
+    }
+}
@@ -0,0 +1,882 @@
+#![no_std]
+
+// FAT12/16/32 salt okunur dosya sistemi sürücüsü.
+// `BlockDevice` üzerine kurulur (genellikle bir `PartitionView`), heap kullanmaz.
+// Dizinler uzun dosya adlarıyla (LFN) birlikte gezilebilir, dosyalar parça parça okunabilir.
+// Sadece 512 baytlık sektörler desteklenir (BLOCK_SIZE).
+
+use crate::storage::{BlockDevice, StorageError, BLOCK_SIZE};
+
+// Dizin girdisi boyutu
+pub const DIR_ENTRY_SIZE: usize = 32;
+// UTF-8'e çevrilmiş dosya adı için en fazla bayt sayısı; sığmayan uzun adlarda 8.3 adı kullanılır.
+pub const MAX_NAME_BYTES: usize = 255;
+// LFN en fazla 255 UCS-2 karakter olabilir (20 girdi x 13 karakter = 260 yer).
+const LFN_MAX_UNITS: usize = 260;
+const LFN_CHARS_PER_ENTRY: usize = 13;
+
+// Dizin girdisi öznitelikleri
+pub const ATTR_READ_ONLY: u8 = 0x01;
+pub const ATTR_HIDDEN: u8 = 0x02;
+pub const ATTR_SYSTEM: u8 = 0x04;
+pub const ATTR_VOLUME_ID: u8 = 0x08;
+pub const ATTR_DIRECTORY: u8 = 0x10;
+pub const ATTR_ARCHIVE: u8 = 0x20;
+pub const ATTR_LONG_NAME: u8 = ATTR_READ_ONLY | ATTR_HIDDEN | ATTR_SYSTEM | ATTR_VOLUME_ID;
+
+const ENTRY_END: u8 = 0x00;
+const ENTRY_DELETED: u8 = 0xE5;
+const LFN_LAST_FLAG: u8 = 0x40;
+
+#[derive(Debug)]
+pub enum FatError {
+    Storage(StorageError),
+    InvalidBootSector,    // BPB geçersiz veya desteklenmeyen sektör boyutu
+    CorruptClusterChain,  // FAT zinciri aralık dışı küme veya döngü içeriyor
+    NotFound,
+    NotADirectory,
+    NotAFile,
+    InvalidPath,
+}
+
+impl From<StorageError> for FatError {
+    fn from(err: StorageError) -> Self { FatError::Storage(err) }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum FatType {
+    Fat12,
+    Fat16,
+    Fat32,
+}
+
+/// Bir dizin girdisi (dosya veya alt dizin).
+#[derive(Clone)]
+pub struct DirEntry {
+    name: [u8; MAX_NAME_BYTES],
+    name_len: usize,
+    pub short_name: [u8; 11],
+    pub attributes: u8,
+    pub first_cluster: u32,
+    pub size: u32,
+}
+
+impl DirEntry {
+    /// Uzun ad varsa uzun ad, yoksa "NAME.EXT" biçimindeki 8.3 adı.
+    pub fn name(&self) -> &str {
+        core::str::from_utf8(&self.name[..self.name_len]).unwrap_or("?")
+    }
+
+    pub fn is_dir(&self) -> bool {
+        self.attributes & ATTR_DIRECTORY != 0
+    }
+
+    /// FAT'in büyük/küçük harf duyarsız ad eşleştirmesi; hem uzun hem 8.3 ad kontrol edilir.
+    pub fn matches(&self, name: &str) -> bool {
+        if self.name().eq_ignore_ascii_case(name) {
+            return true;
+        }
+        let mut short = [0u8; 12];
+        let len = format_short_name(&self.short_name, &mut short);
+        core::str::from_utf8(&short[..len]).map(|s| s.eq_ignore_ascii_case(name)).unwrap_or(false)
+    }
+}
+
+// "NAME    EXT" -> "NAME.EXT"
+fn format_short_name(raw: &[u8; 11], out: &mut [u8; 12]) -> usize {
+    let mut len = 0;
+    for &c in raw[0..8].iter().take_while(|&&c| c != b' ') {
+        out[len] = c;
+        len += 1;
+    }
+    if raw[8] != b' ' {
+        out[len] = b'.';
+        len += 1;
+        for &c in raw[8..11].iter().take_while(|&&c| c != b' ') {
+            out[len] = c;
+            len += 1;
+        }
+    }
+    // İlk baytı 0x05 olan adlar aslında 0xE5 ile başlar (silinmiş işaretiyle çakışmasın diye).
+    if len > 0 && out[0] == 0x05 {
+        out[0] = 0xE5;
+    }
+    len
+}
+
+fn short_name_checksum(raw: &[u8; 11]) -> u8 {
+    raw.iter().fold(0u8, |sum, &c| ((sum & 1) << 7).wrapping_add(sum >> 1).wrapping_add(c))
+}
+
+#[derive(Copy, Clone)]
+enum DirStart {
+    FixedRoot,     // FAT12/16 kök dizini (sabit bölge)
+    Cluster(u32),  // Küme zinciri olarak saklanan dizin
+}
+
+/// Açık bir dizin üzerindeki okuma konumu.
+#[derive(Copy, Clone)]
+pub struct Directory {
+    start: DirStart,
+    cluster: u32,
+    offset: u32, // Sabit kökte dizin başından, küme dizinlerinde mevcut küme başından bayt ofseti
+    clusters_walked: u32,
+    finished: bool,
+}
+
+/// Açık bir dosya ve okuma konumu.
+#[derive(Copy, Clone)]
+pub struct File {
+    first_cluster: u32,
+    size: u32,
+    position: u32,
+    current_cluster: u32,
+    current_cluster_index: u32, // current_cluster, dosyanın kaçıncı kümesi
+}
+
+impl File {
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    pub fn position(&self) -> u32 {
+        self.position
+    }
+}
+
+// Tek sektörlük önbellek: FAT ve dizin okumalarında aynı sektörün tekrar okunmasını önler.
+struct SectorCache {
+    lba: Option<u64>,
+    data: [u8; BLOCK_SIZE],
+}
+
+/// Bağlanmış (mount edilmiş) bir FAT birimi.
+pub struct FatVolume<D: BlockDevice> {
+    device: D,
+    fat_type: FatType,
+    sectors_per_cluster: u32,
+    reserved_sectors: u32,
+    root_entry_count: u32,
+    first_root_dir_sector: u32,
+    first_data_sector: u32,
+    cluster_count: u32,
+    root_cluster: u32,
+    volume_label: [u8; 11],
+    cache: SectorCache,
+}
+
+fn le_u16(b: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([b[offset], b[offset + 1]])
+}
+
+fn le_u32(b: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([b[offset], b[offset + 1], b[offset + 2], b[offset + 3]])
+}
+
+impl<D: BlockDevice> FatVolume<D> {
+    /// Aygıtın ilk sektöründeki BPB'yi okuyup birimi bağlar.
+    pub fn mount(mut device: D) -> Result<Self, FatError> {
+        let mut bs = [0u8; BLOCK_SIZE];
+        device.read_block(0, &mut bs)?;
+
+        if bs[510] != 0x55 || bs[511] != 0xAA || !(bs[0] == 0xEB || bs[0] == 0xE9) {
+            return Err(FatError::InvalidBootSector);
+        }
+        let bytes_per_sector = le_u16(&bs, 11) as usize;
+        let sectors_per_cluster = bs[13] as u32;
+        let reserved_sectors = le_u16(&bs, 14) as u32;
+        let num_fats = bs[16] as u32;
+        let root_entry_count = le_u16(&bs, 17) as u32;
+        let total_sectors_16 = le_u16(&bs, 19) as u32;
+        let fat_size_16 = le_u16(&bs, 22) as u32;
+        let total_sectors_32 = le_u32(&bs, 32);
+        let fat_size_32 = le_u32(&bs, 36);
+
+        if bytes_per_sector != BLOCK_SIZE
+            || sectors_per_cluster == 0
+            || !sectors_per_cluster.is_power_of_two()
+            || reserved_sectors == 0
+            || num_fats == 0
+        {
+            return Err(FatError::InvalidBootSector);
+        }
+
+        let sectors_per_fat = if fat_size_16 != 0 { fat_size_16 } else { fat_size_32 };
+        let total_sectors = if total_sectors_16 != 0 { total_sectors_16 } else { total_sectors_32 };
+        let root_dir_sectors = (root_entry_count * DIR_ENTRY_SIZE as u32).div_ceil(BLOCK_SIZE as u32);
+        // BPB alanları güvenilmez; bölge sınırları taşarsa birim geçersizdir.
+        let first_root_dir_sector = num_fats
+            .checked_mul(sectors_per_fat)
+            .and_then(|fat_sectors| fat_sectors.checked_add(reserved_sectors))
+            .ok_or(FatError::InvalidBootSector)?;
+        let first_data_sector = first_root_dir_sector.checked_add(root_dir_sectors).ok_or(FatError::InvalidBootSector)?;
+        let cluster_count = total_sectors
+            .checked_sub(first_data_sector)
+            .map(|data_sectors| data_sectors / sectors_per_cluster)
+            .ok_or(FatError::InvalidBootSector)?;
+        if sectors_per_fat == 0 || cluster_count == 0 {
+            return Err(FatError::InvalidBootSector);
+        }
+
+        // FAT türü sadece küme sayısına göre belirlenir (Microsoft FAT spesifikasyonu).
+        let fat_type = if cluster_count < 4085 {
+            FatType::Fat12
+        } else if cluster_count < 65525 {
+            FatType::Fat16
+        } else {
+            FatType::Fat32
+        };
+
+        let (root_cluster, label_offset) = match fat_type {
+            FatType::Fat32 => (le_u32(&bs, 44), 71),
+            _ => (0, 43),
+        };
+        if fat_type == FatType::Fat32 && root_cluster < 2 {
+            return Err(FatError::InvalidBootSector);
+        }
+        let mut volume_label = [b' '; 11];
+        volume_label.copy_from_slice(&bs[label_offset..label_offset + 11]);
+
+        Ok(FatVolume {
+            device,
+            fat_type,
+            sectors_per_cluster,
+            reserved_sectors,
+            root_entry_count,
+            first_root_dir_sector,
+            first_data_sector,
+            cluster_count,
+            root_cluster,
+            volume_label,
+            cache: SectorCache { lba: None, data: [0u8; BLOCK_SIZE] },
+        })
+    }
+
+    pub fn fat_type(&self) -> FatType {
+        self.fat_type
+    }
+
+    /// Birim etiketi (sondaki boşluklar kırpılmış).
+    pub fn volume_label(&self) -> &str {
+        core::str::from_utf8(&self.volume_label).unwrap_or("").trim_end()
+    }
+
+    pub fn cluster_size(&self) -> u32 {
+        self.sectors_per_cluster * BLOCK_SIZE as u32
+    }
+
+    /// Alttaki blok aygıtını geri verir.
+    pub fn into_device(self) -> D {
+        self.device
+    }
+
+    // --- Düşük seviye okuma ---
+
+    fn read_sector(&mut self, lba: u64) -> Result<&[u8; BLOCK_SIZE], FatError> {
+        if self.cache.lba != Some(lba) {
+            self.cache.lba = None;
+            self.device.read_block(lba, &mut self.cache.data)?;
+            self.cache.lba = Some(lba);
+        }
+        Ok(&self.cache.data)
+    }
+
+    fn cluster_to_lba(&self, cluster: u32) -> u64 {
+        (self.first_data_sector + (cluster - 2) * self.sectors_per_cluster) as u64
+    }
+
+    fn is_valid_cluster(&self, cluster: u32) -> bool {
+        cluster >= 2 && cluster < self.cluster_count + 2
+    }
+
+    fn is_end_of_chain(&self, value: u32) -> bool {
+        match self.fat_type {
+            FatType::Fat12 => value >= 0x0FF8,
+            FatType::Fat16 => value >= 0xFFF8,
+            FatType::Fat32 => value >= 0x0FFF_FFF8,
+        }
+    }
+
+    // Birinci FAT kopyasından bir kümenin değerini okur.
+    fn fat_entry(&mut self, cluster: u32) -> Result<u32, FatError> {
+        let fat_start = self.reserved_sectors as u64;
+        let cluster = cluster as u64;
+        let byte_offset = match self.fat_type {
+            FatType::Fat12 => cluster + cluster / 2,
+            FatType::Fat16 => cluster * 2,
+            FatType::Fat32 => cluster * 4,
+        };
+        let lba = fat_start + byte_offset / BLOCK_SIZE as u64;
+        let offset = (byte_offset % BLOCK_SIZE as u64) as usize;
+
+        Ok(match self.fat_type {
+            FatType::Fat12 => {
+                // FAT12 girdisi iki sektör arasında bölünmüş olabilir.
+                let lo = self.read_sector(lba)?[offset];
+                let hi = if offset + 1 < BLOCK_SIZE { self.read_sector(lba)?[offset + 1] } else { self.read_sector(lba + 1)?[0] };
+                let pair = u16::from_le_bytes([lo, hi]) as u32;
+                if cluster & 1 == 0 { pair & 0x0FFF } else { pair >> 4 }
+            }
+            FatType::Fat16 => le_u16(self.read_sector(lba)?, offset) as u32,
+            FatType::Fat32 => le_u32(self.read_sector(lba)?, offset) & 0x0FFF_FFFF,
+        })
+    }
+
+    // Zincirdeki sonraki kümeyi döndürür; zincir bittiyse None.
+    fn next_cluster(&mut self, cluster: u32) -> Result<Option<u32>, FatError> {
+        let value = self.fat_entry(cluster)?;
+        if self.is_end_of_chain(value) {
+            return Ok(None);
+        }
+        if !self.is_valid_cluster(value) {
+            return Err(FatError::CorruptClusterChain);
+        }
+        Ok(Some(value))
+    }
+
+    // --- Dizinler ---
+
+    /// Kök dizini açar.
+    pub fn root_dir(&self) -> Directory {
+        let start = match self.fat_type {
+            FatType::Fat32 => DirStart::Cluster(self.root_cluster),
+            _ => DirStart::FixedRoot,
+        };
+        Directory::new(start)
+    }
+
+    /// Yol ile verilen dizini açar ("/" veya "" kök dizindir).
+    pub fn open_dir(&mut self, path: &str) -> Result<Directory, FatError> {
+        match self.resolve(path)? {
+            None => Ok(self.root_dir()),
+            Some(entry) if entry.is_dir() => Ok(self.dir_of(&entry)),
+            Some(_) => Err(FatError::NotADirectory),
+        }
+    }
+
+    fn dir_of(&self, entry: &DirEntry) -> Directory {
+        // ".." girdisinde küme 0, kök dizin anlamına gelir.
+        if entry.first_cluster == 0 { self.root_dir() } else { Directory::new(DirStart::Cluster(entry.first_cluster)) }
+    }
+
+    // Dizindeki sonraki 32 baytlık ham girdiyi okur. Dizin sonunda None döner.
+    fn next_raw_entry(&mut self, dir: &mut Directory) -> Result<Option<[u8; DIR_ENTRY_SIZE]>, FatError> {
+        if dir.finished {
+            return Ok(None);
+        }
+        let lba = match dir.start {
+            DirStart::FixedRoot => {
+                if dir.offset >= self.root_entry_count * DIR_ENTRY_SIZE as u32 {
+                    dir.finished = true;
+                    return Ok(None);
+                }
+                (self.first_root_dir_sector + dir.offset / BLOCK_SIZE as u32) as u64
+            }
+            DirStart::Cluster(_) => {
+                if dir.offset >= self.cluster_size() {
+                    match self.next_cluster(dir.cluster)? {
+                        Some(next) => {
+                            dir.clusters_walked += 1;
+                            if dir.clusters_walked > self.cluster_count {
+                                return Err(FatError::CorruptClusterChain);
+                            }
+                            dir.cluster = next;
+                            dir.offset = 0;
+                        }
+                        None => {
+                            dir.finished = true;
+                            return Ok(None);
+                        }
+                    }
+                }
+                if !self.is_valid_cluster(dir.cluster) {
+                    return Err(FatError::CorruptClusterChain);
+                }
+                self.cluster_to_lba(dir.cluster) + (dir.offset / BLOCK_SIZE as u32) as u64
+            }
+        };
+        let in_sector = (dir.offset % BLOCK_SIZE as u32) as usize;
+        let mut raw = [0u8; DIR_ENTRY_SIZE];
+        raw.copy_from_slice(&self.read_sector(lba)?[in_sector..in_sector + DIR_ENTRY_SIZE]);
+        dir.offset += DIR_ENTRY_SIZE as u32;
+
+        if raw[0] == ENTRY_END {
+            dir.finished = true;
+            return Ok(None);
+        }
+        Ok(Some(raw))
+    }
+
+    /// Dizindeki sonraki geçerli girdiyi döndürür (silinmiş girdiler ve birim etiketi atlanır).
+    /// Uzun ad girdileri (LFN) birleştirilip ilgili kısa girdiye eklenir.
+    pub fn next_entry(&mut self, dir: &mut Directory) -> Result<Option<DirEntry>, FatError> {
+        let mut lfn = [0u16; LFN_MAX_UNITS];
+        let mut lfn_checksum: Option<u8> = None;
+        let mut lfn_expected_seq = 0u8;
+
+        while let Some(raw) = self.next_raw_entry(dir)? {
+            if raw[0] == ENTRY_DELETED {
+                lfn_checksum = None;
+                continue;
+            }
+            let attributes = raw[11];
+
+            if attributes & ATTR_LONG_NAME == ATTR_LONG_NAME {
+                // LFN girdileri sondan başa doğru gelir; ilkinde LAST bayrağı vardır.
+                let seq = raw[0] & 0x1F;
+                if raw[0] & LFN_LAST_FLAG != 0 {
+                    lfn = [0u16; LFN_MAX_UNITS];
+                    lfn_checksum = Some(raw[13]);
+                    lfn_expected_seq = seq;
+                }
+                if lfn_checksum != Some(raw[13]) || seq == 0 || seq != lfn_expected_seq || seq as usize * LFN_CHARS_PER_ENTRY > LFN_MAX_UNITS {
+                    lfn_checksum = None;
+                    continue;
+                }
+                let base = (seq as usize - 1) * LFN_CHARS_PER_ENTRY;
+                let positions = [1usize, 3, 5, 7, 9, 14, 16, 18, 20, 22, 24, 28, 30];
+                for (i, &p) in positions.iter().enumerate() {
+                    lfn[base + i] = le_u16(&raw, p);
+                }
+                lfn_expected_seq = seq - 1;
+                continue;
+            }
+
+            if attributes & ATTR_VOLUME_ID != 0 {
+                lfn_checksum = None;
+                continue;
+            }
+
+            let mut short_name = [0u8; 11];
+            short_name.copy_from_slice(&raw[0..11]);
+            let first_cluster = match self.fat_type {
+                FatType::Fat32 => ((le_u16(&raw, 20) as u32) << 16) | le_u16(&raw, 26) as u32,
+                _ => le_u16(&raw, 26) as u32,
+            };
+            let mut entry = DirEntry {
+                name: [0u8; MAX_NAME_BYTES],
+                name_len: 0,
+                short_name,
+                attributes,
+                first_cluster,
+                size: le_u32(&raw, 28),
+            };
+
+            // LFN tamamlanmış ve bu kısa girdiye aitse onu kullan, yoksa 8.3 adına düş.
+            let lfn_valid = lfn_checksum == Some(short_name_checksum(&short_name)) && lfn_expected_seq == 0;
+            if !(lfn_valid && encode_lfn(&lfn, &mut entry.name, &mut entry.name_len)) {
+                let mut short = [0u8; 12];
+                let len = format_short_name(&short_name, &mut short);
+                entry.name[..len].copy_from_slice(&short[..len]);
+                entry.name_len = len;
+            }
+            return Ok(Some(entry));
+        }
+        Ok(None)
+    }
+
+    /// Dizinde ada göre girdi arar.
+    pub fn find_in(&mut self, dir: &mut Directory, name: &str) -> Result<DirEntry, FatError> {
+        while let Some(entry) = self.next_entry(dir)? {
+            if entry.matches(name) {
+                return Ok(entry);
+            }
+        }
+        Err(FatError::NotFound)
+    }
+
+    // Yolu çözer. Kök dizin için None, diğer her şey için ilgili girdi döner.
+    fn resolve(&mut self, path: &str) -> Result<Option<DirEntry>, FatError> {
+        let mut current: Option<DirEntry> = None;
+        for component in path.split('/').filter(|c| !c.is_empty()) {
+            if component.len() > MAX_NAME_BYTES {
+                return Err(FatError::InvalidPath);
+            }
+            let mut dir = match &current {
+                None => self.root_dir(),
+                Some(entry) if entry.is_dir() => self.dir_of(entry),
+                Some(_) => return Err(FatError::NotADirectory),
+            };
+            current = Some(self.find_in(&mut dir, component)?);
+        }
+        Ok(current)
+    }
+
+    /// Yol ile verilen girdiyi (dosya veya dizin) bulur.
+    pub fn stat(&mut self, path: &str) -> Result<DirEntry, FatError> {
+        self.resolve(path)?.ok_or(FatError::NotAFile)
+    }
+
+    // --- Dosyalar ---
+
+    /// Yol ile verilen dosyayı okumak için açar.
+    pub fn open(&mut self, path: &str) -> Result<File, FatError> {
+        let entry = self.stat(path)?;
+        if entry.is_dir() {
+            return Err(FatError::NotAFile);
+        }
+        Ok(File {
+            first_cluster: entry.first_cluster,
+            size: entry.size,
+            position: 0,
+            current_cluster: entry.first_cluster,
+            current_cluster_index: 0,
+        })
+    }
+
+    /// Okuma konumunu değiştirir. Dosya sonundan ileri konumlar dosya sonuna kısıtlanır.
+    pub fn seek(&mut self, file: &mut File, position: u32) {
+        file.position = core::cmp::min(position, file.size);
+    }
+
+    /// Dosyanın mevcut konumundan `buffer`'a veri okur; okunan bayt sayısını döndürür.
+    /// Dosya sonunda 0 döner.
+    pub fn read(&mut self, file: &mut File, buffer: &mut [u8]) -> Result<usize, FatError> {
+        let cluster_size = self.cluster_size();
+        let mut done = 0usize;
+
+        while done < buffer.len() && file.position < file.size {
+            // Konumun bulunduğu kümeye git. Geri sarılmışsa zincirin başından başla.
+            let target_index = file.position / cluster_size;
+            if target_index < file.current_cluster_index {
+                file.current_cluster = file.first_cluster;
+                file.current_cluster_index = 0;
+            }
+            while file.current_cluster_index < target_index {
+                file.current_cluster = self.next_cluster(file.current_cluster)?.ok_or(FatError::CorruptClusterChain)?;
+                file.current_cluster_index += 1;
+            }
+            if !self.is_valid_cluster(file.current_cluster) {
+                return Err(FatError::CorruptClusterChain);
+            }
+
+            let in_cluster = file.position % cluster_size;
+            let lba = self.cluster_to_lba(file.current_cluster) + (in_cluster / BLOCK_SIZE as u32) as u64;
+            let in_sector = (in_cluster % BLOCK_SIZE as u32) as usize;
+            let chunk = core::cmp::min(
+                core::cmp::min(BLOCK_SIZE - in_sector, buffer.len() - done),
+                (file.size - file.position) as usize,
+            );
+            let sector = self.read_sector(lba)?;
+            buffer[done..done + chunk].copy_from_slice(&sector[in_sector..in_sector + chunk]);
+            done += chunk;
+            file.position += chunk as u32;
+        }
+        Ok(done)
+    }
+}
+
+impl Directory {
+    fn new(start: DirStart) -> Self {
+        let cluster = match start {
+            DirStart::Cluster(c) => c,
+            DirStart::FixedRoot => 0,
+        };
+        Directory { start, cluster, offset: 0, clusters_walked: 0, finished: false }
+    }
+}
+
+// UCS-2 uzun adı UTF-8'e çevirir. Ad sığmazsa false döner (çağıran 8.3 adına düşer).
+fn encode_lfn(units: &[u16; LFN_MAX_UNITS], out: &mut [u8; MAX_NAME_BYTES], out_len: &mut usize) -> bool {
+    let mut len = 0;
+    let chars = units.iter().copied().take_while(|&u| u != 0x0000 && u != 0xFFFF);
+    for c in core::char::decode_utf16(chars) {
+        let c = c.unwrap_or(core::char::REPLACEMENT_CHARACTER);
+        if len + c.len_utf8() > MAX_NAME_BYTES {
+            return false;
+        }
+        c.encode_utf8(&mut out[len..]);
+        len += c.len_utf8();
+    }
+    *out_len = len;
+    len > 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::RamDisk;
+
+    // Testler için en küçük FAT biçimlendiricisi: küme başına bir sektör, iki FAT kopyası.
+    struct Image {
+        data: Vec<u8>,
+        fat_type: FatType,
+        reserved: u32,
+        fat_sectors: u32,
+        root_dir_sector: u32,
+        data_sector: u32,
+    }
+
+    impl Image {
+        fn format(fat_type: FatType, total_sectors: u32) -> Self {
+            let (reserved, root_entries, half_bytes_per_entry) = match fat_type {
+                FatType::Fat12 => (1u32, 512u32, 3u32),
+                FatType::Fat16 => (1, 512, 4),
+                FatType::Fat32 => (32, 0, 8),
+            };
+            let fat_sectors = ((total_sectors + 2) * half_bytes_per_entry / 2).div_ceil(BLOCK_SIZE as u32);
+            let root_dir_sector = reserved + 2 * fat_sectors;
+            let data_sector = root_dir_sector + root_entries * DIR_ENTRY_SIZE as u32 / BLOCK_SIZE as u32;
+
+            let mut data = vec![0u8; total_sectors as usize * BLOCK_SIZE];
+            let bs = &mut data[..BLOCK_SIZE];
+            bs[0..3].copy_from_slice(&[0xEB, 0x3C, 0x90]);
+            bs[11..13].copy_from_slice(&(BLOCK_SIZE as u16).to_le_bytes());
+            bs[13] = 1;
+            bs[14..16].copy_from_slice(&(reserved as u16).to_le_bytes());
+            bs[16] = 2;
+            bs[17..19].copy_from_slice(&(root_entries as u16).to_le_bytes());
+            bs[21] = 0xF8;
+            let label_offset = if fat_type == FatType::Fat32 {
+                bs[32..36].copy_from_slice(&total_sectors.to_le_bytes());
+                bs[36..40].copy_from_slice(&fat_sectors.to_le_bytes());
+                bs[44..48].copy_from_slice(&2u32.to_le_bytes()); // kök dizin kümesi
+                71
+            } else {
+                bs[19..21].copy_from_slice(&(total_sectors as u16).to_le_bytes());
+                bs[22..24].copy_from_slice(&(fat_sectors as u16).to_le_bytes());
+                43
+            };
+            bs[label_offset..label_offset + 11].copy_from_slice(b"PACKETBOX  ");
+            bs[510] = 0x55;
+            bs[511] = 0xAA;
+
+            let mut image = Image { data, fat_type, reserved, fat_sectors, root_dir_sector, data_sector };
+            image.set_fat(0, 0x0FFF_FFF8);
+            image.set_fat(1, image.eoc());
+            if fat_type == FatType::Fat32 {
+                image.set_fat(2, image.eoc());
+            }
+            image
+        }
+
+        fn eoc(&self) -> u32 {
+            match self.fat_type {
+                FatType::Fat12 => 0x0FFF,
+                FatType::Fat16 => 0xFFFF,
+                FatType::Fat32 => 0x0FFF_FFFF,
+            }
+        }
+
+        fn set_fat(&mut self, cluster: u32, value: u32) {
+            for copy in 0..2 {
+                let fat = (self.reserved + copy * self.fat_sectors) as usize * BLOCK_SIZE;
+                let cluster = cluster as usize;
+                match self.fat_type {
+                    FatType::Fat12 => {
+                        let offset = fat + cluster + cluster / 2;
+                        let pair = le_u16(&self.data, offset);
+                        let value = value as u16 & 0x0FFF;
+                        let pair = if cluster & 1 == 0 { (pair & 0xF000) | value } else { (pair & 0x000F) | (value << 4) };
+                        self.data[offset..offset + 2].copy_from_slice(&pair.to_le_bytes());
+                    }
+                    FatType::Fat16 => self.data[fat + cluster * 2..][..2].copy_from_slice(&(value as u16).to_le_bytes()),
+                    FatType::Fat32 => self.data[fat + cluster * 4..][..4].copy_from_slice(&value.to_le_bytes()),
+                }
+            }
+        }
+
+        fn cluster_offset(&self, cluster: u32) -> usize {
+            (self.data_sector + cluster - 2) as usize * BLOCK_SIZE
+        }
+
+        // Kümeleri verilen sırayla zincirler ve içeriği bu kümelere yazar.
+        fn write_chain(&mut self, clusters: &[u32], content: &[u8]) {
+            for pair in clusters.windows(2) {
+                self.set_fat(pair[0], pair[1]);
+            }
+            self.set_fat(*clusters.last().unwrap(), self.eoc());
+            for (chunk, &cluster) in content.chunks(BLOCK_SIZE).zip(clusters) {
+                let offset = self.cluster_offset(cluster);
+                self.data[offset..offset + chunk.len()].copy_from_slice(chunk);
+            }
+        }
+
+        // `dir` None ise kök dizin (FAT12/16'da sabit bölge, FAT32'de küme 2).
+        fn put_entries(&mut self, dir: Option<u32>, first_slot: usize, entries: &[[u8; DIR_ENTRY_SIZE]]) {
+            let base = match (dir, self.fat_type) {
+                (Some(cluster), _) => self.cluster_offset(cluster),
+                (None, FatType::Fat32) => self.cluster_offset(2),
+                (None, _) => self.root_dir_sector as usize * BLOCK_SIZE,
+            };
+            for (i, entry) in entries.iter().enumerate() {
+                let offset = base + (first_slot + i) * DIR_ENTRY_SIZE;
+                self.data[offset..offset + DIR_ENTRY_SIZE].copy_from_slice(entry);
+            }
+        }
+
+        fn mount(&mut self) -> Result<FatVolume<RamDisk<'_>>, FatError> {
+            FatVolume::mount(RamDisk::new(&mut self.data))
+        }
+    }
+
+    fn short_entry(name: &[u8; 11], attributes: u8, cluster: u32, size: u32) -> [u8; DIR_ENTRY_SIZE] {
+        let mut raw = [0u8; DIR_ENTRY_SIZE];
+        raw[0..11].copy_from_slice(name);
+        raw[11] = attributes;
+        raw[20..22].copy_from_slice(&((cluster >> 16) as u16).to_le_bytes());
+        raw[26..28].copy_from_slice(&(cluster as u16).to_le_bytes());
+        raw[28..32].copy_from_slice(&size.to_le_bytes());
+        raw
+    }
+
+    // Uzun ad girdileri, diskteki sırayla (sondan başa).
+    fn lfn_entries(long_name: &str, short_name: &[u8; 11]) -> Vec<[u8; DIR_ENTRY_SIZE]> {
+        let units: Vec<u16> = long_name.encode_utf16().collect();
+        let count = units.len().div_ceil(LFN_CHARS_PER_ENTRY);
+        let positions = [1usize, 3, 5, 7, 9, 14, 16, 18, 20, 22, 24, 28, 30];
+        (1..=count).rev().map(|seq| {
+            let mut raw = [0u8; DIR_ENTRY_SIZE];
+            raw[0] = seq as u8 | if seq == count { LFN_LAST_FLAG } else { 0 };
+            raw[11] = ATTR_LONG_NAME;
+            raw[13] = short_name_checksum(short_name);
+            for (i, &p) in positions.iter().enumerate() {
+                let index = (seq - 1) * LFN_CHARS_PER_ENTRY + i;
+                let unit = match index.cmp(&units.len()) {
+                    core::cmp::Ordering::Less => units[index],
+                    core::cmp::Ordering::Equal => 0x0000,
+                    core::cmp::Ordering::Greater => 0xFFFF,
+                };
+                raw[p..p + 2].copy_from_slice(&unit.to_le_bytes());
+            }
+            raw
+        }).collect()
+    }
+
+    const LONG_NAME: &str = "Kernel command line.txt";
+    const LONG_CONTENT: &[u8] = b"console=ttyS0 root=/dev/mmcblk0p2";
+
+    fn hello_content() -> Vec<u8> {
+        (0..1300u32).map(|i| (i % 251) as u8).collect()
+    }
+
+    // Kök: birim etiketi, HELLO.TXT (dağınık 3 küme), DOCS/ (içinde LFN'li bir dosya).
+    fn sample(fat_type: FatType, total_sectors: u32) -> Image {
+        let mut image = Image::format(fat_type, total_sectors);
+        image.write_chain(&[5, 9, 7], &hello_content());
+        image.put_entries(None, 0, &[
+            short_entry(b"PACKETBOX  ", ATTR_VOLUME_ID, 0, 0),
+            short_entry(b"HELLO   TXT", ATTR_ARCHIVE, 5, 1300),
+            short_entry(b"DOCS       ", ATTR_DIRECTORY, 3, 0),
+        ]);
+
+        image.write_chain(&[3], &[]);
+        let alias = *b"KERNEL~1TXT";
+        let mut docs = vec![short_entry(b".          ", ATTR_DIRECTORY, 3, 0), short_entry(b"..         ", ATTR_DIRECTORY, 0, 0)];
+        docs.extend(lfn_entries(LONG_NAME, &alias));
+        docs.push(short_entry(&alias, ATTR_ARCHIVE, 4, LONG_CONTENT.len() as u32));
+        image.put_entries(Some(3), 0, &docs);
+        image.write_chain(&[4], LONG_CONTENT);
+        image
+    }
+
+    fn read_all<D: BlockDevice>(volume: &mut FatVolume<D>, path: &str) -> Result<Vec<u8>, FatError> {
+        let mut file = volume.open(path)?;
+        let mut content = vec![0u8; file.size() as usize];
+        let mut done = 0;
+        // Küme ve sektör sınırlarını farklı yerlerden kesen tek sayılı parçalar.
+        while done < content.len() {
+            let end = core::cmp::min(done + 333, content.len());
+            let n = volume.read(&mut file, &mut content[done..end])?;
+            assert!(n > 0);
+            done += n;
+        }
+        assert_eq!(volume.read(&mut file, &mut [0u8; 16])?, 0);
+        Ok(content)
+    }
+
+    const TYPES: [(FatType, u32); 3] = [(FatType::Fat12, 2000), (FatType::Fat16, 6000), (FatType::Fat32, 70000)];
+
+    #[test]
+    fn mounts_fat12_fat16_and_fat32() {
+        for (fat_type, total_sectors) in TYPES {
+            let mut image = sample(fat_type, total_sectors);
+            let mut volume = image.mount().unwrap();
+            assert_eq!(volume.fat_type(), fat_type);
+            assert_eq!(volume.volume_label(), "PACKETBOX");
+
+            let mut root = volume.root_dir();
+            let mut names = Vec::new();
+            while let Some(entry) = volume.next_entry(&mut root).unwrap() {
+                names.push((entry.name().to_string(), entry.is_dir()));
+            }
+            assert_eq!(names, [("HELLO.TXT".to_string(), false), ("DOCS".to_string(), true)], "{:?}", fat_type);
+        }
+    }
+
+    #[test]
+    fn reads_a_scattered_cluster_chain() {
+        for (fat_type, total_sectors) in TYPES {
+            let mut image = sample(fat_type, total_sectors);
+            let mut volume = image.mount().unwrap();
+            assert_eq!(read_all(&mut volume, "/hello.txt").unwrap(), hello_content(), "{:?}", fat_type);
+
+            // Geri sarma zincirin başından yeniden yürür.
+            let mut file = volume.open("HELLO.TXT").unwrap();
+            volume.seek(&mut file, 1100);
+            let mut tail = [0u8; 300];
+            assert_eq!(volume.read(&mut file, &mut tail).unwrap(), 200);
+            volume.seek(&mut file, 600);
+            let mut middle = [0u8; 10];
+            volume.read(&mut file, &mut middle).unwrap();
+            assert_eq!(&middle[..], &hello_content()[600..610]);
+        }
+    }
+
+    #[test]
+    fn finds_files_by_long_and_short_name() {
+        for (fat_type, total_sectors) in TYPES {
+            let mut image = sample(fat_type, total_sectors);
+            let mut volume = image.mount().unwrap();
+            let entry = volume.stat("/DOCS/kernel COMMAND line.TXT").unwrap();
+            assert_eq!(entry.name(), LONG_NAME);
+            assert_eq!(read_all(&mut volume, "docs/KERNEL~1.TXT").unwrap(), LONG_CONTENT, "{:?}", fat_type);
+            assert!(matches!(volume.open("/docs/missing.txt"), Err(FatError::NotFound)));
+            assert!(matches!(volume.open("/hello.txt/x"), Err(FatError::NotADirectory)));
+            assert!(matches!(volume.open("/docs"), Err(FatError::NotAFile)));
+        }
+    }
+
+    #[test]
+    fn lfn_with_wrong_checksum_falls_back_to_short_name() {
+        let mut image = sample(FatType::Fat16, 6000);
+        let mut lfn = lfn_entries(LONG_NAME, b"KERNEL~1TXT");
+        for entry in lfn.iter_mut() {
+            entry[13] ^= 0xFF;
+        }
+        image.put_entries(Some(3), 2, &lfn);
+        let mut volume = image.mount().unwrap();
+        assert!(matches!(volume.stat("/docs/Kernel command line.txt"), Err(FatError::NotFound)));
+        assert_eq!(volume.stat("/docs/kernel~1.txt").unwrap().name(), "KERNEL~1.TXT");
+    }
+
+    #[test]
+    fn broken_cluster_chains_are_reported() {
+        let mut image = sample(FatType::Fat16, 6000);
+        image.set_fat(9, 0x7FFF); // aralık dışı küme
+        let mut volume = image.mount().unwrap();
+        assert!(matches!(read_all(&mut volume, "/hello.txt"), Err(FatError::CorruptClusterChain)));
+
+        let mut image = sample(FatType::Fat16, 6000);
+        image.set_fat(9, image.eoc()); // zincir dosya boyutundan önce bitiyor
+        let mut volume = image.mount().unwrap();
+        assert!(matches!(read_all(&mut volume, "/hello.txt"), Err(FatError::CorruptClusterChain)));
+    }
+
+    #[test]
+    fn overflowing_boot_sector_fields_are_rejected() {
+        let mut image = Image::format(FatType::Fat32, 70000);
+        image.data[36..40].copy_from_slice(&0xFFFF_FFFFu32.to_le_bytes()); // num_fats * sectors_per_fat taşar
+        assert!(matches!(image.mount(), Err(FatError::InvalidBootSector)));
+
+        let mut image = Image::format(FatType::Fat32, 70000);
+        image.data[14..16].copy_from_slice(&0xFFFFu16.to_le_bytes());
+        image.data[16] = 1;
+        image.data[36..40].copy_from_slice(&0xFFFF_F000u32.to_le_bytes()); // + reserved_sectors taşar
+        assert!(matches!(image.mount(), Err(FatError::InvalidBootSector)));
+
+        let mut image = Image::format(FatType::Fat16, 6000);
+        image.data[19..21].copy_from_slice(&10u16.to_le_bytes()); // veri bölgesi yok
+        assert!(matches!(image.mount(), Err(FatError::InvalidBootSector)));
+    }
+}
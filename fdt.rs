@@ -0,0 +1,536 @@
+#![no_std]
+
+// Flattened Device Tree (FDT / DTB) üretimi.
+// Sahne Karnal çekirdeği UART, bellek ve depolama denetleyicisi adreslerini sabit kodlamak yerine
+// firmware'in boot sırasında ürettiği bu ağaçtan okur. Blob, devicetree spesifikasyonundaki
+// sürüm 17 biçimindedir (büyük-endian): başlık, bellek rezervasyon haritası, yapı bloğu, string bloğu.
+
+use core::fmt;
+
+use crate::memory::{FIRMWARE_RESERVED_BYTES, LPDDR1_BASE_ADDRESS, LPDDR1_SIZE_BYTES};
+use crate::plic::{EMMC_IRQ, GPIO_IRQ, I2C_IRQ, PLIC_BASE, PLIC_REG_SIZE, PLIC_SOURCE_COUNT, SD_IRQ, UART0_IRQ};
+use crate::storage::{EMMC_CONTROLLER_BASE, SD_CONTROLLER_BASE, STORAGE_CONTROLLER_SIZE};
+use crate::uart::UART0_BASE_ADDRESS;
+
+pub const FDT_MAGIC: u32 = 0xD00D_FEED;
+pub const FDT_VERSION: u32 = 17;
+pub const FDT_LAST_COMPATIBLE_VERSION: u32 = 16;
+const FDT_HEADER_SIZE: usize = 40;
+
+const FDT_BEGIN_NODE: u32 = 0x1;
+const FDT_END_NODE: u32 = 0x2;
+const FDT_PROP: u32 = 0x3;
+const FDT_END: u32 = 0x9;
+
+// Üretilen blob için ayrılan alan ve string bloğu için geçici alan.
+pub const FDT_MAX_SIZE: usize = 8 * 1024;
+const FDT_MAX_STRINGS: usize = 512;
+const FDT_MAX_RESERVATIONS: usize = 4;
+
+// Yazmaç pencerelerinin boyutu (reg özelliği için).
+const UART_REG_SIZE: u32 = 0x1000;
+const GPIO_REG_SIZE: u32 = 0x1000;
+const I2C_REG_SIZE: u32 = 0x1000;
+// Başvurulan düğümlerin phandle'ları: GPIO (PSU düğümü), hart'ın kesme denetleyicisi (PLIC) ve
+// PLIC (`soc` altındaki aygıtların `interrupt-parent`'ı).
+const GPIO_PHANDLE: u32 = 1;
+const CPU_INTC_PHANDLE: u32 = 2;
+const PLIC_PHANDLE: u32 = 3;
+// Hart'ın makine modu harici kesmesi (mcause 11); PLIC bu girişe bağlıdır.
+const IRQ_M_EXT: u32 = 11;
+const GPIO_ACTIVE_HIGH: u32 = 0;
+const GPIO_ACTIVE_LOW: u32 = 1;
+
+#[derive(Debug)]
+pub enum FdtError {
+    OutOfSpace,       // Blob veya string bloğu ayrılan alana sığmıyor
+    UnbalancedNodes,  // begin_node/end_node sayıları eşleşmiyor
+    TooManyReservations,
+}
+
+/// FDT blob'unu doğrudan çıktı arabelleğine yazan üretici.
+/// Yapı bloğu arabelleğe yazılırken özellik adları ayrı bir string bloğunda toplanır ve
+/// `finish` ile yapı bloğunun arkasına eklenir.
+pub struct FdtBuilder<'a> {
+    out: &'a mut [u8],
+    struct_start: usize,
+    position: usize,
+    strings: [u8; FDT_MAX_STRINGS],
+    strings_len: usize,
+    depth: usize,
+    boot_cpuid: u32,
+    error: Option<FdtError>,
+}
+
+impl<'a> FdtBuilder<'a> {
+    /// Üreticiyi başlatır; bellek rezervasyon haritası hemen yazılır.
+    pub fn new(out: &'a mut [u8], reservations: &[(u64, u64)], boot_cpuid: u32) -> Result<Self, FdtError> {
+        if reservations.len() > FDT_MAX_RESERVATIONS {
+            return Err(FdtError::TooManyReservations);
+        }
+        let struct_start = FDT_HEADER_SIZE + (reservations.len() + 1) * 16;
+        if out.len() < struct_start {
+            return Err(FdtError::OutOfSpace);
+        }
+        out[..struct_start].fill(0);
+        for (i, (address, size)) in reservations.iter().enumerate() {
+            let offset = FDT_HEADER_SIZE + i * 16;
+            out[offset..offset + 8].copy_from_slice(&address.to_be_bytes());
+            out[offset + 8..offset + 16].copy_from_slice(&size.to_be_bytes());
+        }
+        Ok(FdtBuilder {
+            out,
+            struct_start,
+            position: struct_start,
+            strings: [0; FDT_MAX_STRINGS],
+            strings_len: 0,
+            depth: 0,
+            boot_cpuid,
+            error: None,
+        })
+    }
+
+    // Yazma hataları ilk hatada kaydedilir ve `finish`'te döndürülür; böylece ağaç tanımı
+    // her satırda hata kontrolü yapmadan yazılabilir.
+    fn put(&mut self, bytes: &[u8]) {
+        if self.error.is_some() {
+            return;
+        }
+        if self.position + bytes.len() > self.out.len() {
+            self.error = Some(FdtError::OutOfSpace);
+            return;
+        }
+        self.out[self.position..self.position + bytes.len()].copy_from_slice(bytes);
+        self.position += bytes.len();
+    }
+
+    fn put_u32(&mut self, value: u32) {
+        self.put(&value.to_be_bytes());
+    }
+
+    fn align4(&mut self) {
+        while self.error.is_none() && !self.position.is_multiple_of(4) {
+            self.put(&[0]);
+        }
+    }
+
+    // Özellik adını string bloğunda arar, yoksa ekler; ofsetini döndürür.
+    fn string_offset(&mut self, name: &str) -> u32 {
+        let mut offset = 0;
+        while offset < self.strings_len {
+            let end = offset + self.strings[offset..self.strings_len].iter().position(|&b| b == 0).unwrap_or(0);
+            if &self.strings[offset..end] == name.as_bytes() {
+                return offset as u32;
+            }
+            offset = end + 1;
+        }
+        if self.strings_len + name.len() + 1 > FDT_MAX_STRINGS {
+            self.error.get_or_insert(FdtError::OutOfSpace);
+            return 0;
+        }
+        let offset = self.strings_len;
+        self.strings[offset..offset + name.len()].copy_from_slice(name.as_bytes());
+        self.strings[offset + name.len()] = 0;
+        self.strings_len += name.len() + 1;
+        offset as u32
+    }
+
+    pub fn begin_node(&mut self, name: fmt::Arguments<'_>) {
+        self.put_u32(FDT_BEGIN_NODE);
+        let _ = fmt::write(self, name);
+        self.put(&[0]);
+        self.align4();
+        self.depth += 1;
+    }
+
+    pub fn end_node(&mut self) {
+        if self.depth == 0 {
+            self.error.get_or_insert(FdtError::UnbalancedNodes);
+            return;
+        }
+        self.put_u32(FDT_END_NODE);
+        self.depth -= 1;
+    }
+
+    pub fn property(&mut self, name: &str, value: &[u8]) {
+        let name_offset = self.string_offset(name);
+        self.put_u32(FDT_PROP);
+        self.put_u32(value.len() as u32);
+        self.put_u32(name_offset);
+        self.put(value);
+        self.align4();
+    }
+
+    /// Değeri olmayan (boolean) özellik, örn. `interrupt-controller`.
+    pub fn property_empty(&mut self, name: &str) {
+        self.property(name, &[]);
+    }
+
+    pub fn property_u32(&mut self, name: &str, value: u32) {
+        self.property(name, &value.to_be_bytes());
+    }
+
+    /// Birden fazla hücreden oluşan özellik, örn. `reg = <base size>`.
+    pub fn property_cells(&mut self, name: &str, cells: &[u32]) {
+        let name_offset = self.string_offset(name);
+        self.put_u32(FDT_PROP);
+        self.put_u32((cells.len() * 4) as u32);
+        self.put_u32(name_offset);
+        for &cell in cells {
+            self.put_u32(cell);
+        }
+    }
+
+    /// Sonu 0 ile biten string özelliği; değer biçimlendirilerek doğrudan blob'a yazılır.
+    pub fn property_string(&mut self, name: &str, value: fmt::Arguments<'_>) {
+        let name_offset = self.string_offset(name);
+        self.put_u32(FDT_PROP);
+        let length_position = self.position;
+        self.put_u32(0); // Uzunluk, değer yazıldıktan sonra doldurulur
+        self.put_u32(name_offset);
+        let value_start = self.position;
+        let _ = fmt::write(self, value);
+        self.put(&[0]);
+        if self.error.is_none() {
+            let length = (self.position - value_start) as u32;
+            self.out[length_position..length_position + 4].copy_from_slice(&length.to_be_bytes());
+        }
+        self.align4();
+    }
+
+    /// Yapı bloğunu kapatır, string bloğunu ekler, başlığı yazar ve toplam boyutu döndürür.
+    pub fn finish(mut self) -> Result<usize, FdtError> {
+        if self.depth != 0 {
+            self.error.get_or_insert(FdtError::UnbalancedNodes);
+        }
+        self.put_u32(FDT_END);
+        let struct_size = self.position - self.struct_start;
+        let strings_offset = self.position;
+        let strings = self.strings;
+        self.put(&strings[..self.strings_len]);
+        if let Some(e) = self.error {
+            return Err(e);
+        }
+        let total_size = self.position;
+
+        let header: [u32; 10] = [
+            FDT_MAGIC,
+            total_size as u32,
+            self.struct_start as u32,
+            strings_offset as u32,
+            FDT_HEADER_SIZE as u32, // Bellek rezervasyon haritası başlığın hemen arkasında
+            FDT_VERSION,
+            FDT_LAST_COMPATIBLE_VERSION,
+            self.boot_cpuid,
+            self.strings_len as u32,
+            struct_size as u32,
+        ];
+        for (i, word) in header.iter().enumerate() {
+            self.out[i * 4..i * 4 + 4].copy_from_slice(&word.to_be_bytes());
+        }
+        Ok(total_size)
+    }
+}
+
+impl fmt::Write for FdtBuilder<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.put(s.as_bytes());
+        Ok(())
+    }
+}
+
+/// Blob'un hizalı tutulduğu statik alan (spesifikasyon 8 bayt hizalama ister).
+#[repr(C, align(8))]
+pub struct FdtBlob(pub [u8; FDT_MAX_SIZE]);
+
+// --- Global FDT Alanı ---
+// Firmware'e ayrılmış bölgede durur; handoff tablosundaki bellek haritasında korunur.
+// # Safety: Global mutable static kullanimi unsafe'dir.
+pub static mut FDT_BLOB: FdtBlob = FdtBlob([0; FDT_MAX_SIZE]);
+
+/// PacketBox kartını tanımlayan ağacı üretir ve blob boyutunu döndürür.
+/// `bootargs` /chosen düğümüne yazılır; depolama denetleyicilerinin `status` değeri
+/// başlatılıp başlatılamadıklarına göre belirlenir.
+/// # Safety
+/// Global depolama aygıtlarının durumunu okur.
+pub unsafe fn build_device_tree(out: &mut [u8], bootargs: &str, boot_hart: u32) -> Result<usize, FdtError> {
+    let reservations = [(LPDDR1_BASE_ADDRESS as u64, FIRMWARE_RESERVED_BYTES as u64)];
+    let mut fdt = FdtBuilder::new(out, &reservations, boot_hart)?;
+    let emmc_status = if crate::storage::EMIC_STORAGE_GLOBAL.is_some() { "okay" } else { "disabled" };
+    let sd_status = if crate::storage::SD_CARD_STORAGE_GLOBAL.is_some() { "okay" } else { "disabled" };
+
+    fdt.begin_node(format_args!(""));
+    fdt.property_u32("#address-cells", 1);
+    fdt.property_u32("#size-cells", 1);
+    fdt.property_string("compatible", format_args!("sahnedunya,packetbox"));
+    fdt.property_string("model", format_args!("PacketBox"));
+
+    fdt.begin_node(format_args!("chosen"));
+    fdt.property_string("bootargs", format_args!("{}", bootargs));
+    fdt.property_string("stdout-path", format_args!("/soc/serial@{:x}", UART0_BASE_ADDRESS));
+    if let Some(slot) = crate::slots::booted_slot() {
+        fdt.property_string("packetbox,boot-slot", format_args!("{}", slot.name()));
+    }
+    fdt.end_node();
+
+    fdt.begin_node(format_args!("aliases"));
+    fdt.property_string("serial0", format_args!("/soc/serial@{:x}", UART0_BASE_ADDRESS));
+    fdt.end_node();
+
+    fdt.begin_node(format_args!("cpus"));
+    fdt.property_u32("#address-cells", 1);
+    fdt.property_u32("#size-cells", 0);
+    fdt.property_u32("timebase-frequency", crate::timer::TIMEBASE_FREQUENCY_HZ);
+    fdt.begin_node(format_args!("cpu@0"));
+    fdt.property_string("device_type", format_args!("cpu"));
+    fdt.property_u32("reg", 0);
+    fdt.property("compatible", b"sifive,s21\0riscv\0");
+    fdt.property_string("riscv,isa", format_args!("rv32imac"));
+    fdt.property_string("status", format_args!("okay"));
+    fdt.begin_node(format_args!("interrupt-controller"));
+    fdt.property_u32("#interrupt-cells", 1);
+    fdt.property_empty("interrupt-controller");
+    fdt.property_string("compatible", format_args!("riscv,cpu-intc"));
+    fdt.property_u32("phandle", CPU_INTC_PHANDLE);
+    fdt.end_node();
+    fdt.end_node();
+    fdt.end_node();
+
+    fdt.begin_node(format_args!("memory@{:x}", LPDDR1_BASE_ADDRESS));
+    fdt.property_string("device_type", format_args!("memory"));
+    fdt.property_cells("reg", &[LPDDR1_BASE_ADDRESS as u32, LPDDR1_SIZE_BYTES as u32]);
+    fdt.end_node();
+
+    fdt.begin_node(format_args!("soc"));
+    fdt.property_u32("#address-cells", 1);
+    fdt.property_u32("#size-cells", 1);
+    fdt.property_string("compatible", format_args!("simple-bus"));
+    fdt.property_empty("ranges");
+
+    fdt.begin_node(format_args!("interrupt-controller@{:x}", PLIC_BASE));
+    fdt.property("compatible", b"sifive,plic-1.0.0\0riscv,plic0\0");
+    fdt.property_cells("reg", &[PLIC_BASE as u32, PLIC_REG_SIZE as u32]);
+    fdt.property_u32("#address-cells", 0);
+    fdt.property_u32("#interrupt-cells", 1);
+    fdt.property_empty("interrupt-controller");
+    fdt.property_cells("interrupts-extended", &[CPU_INTC_PHANDLE, IRQ_M_EXT]);
+    fdt.property_u32("riscv,ndev", PLIC_SOURCE_COUNT);
+    fdt.property_u32("phandle", PLIC_PHANDLE);
+    fdt.end_node();
+
+    fdt.begin_node(format_args!("serial@{:x}", UART0_BASE_ADDRESS));
+    fdt.property_string("compatible", format_args!("sifive,uart0"));
+    fdt.property_cells("reg", &[UART0_BASE_ADDRESS as u32, UART_REG_SIZE]);
+    fdt.property_u32("clock-frequency", crate::uart::UART_CLOCK_HZ);
+    fdt.property_u32("current-speed", crate::uart::UART0_GLOBAL.baud_config().requested);
+    fdt.property_u32("interrupt-parent", PLIC_PHANDLE);
+    fdt.property_u32("interrupts", UART0_IRQ);
+    fdt.property_string("status", format_args!("okay"));
+    fdt.end_node();
+
+    fdt.begin_node(format_args!("mmc@{:x}", EMMC_CONTROLLER_BASE));
+    fdt.property_string("compatible", format_args!("sahnedunya,packetbox-emmc"));
+    fdt.property_cells("reg", &[EMMC_CONTROLLER_BASE as u32, STORAGE_CONTROLLER_SIZE]);
+    fdt.property_empty("non-removable");
+    fdt.property_u32("interrupt-parent", PLIC_PHANDLE);
+    fdt.property_u32("interrupts", EMMC_IRQ);
+    fdt.property_string("status", format_args!("{}", emmc_status));
+    fdt.end_node();
+
+    fdt.begin_node(format_args!("mmc@{:x}", SD_CONTROLLER_BASE));
+    fdt.property_string("compatible", format_args!("sahnedunya,packetbox-sd"));
+    fdt.property_cells("reg", &[SD_CONTROLLER_BASE as u32, STORAGE_CONTROLLER_SIZE]);
+    fdt.property_u32("interrupt-parent", PLIC_PHANDLE);
+    fdt.property_u32("interrupts", SD_IRQ);
+    fdt.property_string("status", format_args!("{}", sd_status));
+    fdt.end_node();
+
+    fdt.begin_node(format_args!("gpio@{:x}", crate::psu::GPIO_BASE));
+    fdt.property_string("compatible", format_args!("sifive,gpio0"));
+    fdt.property_cells("reg", &[crate::psu::GPIO_BASE as u32, GPIO_REG_SIZE]);
+    fdt.property_empty("gpio-controller");
+    fdt.property_u32("#gpio-cells", 2);
+    fdt.property_u32("interrupt-parent", PLIC_PHANDLE);
+    fdt.property_u32("interrupts", GPIO_IRQ);
+    fdt.property_u32("phandle", GPIO_PHANDLE);
+    fdt.end_node();
+
+    fdt.begin_node(format_args!("i2c@{:x}", crate::refrigerator::I2C_BASE));
+    fdt.property_string("compatible", format_args!("sahnedunya,packetbox-i2c"));
+    fdt.property_cells("reg", &[crate::refrigerator::I2C_BASE as u32, I2C_REG_SIZE]);
+    fdt.property_u32("interrupt-parent", PLIC_PHANDLE);
+    fdt.property_u32("interrupts", I2C_IRQ);
+    fdt.property_u32("#address-cells", 1);
+    fdt.property_u32("#size-cells", 0);
+    fdt.begin_node(format_args!("refrigerator@{:x}", crate::refrigerator::FRIDGE_I2C_ADDRESS));
+    fdt.property_string("compatible", format_args!("sahnedunya,packetbox-refrigerator"));
+    fdt.property_u32("reg", crate::refrigerator::FRIDGE_I2C_ADDRESS);
+    fdt.end_node();
+    fdt.end_node();
+
+    fdt.end_node(); // soc
+
+    fdt.begin_node(format_args!("psu"));
+    fdt.property_string("compatible", format_args!("sahnedunya,packetbox-psu"));
+    fdt.property_cells("power-good-gpios", &[GPIO_PHANDLE, crate::psu::PSU_PG_GPIO_PIN_IDX, GPIO_ACTIVE_HIGH]);
+    fdt.property_cells("power-on-gpios", &[GPIO_PHANDLE, crate::psu::PSU_PSON_GPIO_PIN_IDX, GPIO_ACTIVE_LOW]);
+    fdt.property_u32("max-power-watts", crate::psu::PSU_WATTAGE_WATTS as u32);
+    fdt.end_node();
+
+    fdt.end_node(); // /
+    fdt.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn be32(blob: &[u8], offset: usize) -> u32 {
+        u32::from_be_bytes(blob[offset..offset + 4].try_into().unwrap())
+    }
+
+    // Yapı bloğunu dolaşır ve (düğüm yolu, özellik adı, değer) üçlülerini döndürür.
+    // Her token'ın 4 bayt hizalı olduğu da burada doğrulanır.
+    fn properties(blob: &[u8]) -> Vec<(String, String, Vec<u8>)> {
+        let struct_offset = be32(blob, 8) as usize;
+        let strings_offset = be32(blob, 12) as usize;
+        let mut path: Vec<String> = Vec::new();
+        let mut found = Vec::new();
+        let mut offset = struct_offset;
+        loop {
+            assert_eq!(offset % 4, 0, "token at {:#x} is not aligned", offset);
+            let token = be32(blob, offset);
+            offset += 4;
+            match token {
+                FDT_BEGIN_NODE => {
+                    let len = blob[offset..].iter().position(|&b| b == 0).unwrap();
+                    path.push(String::from_utf8(blob[offset..offset + len].to_vec()).unwrap());
+                    offset = (offset + len + 1).next_multiple_of(4);
+                }
+                FDT_END_NODE => {
+                    path.pop().expect("END_NODE without BEGIN_NODE");
+                }
+                FDT_PROP => {
+                    let len = be32(blob, offset) as usize;
+                    let name_offset = strings_offset + be32(blob, offset + 4) as usize;
+                    let name_len = blob[name_offset..].iter().position(|&b| b == 0).unwrap();
+                    let name = String::from_utf8(blob[name_offset..name_offset + name_len].to_vec()).unwrap();
+                    let value = blob[offset + 8..offset + 8 + len].to_vec();
+                    found.push((path.join("/"), name, value));
+                    offset = (offset + 8 + len).next_multiple_of(4);
+                }
+                FDT_END => break,
+                other => panic!("unknown token {:#x} at {:#x}", other, offset - 4),
+            }
+        }
+        assert!(path.is_empty());
+        assert_eq!(offset - struct_offset, be32(blob, 36) as usize);
+        found
+    }
+
+    fn property<'a>(props: &'a [(String, String, Vec<u8>)], path: &str, name: &str) -> &'a [u8] {
+        &props.iter().find(|(p, n, _)| p == path && n == name).unwrap_or_else(|| panic!("{} {} missing", path, name)).2
+    }
+
+    #[test]
+    fn header_describes_the_blob() {
+        let mut out = [0u8; 512];
+        let mut fdt = FdtBuilder::new(&mut out, &[(0x8000_0000, 0x1000)], 2).unwrap();
+        fdt.begin_node(format_args!(""));
+        fdt.property_string("model", format_args!("test"));
+        fdt.end_node();
+        let size = fdt.finish().unwrap();
+
+        assert_eq!(be32(&out, 0), FDT_MAGIC);
+        assert_eq!(be32(&out, 4) as usize, size);
+        assert_eq!(be32(&out, 8) as usize, FDT_HEADER_SIZE + 2 * 16); // Bir kayıt + sonlandırıcı
+        assert_eq!(be32(&out, 16) as usize, FDT_HEADER_SIZE);
+        assert_eq!(be32(&out, 20), FDT_VERSION);
+        assert_eq!(be32(&out, 24), FDT_LAST_COMPATIBLE_VERSION);
+        assert_eq!(be32(&out, 28), 2);
+        assert_eq!(be32(&out, 32), 6); // "model\0"
+        assert_eq!(be32(&out, 12) + be32(&out, 32), size as u32); // String bloğu en sonda
+        assert_eq!(out[FDT_HEADER_SIZE..FDT_HEADER_SIZE + 16], [0, 0, 0, 0, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x10, 0]);
+        assert_eq!(out[FDT_HEADER_SIZE + 16..FDT_HEADER_SIZE + 32], [0; 16]);
+        assert_eq!(properties(&out[..size]), [("".to_string(), "model".to_string(), b"test\0".to_vec())]);
+    }
+
+    #[test]
+    fn unaligned_names_and_values_are_padded() {
+        let mut out = [0u8; 512];
+        let mut fdt = FdtBuilder::new(&mut out, &[], 0).unwrap();
+        fdt.begin_node(format_args!(""));
+        fdt.begin_node(format_args!("a"));
+        fdt.property("odd", &[1, 2, 3]);
+        fdt.property_empty("flag");
+        fdt.property_string("text", format_args!("12345"));
+        fdt.end_node();
+        fdt.end_node();
+        let size = fdt.finish().unwrap();
+        let props = properties(&out[..size]); // Hizalama dolaşırken denetlenir
+        assert_eq!(property(&props, "/a", "odd"), [1, 2, 3]);
+        assert_eq!(property(&props, "/a", "flag"), []);
+        assert_eq!(property(&props, "/a", "text"), b"12345\0");
+    }
+
+    #[test]
+    fn property_names_are_stored_once() {
+        let mut out = [0u8; 512];
+        let mut fdt = FdtBuilder::new(&mut out, &[], 0).unwrap();
+        fdt.begin_node(format_args!(""));
+        for node in ["x", "y"] {
+            fdt.begin_node(format_args!("{}", node));
+            fdt.property_cells("reg", &[1, 2]);
+            fdt.property_u32("phandle", 1);
+            fdt.end_node();
+        }
+        fdt.end_node();
+        let size = fdt.finish().unwrap();
+        let strings = be32(&out, 12) as usize;
+        assert_eq!(&out[strings..size], b"reg\0phandle\0");
+        assert_eq!(properties(&out[..size]).len(), 4);
+    }
+
+    #[test]
+    fn short_buffer_reports_out_of_space() {
+        let mut tiny = [0u8; FDT_HEADER_SIZE];
+        assert!(matches!(FdtBuilder::new(&mut tiny, &[], 0), Err(FdtError::OutOfSpace)));
+        let mut out = [0u8; 80];
+        let mut fdt = FdtBuilder::new(&mut out, &[], 0).unwrap();
+        fdt.begin_node(format_args!(""));
+        fdt.property_string("bootargs", format_args!("{}", "x".repeat(64)));
+        fdt.end_node();
+        assert!(matches!(fdt.finish(), Err(FdtError::OutOfSpace)));
+        let mut out = [0u8; 512];
+        assert!(matches!(FdtBuilder::new(&mut out, &[(0, 1); FDT_MAX_RESERVATIONS + 1], 0), Err(FdtError::TooManyReservations)));
+    }
+
+    #[test]
+    fn unbalanced_nodes_are_rejected() {
+        let mut out = [0u8; 512];
+        let mut fdt = FdtBuilder::new(&mut out, &[], 0).unwrap();
+        fdt.begin_node(format_args!(""));
+        assert!(matches!(fdt.finish(), Err(FdtError::UnbalancedNodes)));
+        let mut fdt = FdtBuilder::new(&mut out, &[], 0).unwrap();
+        fdt.end_node();
+        assert!(matches!(fdt.finish(), Err(FdtError::UnbalancedNodes)));
+    }
+
+    #[test]
+    fn board_tree_carries_bootargs_and_plic_interrupts() {
+        let mut blob = FdtBlob([0; FDT_MAX_SIZE]);
+        let size = unsafe { build_device_tree(&mut blob.0, "console=ttySIF0 root=/dev/mmcblk0p2", 0) }.unwrap();
+        let props = properties(&blob.0[..size]);
+        assert_eq!(property(&props, "/chosen", "bootargs"), b"console=ttySIF0 root=/dev/mmcblk0p2\0");
+
+        let plic = format!("/soc/interrupt-controller@{:x}", PLIC_BASE);
+        assert!(property(&props, &plic, "compatible").split(|&b| b == 0).any(|c| c == b"riscv,plic0"));
+        assert_eq!(property(&props, &plic, "phandle"), PLIC_PHANDLE.to_be_bytes());
+        assert_eq!(property(&props, &plic, "interrupts-extended"), [CPU_INTC_PHANDLE.to_be_bytes(), IRQ_M_EXT.to_be_bytes()].concat());
+        assert_eq!(property(&props, "/cpus/cpu@0/interrupt-controller", "phandle"), CPU_INTC_PHANDLE.to_be_bytes());
+
+        let serial = format!("/soc/serial@{:x}", UART0_BASE_ADDRESS);
+        assert_eq!(property(&props, &serial, "interrupt-parent"), PLIC_PHANDLE.to_be_bytes());
+        assert_eq!(property(&props, &serial, "interrupts"), UART0_IRQ.to_be_bytes());
+    }
+}
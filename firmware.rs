@@ -1,230 +1,311 @@
-#![no_std]
-#![no_main]
-
-use riscv_rt::entry;
-use core::fmt::Write; // writeln! makrosu için
-
-// Modüllerin içe aktarılması
-mod firmware_common;
-mod uart;
-mod memory;
-mod storage;
-mod psu;
-mod refrigerator;
-mod cli;
-mod boot; // Boot aşamaları
-
-// Ortak öğeler ve genel hata türü
-use crate::firmware_common::{self, log, Error, SystemState, set_system_state};
-
-// Global staticlere erişim için use bildirimleri
-use crate::uart::UART0_GLOBAL;
-use crate::cli::CLI_GLOBAL;
-use crate::storage::{EMIC_STORAGE_GLOBAL, SD_CARD_STORAGE_GLOBAL}; // Storage global statikleri
-use crate::psu::PSU_MONITOR_GLOBAL; // PSU global statik
-use crate::refrigerator::FRIDGE_CONTROLLER_GLOBAL; // Refrigerator global statik
-
-
-// Firmware'in ana giriş noktası.
-#[entry]
-fn main() -> ! {
-    // İşlemci riscv-rt tarafindan baslatildi. Yigin, BSS/Data hazir.
-    // Donanıma özgü başlatma aşamalarını yönetelim.
-
-    unsafe { set_system_state(SystemState::Initializing); }
-     log!("System State: Initializing"); // Bu log UART init'ten once calismaz!
-
-    // --- Boot Süreci Aşamaları ---
-
-    // 1. Erken Donanım Başlatma (Saat, Temel Güç)
-    //    UART çalışmadan önce olabilecek hatalar için özel raporlama gerekebilir (LED gibi).
-    match unsafe { boot::perform_early_hardware_init() } {
-        Ok(_) => { /* Başarılı */ },
-        Err(_) => unsafe {
-             // Hata! UART yok, LED yakarak veya baska bir yolla raporla
-             set_system_state(SystemState::Error);
-             loop {} // Kurtarılamaz hata
-        }
-    }
-
-    // 2. Temel Çevre Birimleri Başlatma (UART - loglama için)
-    //    UART başlatıldıktan sonra log! makrosu kullanılabilir.
-    match unsafe { boot::initialize_peripherals() } {
-        Ok(_) => {
-            // UART artık çalışıyor olmalı. log! makrosu kullanılabilir.
-            unsafe { log!("UART and core peripherals initialized.") };
-        },
-        Err(e) => unsafe {
-            // UART başlatma hatası. Loglama mümkün değil.
-            // Belki ham putc deneyebiliriz veya LED yakabiliriz.
-             unsafe { UART0_GLOBAL.putc(b'U'); UART0_GLOBAL.putc(b'E'); }
-             set_system_state(SystemState::Error);
-             loop {} // Kurtarılamaz hata
-        }
-    }
-
-    // 3. Bellek Başlatma (LPDDR1)
-    match unsafe { boot::initialize_memory() } {
-        Ok(_) => unsafe { log!("Memory initialized successfully.") },
-        Err(e) => unsafe {
-            log!("Memory initialization FAILED: {:?}", e);
-            set_system_state(SystemState::Error);
-            loop {} // Kurtarılamaz hata
-        }
-    }
-
-    // İsteğe Bağlı: Global Bellek Ayırıcıyı Başlatma (Eğer kullanılıyorsa)
-    
-    unsafe {
-        match crate::memory::init_allocator() {
-             Ok(_) => log!("Global allocator initialized."),
-             Err(e) => {
-                 log!("Global allocator initialization FAILED: {:?}", e);
-                 set_system_state(SystemState::Error);
-                 loop {} // Kurtarılamaz hata
-             }
-        }
-    }
-    
-
-
-    // --- Diğer Sistem Modüllerini Başlatma ---
-
-    // Depolama Başlatma ve Global Statiklere Atama
-    unsafe {
-        match crate::storage::init_storage_devices() { // Parametreler burada paslanmalı
-             Ok((emic_opt, sd_opt)) => {
-                 EMIC_STORAGE_GLOBAL = emic_opt;
-                 SD_CARD_STORAGE_GLOBAL = sd_opt;
-
-                 if EMIC_STORAGE_GLOBAL.is_some() { log!("eMMC storage initialized.") }
-                 if SD_CARD_GLOBAL.is_some() { log!("SD card storage initialized.") }
-                 if EMIC_STORAGE_GLOBAL.is_none() && SD_CARD_GLOBAL.is_none() { log!("No storage devices initialized.") }
-             },
-             Err(e) => {
-                 log!("Storage initialization FAILED: {:?}", e);
-                 // Depolama hatası kurtarılamaz olmayabilir, duruma göre karar verin.
-                  set_system_state(SystemState::Error); loop {};
-             }
-        }
-    }
-
-    // PSU İzleyici Başlatma ve Global Statiğe Atama
-     unsafe {
-         let mut psu_monitor = crate::psu::PsuMonitor::new(); // Parametreler burada paslanmalı
-        match psu_monitor.init() {
-             Ok(_) => {
-                PSU_MONITOR_GLOBAL = Some(psu_monitor);
-                 log!("PSU monitor initialized.");
-                  // PSU'yu aç (eğer soft-power kontrolü varsa)
-                   unsafe { if let Some(psu) = &mut PSU_MONITOR_GLOBAL { let _ = psu.turn_on(); } }
-                  // Power Good sinyalini bekle (boot.rs'de veya burada yapılabilir)
-                   match boot::wait_for_power_good() { ... }
-             },
-             Err(e) => {
-                 log!("PSU monitor initialization FAILED: {:?}", e);
-                 // PSU hatası kurtarılamaz olmayabilir.
-                  set_system_state(SystemState::Error); loop {};
-             }
-        }
-     }
-
-    // Buzdolabı Arayüzü Başlatma ve Global Statiğe Atama
-     unsafe {
-        let mut fridge_controller = crate::refrigerator::RefrigeratorController::new(); // Parametreler burada paslanmalı
-        match fridge_controller.init() {
-             Ok(_) => {
-                FRIDGE_CONTROLLER_GLOBAL = Some(fridge_controller);
-                 log!("Refrigerator interface initialized.");
-             },
-             Err(e) => {
-                 log!("Refrigerator interface initialization FAILED: {:?}", e);
-                 // Buzdolabı hatası kurtarılamaz olmayabilir.
-                  set_system_state(SystemState::Error); loop {};
-             }
-        }
-     }
-
-
-    // CLI Başlatma (UART'a bağımlı olduğu için diğer çevre birimlerinden sonra)
-     unsafe {
-        CLI_GLOBAL.init(); // CLI state'ini sıfırla
-        let _ = CLI_GLOBAL.print_prompt(&mut UART0_GLOBAL); // İlk prompt'u yazdır
-        log!("CLI initialized. Type 'help'.");
-     }
-
-
-    // --- Başlatma Tamamlandı ---
-    unsafe { set_system_state(SystemState::Running); }
-    unsafe { log!("PacketBox System is now Running."); }
-
-
-    // --- Ana Çalışma Döngüsü (BIOS Benzeri CLI Etkileşimi) ---
-    // Bu döngüde sistem CLI input bekler ve diğer temel görevleri (varsa) yapar.
-    loop {
-        // 1. CLI Girişini İşleme (Polling)
-        // UART'tan byte gelip gelmediğini non-blocking olarak kontrol et.
-        // Eğer byte geldiyse CLI işleyicisine ilet.
-        unsafe {
-            if let Some(byte) = UART0_GLOBAL.read_byte() {
-                 // Byte geldiyse CLI'ya işle. process_byte kendi içinde echo yapar ve komut çalıştırır.
-                 let _ = CLI_GLOBAL.process_byte(byte, &mut UART0_GLOBAL);
-            }
-        }
-
-        // 2. Diğer Periyodik veya Olay Tabanlı Görevler
-        // CLI input beklenmediği zaman CPU burayı çalıştırır.
-        // - Buzdolabı durumu kontrolü
-        // - PSU durumu izleme
-        // - Sistem sağlığı kontrolü
-        // - Çok kısa beklemeler (CPU'yu tamamen meşgul etmemek için, polling yapılıyorsa)
-        // crate::firmware_common::delay_cycles(100); // Varsayımsal kısa bekleme
-
-        // Eğer interrupt kullanılıyorsa, burada WFI (Wait For Interrupt) kullanılabilir:
-         riscv::asm::wfi(); // interrupt gelene kadar bekler
-
-    }
-}
-
-// Hata işleyici fonksiyonu (Panic handler)
-#[panic_handler]
-fn panic(info: &core::panic::PanicInfo) -> ! {
-    // Panic bilgilerini UART üzerinden raporla.
-    unsafe {
-        let mut uart = &mut UART0_GLOBAL; // Global UART'a unsafe erişim
-        let _ = writeln!(uart, "\n--- PANIC ---"); // Yeni satir ekleyerek CLI promptunu bozmamaya calis
-        if let Some(location) = info.location() {
-            let _ = writeln!(uart, "at {}:{}", location.file(), location.line());
-        } else {
-            let _ = writeln!(uart, "at unknown location");
-        }
-        if let Some(message) = info.payload().downcast_ref::<&'static str>() {
-             let _ = writeln!(uart, "Message: {}", message);
-        } else {
-             let _ = writeln!(uart, "Message: <unknown>");
-        }
-        let _ = writeln!(uart, "--- PANIC ---");
-    }
-
-    unsafe { set_system_state(SystemState::Error); }
-
-    // Kurtarılamaz hata durumunda sonsuz döngüye gir.
-    loop { /* Hata ledi yak vb. */ }
-}
-
-// Varsayılan İstisna (Exception) İşleyici (isteğe bağlı, riscv-rt sağlar)
-
-use riscv_rt::TrapFrame;
-
-#[exception]
-unsafe fn DefaultExceptionHandler(trapframe: &TrapFrame) -> ! {
-     let mut uart = &mut UART0_GLOBAL;
-     let _ = writeln!(uart, "\n--- EXCEPTION ---"); // Yeni satir ekle
-     // mcause degerini daha anlamli yazdiran bir fonksiyon kullanin
-     let _ = writeln!(uart, "Cause: {}", trapframe.mcause());
-     let _ = writeln!(uart, "PC: {:#x}", trapframe.mepc());
-     let _ = writeln!(uart, "--- EXCEPTION ---");
-
-    unsafe { set_system_state(SystemState::Error); }
-    loop { /* Hata ledi yak */ }
+// Firmware kütüphane olarak derlenir; `main.rs` ikilisi sadece bunu bağlar. Donanımda giriş noktası
+// aşağıdaki `#[entry]` fonksiyonudur. `hosted` feature'ı ile firmware Linux üzerinde normal bir süreç
+// olarak çalışır (bkz. hosted.rs) ve entegrasyon testleri (tests/) modüllere buradan erişir.
+#![cfg_attr(not(feature = "hosted"), no_std)]
+// Sürücüler global durumu `static mut` üzerinden paylaşır ve her modül dosyası `#![no_std]` ile başlar.
+#![allow(static_mut_refs, unused_attributes)]
+// Sürücüler `const fn new()` ile global olarak kurulur; hata durumunda firmware `loop {}` ile durur;
+// host/donanım dalları `#[cfg]` blokları ve `return` ile ayrılır.
+#![allow(clippy::new_without_default, clippy::empty_loop, clippy::needless_return)]
+
+#[cfg(not(feature = "hosted"))]
+use riscv_rt::entry;
+#[cfg(not(feature = "hosted"))]
+use core::fmt::Write; // writeln! makrosu için
+
+// Modüllerin içe aktarılması
+#[macro_use]
+pub mod firmware_common; // Ortak hata türü, sistem durumu ve log! makrosu
+pub mod mmio; // Yazmaç (MMIO) erişim katmanı
+pub mod ringbuf; // Kilitsiz SPSC halka tampon
+pub mod plic; // Platform-Level Interrupt Controller
+pub mod timer; // CLINT mtime tabanlı zaman kaynağı
+pub mod uart;
+pub mod memory;
+pub mod storage;
+pub mod partition; // Bölüm tablosu ortak katmanı
+pub mod mbr; // MBR bölüm tablosu ayrıştırıcısı
+pub mod gpt; // GPT bölüm tablosu ayrıştırıcısı
+pub mod crc; // CRC sağlama algoritmaları
+pub mod fat; // FAT12/16/32 salt okunur dosya sistemi
+pub mod nvram; // eMMC üzerindeki kalıcı değişken deposu
+pub mod env; // Tipli firmware ortam değişkenleri
+pub mod psu;
+pub mod refrigerator;
+pub mod lexer; // CLI komut satırı ayrıştırıcısı (tırnak, kaçış, $değişken, ;)
+pub mod cli;
+pub mod image; // Boot imajı başlık biçimi
+pub mod secureboot; // Ed25519 imza doğrulaması
+pub mod measured; // Ölçülü boot (SHA-256 PCR zinciri ve olay kaydı)
+pub mod handoff; // İşletim sistemine aktarılan boot handoff tablosu
+pub mod fdt; // Flattened Device Tree üretimi
+pub mod boot; // Boot aşamaları
+pub mod bootmgr; // Boot sırası ve cihazlar arası geri dönüş (fallback)
+pub mod slots; // A/B işletim sistemi slotları ve deneme sayaçları
+pub mod xmodem; // XMODEM/YMODEM dosya alma
+pub mod update; // UART üzerinden firmware güncelleme ve firmware bankaları
+pub mod script; // FAT üzerindeki komut betikleri (source, bootscript)
+#[cfg(feature = "hosted")]
+pub mod hosted; // Host (Linux) simülasyon arka ucu
+
+// Ortak öğeler ve genel hata türü
+use crate::firmware_common::{SystemState, set_system_state};
+
+// Global staticlere erişim için use bildirimleri
+use crate::uart::UART0_GLOBAL;
+use crate::cli::CLI_GLOBAL;
+use crate::storage::{EMIC_STORAGE_GLOBAL, SD_CARD_STORAGE_GLOBAL}; // Storage global statikleri
+use crate::psu::PSU_MONITOR_GLOBAL; // PSU global statik
+use crate::refrigerator::FRIDGE_CONTROLLER_GLOBAL; // Refrigerator global statik
+
+
+/// Host simülasyonunda firmware'i başlatır (`main.rs` ve entegrasyon testleri çağırır). Geri dönmez.
+#[cfg(feature = "hosted")]
+pub fn run() -> ! {
+    main()
+}
+
+// Firmware'in ana giriş noktası.
+// Host simülasyonunda `run` üzerinden sürecin normal `main`'i olarak çalışır.
+#[cfg_attr(not(feature = "hosted"), entry)]
+fn main() -> ! {
+    // İşlemci riscv-rt tarafindan baslatildi. Yigin, BSS/Data hazir.
+    // Donanıma özgü başlatma aşamalarını yönetelim.
+
+    unsafe { set_system_state(SystemState::Initializing); }
+     log!("System State: Initializing"); // Bu log UART init'ten once calismaz!
+
+    // --- Boot Süreci Aşamaları ---
+
+    // 1. Erken Donanım Başlatma (Saat, Temel Güç)
+    //    UART çalışmadan önce olabilecek hatalar için özel raporlama gerekebilir (LED gibi).
+    match unsafe { boot::perform_early_hardware_init() } {
+        Ok(_) => { /* Başarılı */ },
+        Err(_) => unsafe {
+             // Hata! UART yok, LED yakarak veya baska bir yolla raporla
+             set_system_state(SystemState::Error);
+             loop {} // Kurtarılamaz hata
+        }
+    }
+
+    // 2. Temel Çevre Birimleri Başlatma (UART - loglama için)
+    //    UART başlatıldıktan sonra log! makrosu kullanılabilir.
+    match unsafe { boot::initialize_peripherals() } {
+        Ok(_) => {
+            // UART artık çalışıyor olmalı. log! makrosu kullanılabilir.
+            log!("UART and core peripherals initialized.");
+        },
+        Err(_) => unsafe {
+            // UART başlatma hatası. Loglama mümkün değil.
+            // Belki ham putc deneyebiliriz veya LED yakabiliriz.
+             UART0_GLOBAL.putc(b'U'); UART0_GLOBAL.putc(b'E');
+             set_system_state(SystemState::Error);
+             loop {} // Kurtarılamaz hata
+        }
+    }
+
+    // 3. Bellek Başlatma (LPDDR1)
+    match unsafe { boot::initialize_memory() } {
+        Ok(_) => log!("Memory initialized successfully."),
+        Err(e) => unsafe {
+            log!("Memory initialization FAILED: {:?}", e);
+            set_system_state(SystemState::Error);
+            loop {} // Kurtarılamaz hata
+        }
+    }
+
+    // İsteğe Bağlı: Global Bellek Ayırıcıyı Başlatma (Eğer kullanılıyorsa)
+    
+    unsafe {
+        match crate::memory::init_allocator() {
+             Ok(_) => log!("Global allocator initialized."),
+             Err(e) => {
+                 log!("Global allocator initialization FAILED: {:?}", e);
+                 set_system_state(SystemState::Error);
+                 loop {} // Kurtarılamaz hata
+             }
+        }
+    }
+    
+
+
+    // --- Diğer Sistem Modüllerini Başlatma ---
+
+    // Depolama Başlatma ve Global Statiklere Atama
+    unsafe {
+        match crate::storage::init_storage_devices() { // Parametreler burada paslanmalı
+             Ok((emic_opt, sd_opt)) => {
+                 EMIC_STORAGE_GLOBAL = emic_opt;
+                 SD_CARD_STORAGE_GLOBAL = sd_opt;
+
+                 if EMIC_STORAGE_GLOBAL.is_some() { log!("eMMC storage initialized.") }
+                 if SD_CARD_STORAGE_GLOBAL.is_some() { log!("SD card storage initialized.") }
+                 if EMIC_STORAGE_GLOBAL.is_none() && SD_CARD_STORAGE_GLOBAL.is_none() { log!("No storage devices initialized.") }
+             },
+             Err(e) => {
+                 log!("Storage initialization FAILED: {:?}", e);
+                 // Depolama hatası kurtarılamaz olmayabilir, duruma göre karar verin.
+                  set_system_state(SystemState::Error); loop {};
+             }
+        }
+    }
+
+    // Kalıcı değişken deposunu (NVRAM) eMMC'den yükle.
+    // Yüklenemezse varsayılan ayarlarla devam edilir; değişkenler sadece RAM'de tutulur.
+    unsafe {
+        match crate::nvram::init() {
+            Ok(_) => log!("NVRAM loaded ({} variable(s)).", crate::nvram::NVRAM_GLOBAL.iter().count()),
+            Err(e) => log!("NVRAM not available, using defaults: {:?}", e),
+        }
+        // Kayıtlı ayarları (örn. secureboot politikası) uygula.
+        crate::env::apply_all();
+        // Üretim anahtarı yazılmamış bir derleme her imajı reddeder; bunu açılışta görünür kıl.
+        crate::secureboot::report_key_status();
+    }
+
+    // PSU İzleyici Başlatma ve Global Statiğe Atama
+     unsafe {
+         let mut psu_monitor = crate::psu::PsuMonitor::new(); // Parametreler burada paslanmalı
+        match psu_monitor.init() {
+             Ok(_) => {
+                PSU_MONITOR_GLOBAL = Some(psu_monitor);
+                 log!("PSU monitor initialized.");
+                  // PSU'yu aç (eğer soft-power kontrolü varsa) ve Power Good sinyalini bekle
+                  if let Some(psu) = &mut PSU_MONITOR_GLOBAL {
+                      let _ = psu.turn_on();
+                      match psu.wait_for_power_good(crate::psu::PSU_POWER_GOOD_TIMEOUT_MS) {
+                          Ok(_) => log!("PSU Power Good."),
+                          Err(e) => log!("Warning: PSU Power Good not asserted: {:?}", e),
+                      }
+                  }
+             },
+             Err(e) => {
+                 log!("PSU monitor initialization FAILED: {:?}", e);
+                 // PSU hatası kurtarılamaz olmayabilir.
+                  set_system_state(SystemState::Error); loop {};
+             }
+        }
+     }
+
+    // Buzdolabı Arayüzü Başlatma ve Global Statiğe Atama
+     unsafe {
+        let mut fridge_controller = crate::refrigerator::RefrigeratorController::new(); // Parametreler burada paslanmalı
+        match fridge_controller.init() {
+             Ok(_) => {
+                FRIDGE_CONTROLLER_GLOBAL = Some(fridge_controller);
+                 log!("Refrigerator interface initialized.");
+             },
+             Err(e) => {
+                 log!("Refrigerator interface initialization FAILED: {:?}", e);
+                 // Buzdolabı hatası kurtarılamaz olmayabilir.
+                  set_system_state(SystemState::Error); loop {};
+             }
+        }
+     }
+
+
+    // Otomatik boot: `bootdelay` saniye içinde tuşa basılmazsa boot sırası çalıştırılır.
+    // Boot başarılı olursa buradan geri dönülmez; iptal edilirse veya başarısız olursa CLI'ya geçilir.
+    unsafe { crate::bootmgr::autoboot(&mut UART0_GLOBAL); }
+
+    // CLI Başlatma (UART'a bağımlı olduğu için diğer çevre birimlerinden sonra)
+     unsafe {
+        CLI_GLOBAL.init(); // CLI state'ini sıfırla
+        let _ = CLI_GLOBAL.print_prompt(&mut UART0_GLOBAL); // İlk prompt'u yazdır
+        log!("CLI initialized. Type 'help'.");
+     }
+
+
+    // --- Başlatma Tamamlandı ---
+    unsafe { set_system_state(SystemState::Running); }
+    log!("PacketBox System is now Running.");
+
+
+    // --- Ana Çalışma Döngüsü (BIOS Benzeri CLI Etkileşimi) ---
+    // Bu döngüde sistem CLI input bekler ve diğer temel görevleri (varsa) yapar.
+    loop {
+        // 1. CLI Girişini İşleme
+        // UART kesmesi gelen baytları RX tamponuna aktarır; tamponda ne varsa CLI işleyicisine ilet.
+        unsafe {
+            while let Some(byte) = UART0_GLOBAL.read_byte() {
+                 // Byte geldiyse CLI'ya işle. process_byte kendi içinde echo yapar ve komut çalıştırır.
+                 let _ = CLI_GLOBAL.process_byte(byte, &mut UART0_GLOBAL);
+            }
+        }
+
+        // 2. Diğer Periyodik veya Olay Tabanlı Görevler
+        // CLI input beklenmediği zaman CPU burayı çalıştırır.
+        // - Buzdolabı durumu kontrolü
+        // - PSU durumu izleme
+        // - Sistem sağlığı kontrolü
+        // - Çok kısa beklemeler (CPU'yu tamamen meşgul etmemek için, polling yapılıyorsa)
+        // crate::firmware_common::delay_cycles(100); // Varsayımsal kısa bekleme
+
+        // Kesme gelene kadar bekle (WFI). Tampon boş kontrolü ile WFI arasında gelen bir kesmenin
+        // kaçırılmaması için kontrol kesmeler kapalıyken yapılır; bekleyen kesme WFI'ı yine de uyandırır.
+        #[cfg(not(feature = "hosted"))]
+        unsafe {
+            riscv::register::mstatus::clear_mie();
+            if !UART0_GLOBAL.has_input() {
+                riscv::asm::wfi();
+            }
+            riscv::register::mstatus::set_mie();
+        }
+
+        // Host simülasyonunda WFI yerine kısa bir bekleme yapılır; stdin kapandığında süreç sonlanır.
+        #[cfg(feature = "hosted")]
+        {
+            if crate::hosted::console_closed() {
+                crate::hosted::exit(0);
+            }
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+
+    }
+}
+
+// Hata işleyici fonksiyonu (Panic handler)
+// Host simülasyonunda std'nin kendi panic işleyicisi kullanılır.
+#[cfg(not(feature = "hosted"))]
+#[panic_handler]
+fn panic(info: &core::panic::PanicInfo) -> ! {
+    // Panic bilgilerini UART üzerinden raporla.
+    unsafe {
+        UART0_GLOBAL.disable_interrupts(); // Kesme gelmeyebilir; yoklama moduna geç
+        let uart = &mut UART0_GLOBAL; // Global UART'a unsafe erişim
+        let _ = writeln!(uart, "\n--- PANIC ---"); // Yeni satir ekleyerek CLI promptunu bozmamaya calis
+        if let Some(location) = info.location() {
+            let _ = writeln!(uart, "at {}:{}", location.file(), location.line());
+        } else {
+            let _ = writeln!(uart, "at unknown location");
+        }
+        let _ = writeln!(uart, "Message: {}", info.message());
+        let _ = writeln!(uart, "--- PANIC ---");
+    }
+
+    unsafe { set_system_state(SystemState::Error); }
+
+    // Kurtarılamaz hata durumunda sonsuz döngüye gir.
+    loop { /* Hata ledi yak vb. */ }
+}
+
+// Varsayılan İstisna (Exception) İşleyici (isteğe bağlı, riscv-rt sağlar)
+
+#[cfg(not(feature = "hosted"))]
+use riscv_rt::TrapFrame;
+
+#[cfg(not(feature = "hosted"))]
+#[export_name = "ExceptionHandler"]
+fn exception_handler(_trapframe: &TrapFrame) -> ! {
+    unsafe {
+        UART0_GLOBAL.disable_interrupts(); // Trap içinde kesmeler kapalı; yoklama moduna geç
+        let uart = &mut UART0_GLOBAL;
+        let _ = writeln!(uart, "\n--- EXCEPTION ---"); // Yeni satir ekle
+        // mcause/mepc trap çerçevesinde değil, CSR'lardan okunur
+        let _ = writeln!(uart, "Cause: {:#x}", riscv::register::mcause::read().bits());
+        let _ = writeln!(uart, "PC: {:#x}", riscv::register::mepc::read());
+        let _ = writeln!(uart, "--- EXCEPTION ---");
+        set_system_state(SystemState::Error);
+    }
+    loop { /* Hata ledi yak */ }
 }
\ No newline at end of file
@@ -1,65 +1,81 @@
-#![no_std]
-
-use crate::storage::StorageError;
-use crate::memory::MemoryError;
-use crate::cli::CliError;
-use crate::refrigerator::RefrigeratorError;
-use crate::psu::PsuError;
-
-#[derive(Debug)]
-pub enum Error {
-    Storage(StorageError),
-    Memory(MemoryError),
-    Cli(CliError),
-    Refrigerator(RefrigeratorError),
-    Psu(PsuError),
-    InitializationError,
-    UnknownError,
-}
-
-impl From<StorageError> for Error {
-    fn from(err: StorageError) -> Self { Error::Storage(err) }
-}
-impl From<MemoryError> for Error {
-    fn from(err: MemoryError) -> Self { Error::Memory(err) }
-}
-impl From<CliError> for Error {
-    fn from(err: CliError) -> Self { Error::Cli(err) }
-}
-impl From<RefrigeratorError> for Error {
-    fn from(err: RefrigeratorError) -> Self { Error::Refrigerator(err) }
-}
-impl From<PsuError> for Error {
-    fn from(err: PsuError) -> Self { Error::Psu(err) }
-}
-
-#[derive(Debug, Copy, Clone, PartialEq)]
-pub enum SystemState {
-    Initializing,
-    Running,
-    Error,
-    ShuttingDown,
-    Off,
-    Unknown,
-}
-
-pub static mut CURRENT_SYSTEM_STATE: SystemState = SystemState::Initializing;
-
-pub unsafe fn set_system_state(state: SystemState) {
-    CURRENT_SYSTEM_STATE = state;
-}
-
-pub unsafe fn get_system_state() -> SystemState {
-    CURRENT_SYSTEM_STATE
-}
-
-#[macro_export]
-macro_rules! log {
-    ($($arg:tt)*) => ({
-        unsafe {
-            use core::fmt::Write;
-            let mut uart = &mut crate::uart::UART0_GLOBAL;
-            let _ = writeln!(uart, $($arg)*);
-        }
-    })
+#![no_std]
+
+use crate::storage::StorageError;
+use crate::memory::MemoryError;
+use crate::cli::CliError;
+use crate::refrigerator::RefrigeratorError;
+use crate::psu::PsuError;
+use crate::partition::PartitionError;
+use crate::fat::FatError;
+
+// Firmware sürümü (status komutu ve boot handoff tablosu için).
+pub const FIRMWARE_VERSION: &str = "1.0";
+
+#[derive(Debug)]
+pub enum Error {
+    Storage(StorageError),
+    Memory(MemoryError),
+    Cli(CliError),
+    Refrigerator(RefrigeratorError),
+    Psu(PsuError),
+    Partition(PartitionError),
+    Fat(FatError),
+    InitializationError,
+    UnknownError,
+}
+
+impl From<StorageError> for Error {
+    fn from(err: StorageError) -> Self { Error::Storage(err) }
+}
+impl From<MemoryError> for Error {
+    fn from(err: MemoryError) -> Self { Error::Memory(err) }
+}
+impl From<CliError> for Error {
+    fn from(err: CliError) -> Self { Error::Cli(err) }
+}
+impl From<RefrigeratorError> for Error {
+    fn from(err: RefrigeratorError) -> Self { Error::Refrigerator(err) }
+}
+impl From<PsuError> for Error {
+    fn from(err: PsuError) -> Self { Error::Psu(err) }
+}
+impl From<PartitionError> for Error {
+    fn from(err: PartitionError) -> Self { Error::Partition(err) }
+}
+impl From<FatError> for Error {
+    fn from(err: FatError) -> Self { Error::Fat(err) }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SystemState {
+    Initializing,
+    Running,
+    Error,
+    ShuttingDown,
+    Off,
+    Unknown,
+}
+
+pub static mut CURRENT_SYSTEM_STATE: SystemState = SystemState::Initializing;
+
+/// # Safety
+/// Global durumu değiştirir; kesme işleyicileriyle eşzamanlı çağrılmamalıdır.
+pub unsafe fn set_system_state(state: SystemState) {
+    CURRENT_SYSTEM_STATE = state;
+}
+
+/// # Safety
+/// Global durumu okur.
+pub unsafe fn get_system_state() -> SystemState {
+    CURRENT_SYSTEM_STATE
+}
+
+#[macro_export]
+macro_rules! log {
+    ($($arg:tt)*) => ({
+        use core::fmt::Write;
+        #[allow(unused_unsafe)]
+        let uart = unsafe { &mut $crate::uart::UART0_GLOBAL };
+        let _ = writeln!(uart, $($arg)*);
+    })
 }
\ No newline at end of file
@@ -0,0 +1,396 @@
+#![no_std]
+
+// GPT (GUID Partition Table) ayrıştırıcısı.
+// Koruyucu MBR'nin (0xEE) arkasındaki birincil GPT başlığını (LBA 1) CRC32 ile doğrular;
+// birincil bozuksa aygıtın son LBA'sındaki yedek başlığa geçer. Bölüm girdi dizisinin CRC'si de
+// kontrol edilir. MBR'nin 32 bitlik LBA alanlarından dolayı gelen ~2.2 TB sınırı GPT'de yoktur.
+
+use core::fmt;
+
+use crate::crc::Crc32;
+use crate::partition::{PartitionError, PartitionInfo, PartitionKind, PartitionTable};
+use crate::storage::{BlockDevice, BLOCK_SIZE};
+
+pub const GPT_SIGNATURE: [u8; 8] = *b"EFI PART";
+pub const GPT_PRIMARY_HEADER_LBA: u64 = 1;
+pub const GPT_MIN_HEADER_SIZE: usize = 92;
+pub const GPT_MIN_ENTRY_SIZE: usize = 128;
+// Okunacak en fazla girdi sayısı (bozuk başlıkların devasa okumalara yol açmaması için).
+pub const GPT_MAX_ENTRIES: u32 = 1024;
+pub const GPT_NAME_LEN: usize = 36; // UTF-16LE karakter sayısı
+
+// Bölüm öznitelikleri
+pub const GPT_ATTR_REQUIRED: u64 = 1 << 0;
+pub const GPT_ATTR_LEGACY_BIOS_BOOTABLE: u64 = 1 << 2;
+
+/// GPT'de disk/bölüm kimlikleri için kullanılan GUID (diskteki karışık endian düzeninde saklanır).
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Guid(pub [u8; 16]);
+
+impl Guid {
+    pub const ZERO: Guid = Guid([0; 16]);
+
+    /// Metin biçimindeki alanlardan (örn. C12A7328-F81F-11D2-BA4B-00A0C93EC93B) GUID oluşturur.
+    pub const fn from_fields(d1: u32, d2: u16, d3: u16, d4: [u8; 8]) -> Self {
+        let a = d1.to_le_bytes();
+        let b = d2.to_le_bytes();
+        let c = d3.to_le_bytes();
+        Guid([a[0], a[1], a[2], a[3], b[0], b[1], c[0], c[1], d4[0], d4[1], d4[2], d4[3], d4[4], d4[5], d4[6], d4[7]])
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0 == [0; 16]
+    }
+}
+
+impl fmt::Display for Guid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let g = &self.0;
+        write!(f, "{:08X}-{:04X}-{:04X}-{:02X}{:02X}-",
+            u32::from_le_bytes([g[0], g[1], g[2], g[3]]),
+            u16::from_le_bytes([g[4], g[5]]),
+            u16::from_le_bytes([g[6], g[7]]),
+            g[8], g[9])?;
+        for byte in &g[10..16] {
+            write!(f, "{:02X}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+// Bilinen bölüm türü GUID'leri
+pub const GUID_EFI_SYSTEM: Guid = Guid::from_fields(0xC12A7328, 0xF81F, 0x11D2, [0xBA, 0x4B, 0x00, 0xA0, 0xC9, 0x3E, 0xC9, 0x3B]);
+pub const GUID_MICROSOFT_BASIC_DATA: Guid = Guid::from_fields(0xEBD0A0A2, 0xB9E5, 0x4433, [0x87, 0xC0, 0x68, 0xB6, 0xB7, 0x26, 0x99, 0xC7]);
+pub const GUID_LINUX_FILESYSTEM: Guid = Guid::from_fields(0x0FC63DAF, 0x8483, 0x4772, [0x8E, 0x79, 0x3D, 0x69, 0xD8, 0x47, 0x7D, 0xE4]);
+
+/// Bölüm türü GUID'i için kısa, okunabilir ad.
+pub fn partition_type_name(type_guid: &Guid) -> &'static str {
+    if *type_guid == GUID_EFI_SYSTEM { "EFI System" }
+    else if *type_guid == GUID_MICROSOFT_BASIC_DATA { "Basic data" }
+    else if *type_guid == GUID_LINUX_FILESYSTEM { "Linux" }
+    else { "Unknown" }
+}
+
+/// GPT bölüm adı (UTF-16LE, sonu 0 ile biten en fazla 36 karakter).
+#[derive(Debug, Copy, Clone)]
+pub struct GptName(pub [u16; GPT_NAME_LEN]);
+
+impl fmt::Display for GptName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let units = self.0.iter().copied().take_while(|&c| c != 0);
+        for c in core::char::decode_utf16(units) {
+            write!(f, "{}", c.unwrap_or(core::char::REPLACEMENT_CHARACTER))?;
+        }
+        Ok(())
+    }
+}
+
+/// Ayrıştırılmış ve CRC'si doğrulanmış GPT başlığı.
+#[derive(Debug, Copy, Clone)]
+pub struct GptHeader {
+    pub revision: u32,
+    pub header_size: u32,
+    pub my_lba: u64,
+    pub alternate_lba: u64,
+    pub first_usable_lba: u64,
+    pub last_usable_lba: u64,
+    pub disk_guid: Guid,
+    pub partition_entry_lba: u64,
+    pub num_partition_entries: u32,
+    pub partition_entry_size: u32,
+    pub partition_entry_array_crc32: u32,
+}
+
+fn le_u32(b: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([b[offset], b[offset + 1], b[offset + 2], b[offset + 3]])
+}
+
+fn le_u64(b: &[u8], offset: usize) -> u64 {
+    (le_u32(b, offset) as u64) | ((le_u32(b, offset + 4) as u64) << 32)
+}
+
+fn guid_at(b: &[u8], offset: usize) -> Guid {
+    let mut g = [0u8; 16];
+    g.copy_from_slice(&b[offset..offset + 16]);
+    Guid(g)
+}
+
+impl GptHeader {
+    /// Bir sektörü GPT başlığı olarak ayrıştırır; imzayı, boyutu ve başlık CRC'sini doğrular.
+    /// `expected_lba` başlığın okunduğu LBA'dır ve başlıktaki `my_lba` ile eşleşmelidir.
+    pub fn parse(sector: &[u8], expected_lba: u64) -> Result<Self, PartitionError> {
+        if sector.len() < BLOCK_SIZE || sector[0..8] != GPT_SIGNATURE {
+            return Err(PartitionError::InvalidGptHeader);
+        }
+        let header_size = le_u32(sector, 12) as usize;
+        if !(GPT_MIN_HEADER_SIZE..=BLOCK_SIZE).contains(&header_size) {
+            return Err(PartitionError::InvalidGptHeader);
+        }
+
+        // Başlık CRC'si, CRC alanı sıfırlanmış haliyle başlığın tamamı üzerinden hesaplanır.
+        let stored_crc = le_u32(sector, 16);
+        let mut crc = Crc32::new();
+        crc.update(&sector[0..16]);
+        crc.update(&[0u8; 4]);
+        crc.update(&sector[20..header_size]);
+        if crc.finish() != stored_crc {
+            return Err(PartitionError::GptHeaderCrcMismatch);
+        }
+
+        let header = GptHeader {
+            revision: le_u32(sector, 8),
+            header_size: header_size as u32,
+            my_lba: le_u64(sector, 24),
+            alternate_lba: le_u64(sector, 32),
+            first_usable_lba: le_u64(sector, 40),
+            last_usable_lba: le_u64(sector, 48),
+            disk_guid: guid_at(sector, 56),
+            partition_entry_lba: le_u64(sector, 72),
+            num_partition_entries: le_u32(sector, 80),
+            partition_entry_size: le_u32(sector, 84),
+            partition_entry_array_crc32: le_u32(sector, 88),
+        };
+
+        let entry_size = header.partition_entry_size as usize;
+        if header.my_lba != expected_lba
+            || !(GPT_MIN_ENTRY_SIZE..=BLOCK_SIZE).contains(&entry_size)
+            || !entry_size.is_power_of_two()
+            || header.num_partition_entries > GPT_MAX_ENTRIES
+            || header.first_usable_lba > header.last_usable_lba
+        {
+            return Err(PartitionError::InvalidGptHeader);
+        }
+        Ok(header)
+    }
+
+    /// Aygıtın belirtilen LBA'sındaki başlığı okur ve doğrular.
+    pub fn read<D: BlockDevice + ?Sized>(device: &mut D, lba: u64) -> Result<Self, PartitionError> {
+        let mut sector = [0u8; BLOCK_SIZE];
+        device.read_block(lba, &mut sector)?;
+        GptHeader::parse(&sector, lba)
+    }
+}
+
+/// Aygıtta geçerli bir GPT başlığı (birincil veya yedek) olup olmadığını kontrol eder.
+/// Kapasite sınırına karar verilirken kullanılır; girdi dizisi burada doğrulanmaz.
+pub fn probe<D: BlockDevice + ?Sized>(device: &mut D) -> bool {
+    if GptHeader::read(device, GPT_PRIMARY_HEADER_LBA).is_ok() {
+        return true;
+    }
+    match device.block_count() {
+        Some(count) if count > 0 => GptHeader::read(device, count - 1).is_ok(),
+        _ => false,
+    }
+}
+
+/// GPT bölümlerini tabloya ekler. Birincil başlık veya girdi dizisi bozuksa yedeği dener.
+pub fn read_partitions<D: BlockDevice + ?Sized>(device: &mut D, table: &mut PartitionTable) -> Result<GptHeader, PartitionError> {
+    match read_from(device, GPT_PRIMARY_HEADER_LBA, table) {
+        Ok(header) => Ok(header),
+        Err(primary_err) => {
+            table.clear();
+            let last_lba = match device.block_count() {
+                Some(count) if count > 0 => count - 1,
+                _ => return Err(primary_err),
+            };
+            read_from(device, last_lba, table).map_err(|_| primary_err)
+        }
+    }
+}
+
+fn read_from<D: BlockDevice + ?Sized>(device: &mut D, header_lba: u64, table: &mut PartitionTable) -> Result<GptHeader, PartitionError> {
+    let header = GptHeader::read(device, header_lba)?;
+
+    let entry_size = header.partition_entry_size as usize;
+    let entries_per_block = BLOCK_SIZE / entry_size;
+    let mut remaining = header.num_partition_entries as usize;
+    let mut lba = header.partition_entry_lba;
+    let mut entry_index = 0usize;
+    let mut crc = Crc32::new();
+    let mut block = [0u8; BLOCK_SIZE];
+
+    while remaining > 0 {
+        device.read_block(lba, &mut block)?;
+        let in_block = core::cmp::min(remaining, entries_per_block);
+        crc.update(&block[..in_block * entry_size]);
+
+        for i in 0..in_block {
+            entry_index += 1;
+            let raw = &block[i * entry_size..(i + 1) * entry_size];
+            let type_guid = guid_at(raw, 0);
+            if type_guid.is_zero() {
+                continue; // Kullanılmayan girdi
+            }
+            let first_lba = le_u64(raw, 32);
+            let last_lba = le_u64(raw, 40);
+            if last_lba < first_lba || first_lba < header.first_usable_lba || last_lba > header.last_usable_lba {
+                return Err(PartitionError::InvalidEntry);
+            }
+            let mut name = [0u16; GPT_NAME_LEN];
+            for (n, unit) in name.iter_mut().enumerate() {
+                *unit = u16::from_le_bytes([raw[56 + n * 2], raw[57 + n * 2]]);
+            }
+            table.push(PartitionInfo {
+                index: entry_index,
+                start_lba: first_lba,
+                block_count: last_lba - first_lba + 1,
+                kind: PartitionKind::Gpt {
+                    type_guid,
+                    unique_guid: guid_at(raw, 16),
+                    attributes: le_u64(raw, 48),
+                    name: GptName(name),
+                },
+            })?;
+        }
+
+        remaining -= in_block;
+        lba += 1;
+    }
+
+    if crc.finish() != header.partition_entry_array_crc32 {
+        return Err(PartitionError::GptEntryArrayCrcMismatch);
+    }
+    Ok(header)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crc::crc32;
+    use crate::storage::{sd_capacity, RamDisk, SdCapacity, MBR_MAX_BLOCKS, SD_MAX_ADDRESSABLE_BLOCKS};
+
+    const DISK_BLOCKS: u64 = 128;
+    const ENTRIES: u32 = 16;
+    const ENTRY_BLOCKS: u64 = ENTRIES as u64 * GPT_MIN_ENTRY_SIZE as u64 / BLOCK_SIZE as u64;
+    const BACKUP_HEADER_LBA: u64 = DISK_BLOCKS - 1;
+    const BACKUP_ENTRIES_LBA: u64 = BACKUP_HEADER_LBA - ENTRY_BLOCKS;
+    const FIRST_USABLE: u64 = 2 + ENTRY_BLOCKS;
+    const LAST_USABLE: u64 = BACKUP_ENTRIES_LBA - 1;
+
+    fn block(disk: &mut [u8], lba: u64) -> &mut [u8] {
+        &mut disk[lba as usize * BLOCK_SIZE..(lba as usize + 1) * BLOCK_SIZE]
+    }
+
+    fn entry_array() -> Vec<u8> {
+        let mut entries = vec![0u8; ENTRIES as usize * GPT_MIN_ENTRY_SIZE];
+        let mut put = |slot: usize, type_guid: Guid, first: u64, last: u64, attributes: u64, name: &str| {
+            let raw = &mut entries[slot * GPT_MIN_ENTRY_SIZE..(slot + 1) * GPT_MIN_ENTRY_SIZE];
+            raw[0..16].copy_from_slice(&type_guid.0);
+            raw[16] = slot as u8 + 1; // benzersiz GUID
+            raw[32..40].copy_from_slice(&first.to_le_bytes());
+            raw[40..48].copy_from_slice(&last.to_le_bytes());
+            raw[48..56].copy_from_slice(&attributes.to_le_bytes());
+            for (n, unit) in name.encode_utf16().enumerate() {
+                raw[56 + n * 2..58 + n * 2].copy_from_slice(&unit.to_le_bytes());
+            }
+        };
+        put(0, GUID_EFI_SYSTEM, FIRST_USABLE, 39, GPT_ATTR_LEGACY_BIOS_BOOTABLE, "boot");
+        put(2, GUID_LINUX_FILESYSTEM, 40, LAST_USABLE, 0, "rootfs");
+        entries
+    }
+
+    fn put_header(disk: &mut [u8], my_lba: u64, alternate_lba: u64, entries_lba: u64, entries_crc: u32) {
+        let header = block(disk, my_lba);
+        header[0..8].copy_from_slice(&GPT_SIGNATURE);
+        header[8..12].copy_from_slice(&0x0001_0000u32.to_le_bytes());
+        header[12..16].copy_from_slice(&(GPT_MIN_HEADER_SIZE as u32).to_le_bytes());
+        header[24..32].copy_from_slice(&my_lba.to_le_bytes());
+        header[32..40].copy_from_slice(&alternate_lba.to_le_bytes());
+        header[40..48].copy_from_slice(&FIRST_USABLE.to_le_bytes());
+        header[48..56].copy_from_slice(&LAST_USABLE.to_le_bytes());
+        header[56..72].copy_from_slice(&[0x5A; 16]);
+        header[72..80].copy_from_slice(&entries_lba.to_le_bytes());
+        header[80..84].copy_from_slice(&ENTRIES.to_le_bytes());
+        header[84..88].copy_from_slice(&(GPT_MIN_ENTRY_SIZE as u32).to_le_bytes());
+        header[88..92].copy_from_slice(&entries_crc.to_le_bytes());
+        let crc = crc32(&header[..GPT_MIN_HEADER_SIZE]);
+        header[16..20].copy_from_slice(&crc.to_le_bytes());
+    }
+
+    // Birincil ve yedek başlığı ve girdi dizilerini içeren geçerli bir GPT diski.
+    fn gpt_disk() -> Vec<u8> {
+        let mut disk = vec![0u8; DISK_BLOCKS as usize * BLOCK_SIZE];
+        let entries = entry_array();
+        let crc = crc32(&entries);
+        for lba in [2, BACKUP_ENTRIES_LBA] {
+            disk[lba as usize * BLOCK_SIZE..][..entries.len()].copy_from_slice(&entries);
+        }
+        put_header(&mut disk, GPT_PRIMARY_HEADER_LBA, BACKUP_HEADER_LBA, 2, crc);
+        put_header(&mut disk, BACKUP_HEADER_LBA, GPT_PRIMARY_HEADER_LBA, BACKUP_ENTRIES_LBA, crc);
+        disk
+    }
+
+    fn read(disk: &mut [u8]) -> Result<(GptHeader, PartitionTable), PartitionError> {
+        let mut table = PartitionTable::new();
+        let header = read_partitions(&mut RamDisk::new(disk), &mut table)?;
+        Ok((header, table))
+    }
+
+    #[test]
+    fn primary_header_and_entries() {
+        let mut disk = gpt_disk();
+        let (header, table) = read(&mut disk).unwrap();
+        assert_eq!(header.my_lba, GPT_PRIMARY_HEADER_LBA);
+        let found: Vec<_> = table.iter().map(|p| (p.index, p.start_lba, p.block_count, p.is_bootable(), p.type_name())).collect();
+        assert_eq!(found, [(1, FIRST_USABLE, 40 - FIRST_USABLE, true, "EFI System"), (3, 40, LAST_USABLE - 39, false, "Linux")]);
+        match table.get(3).unwrap().kind {
+            PartitionKind::Gpt { name, .. } => assert_eq!(format!("{}", name), "rootfs"),
+            PartitionKind::Mbr { .. } => panic!("expected a GPT entry"),
+        }
+    }
+
+    #[test]
+    fn bad_primary_header_crc_falls_back_to_backup() {
+        let mut disk = gpt_disk();
+        block(&mut disk, GPT_PRIMARY_HEADER_LBA)[40] ^= 0x01; // CRC'yi güncellemeden alanı boz
+        assert!(matches!(GptHeader::read(&mut RamDisk::new(&mut disk), GPT_PRIMARY_HEADER_LBA), Err(PartitionError::GptHeaderCrcMismatch)));
+
+        let (header, table) = read(&mut disk).unwrap();
+        assert_eq!(header.my_lba, BACKUP_HEADER_LBA);
+        assert_eq!(table.len(), 2);
+        assert!(probe(&mut RamDisk::new(&mut disk)));
+    }
+
+    #[test]
+    fn bad_primary_entry_array_falls_back_to_backup() {
+        let mut disk = gpt_disk();
+        block(&mut disk, 2)[100] ^= 0x01;
+        let (header, table) = read(&mut disk).unwrap();
+        assert_eq!(header.my_lba, BACKUP_HEADER_LBA);
+        assert_eq!(table.len(), 2);
+    }
+
+    #[test]
+    fn entry_array_with_bad_crc_is_rejected() {
+        let mut disk = gpt_disk();
+        for lba in [2, BACKUP_ENTRIES_LBA] {
+            block(&mut disk, lba)[100] ^= 0x01;
+        }
+        assert!(matches!(read(&mut disk), Err(PartitionError::GptEntryArrayCrcMismatch)));
+    }
+
+    #[test]
+    fn gpt_card_larger_than_32_bit_block_addresses_is_capped() {
+        // Kartın ham kapasitesi (CSD) 2^32 bloğu aşıyor; RamDisk kartın GPT başlığını taşıyan ilk bloklarıdır.
+        let raw_blocks = SD_MAX_ADDRESSABLE_BLOCKS + 4096;
+        let mut disk = gpt_disk();
+        let capacity = sd_capacity(&mut RamDisk::new(&mut disk), raw_blocks);
+        assert_eq!(capacity, SdCapacity { blocks: SD_MAX_ADDRESSABLE_BLOCKS, gpt: true, mbr_capped: false });
+        assert!(u32::try_from(capacity.blocks - 1).is_ok()); // Son blok da komut argümanına sığar
+
+        // Aynı kart GPT'siz ise MBR sınırı bildirilir.
+        let mut blank = vec![0u8; DISK_BLOCKS as usize * BLOCK_SIZE];
+        let capacity = sd_capacity(&mut RamDisk::new(&mut blank), raw_blocks);
+        assert_eq!(capacity, SdCapacity { blocks: MBR_MAX_BLOCKS, gpt: false, mbr_capped: true });
+
+        // Sınırın altındaki kartlar olduğu gibi kullanılır.
+        assert_eq!(sd_capacity(&mut RamDisk::new(&mut disk), 1 << 31).blocks, 1 << 31);
+    }
+
+    #[test]
+    fn missing_headers_are_rejected() {
+        let mut disk = vec![0u8; DISK_BLOCKS as usize * BLOCK_SIZE];
+        assert!(matches!(read(&mut disk), Err(PartitionError::InvalidGptHeader)));
+        assert!(!probe(&mut RamDisk::new(&mut disk)));
+    }
+}
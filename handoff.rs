@@ -0,0 +1,280 @@
+#![no_std]
+
+// Yüklenen işletim sistemine aktarılan boot handoff tablosu.
+// Firmware, imaja atlamadan hemen önce bu tabloyu doldurur ve RISC-V çağrı kuralına göre
+//   a0 = hart id
+//   a1 = handoff tablosunun fiziksel adresi
+// ile giriş noktasını çağırır. Tablo firmware'e ayrılmış bölgede durur; bellek haritasında
+// FirmwareReserved olarak işaretlidir, işletim sistemi okuyana kadar üzerine yazmamalıdır.
+// Alan eklenirken sürüm artırılmalı ve yeni alanlar sona eklenmelidir (`size` alanı ile
+// eski çekirdekler bilmedikleri alanları atlayabilir).
+
+use crate::boot::{BootPath, LoadedImage};
+use crate::memory::{self, MemoryKind, MemoryRegion};
+use crate::storage::BlockDevice;
+
+pub const HANDOFF_MAGIC: u32 = 0x4F48_4250; // "PBHO"
+pub const HANDOFF_VERSION: u32 = 3; // 2: fdt alanları eklendi, 3: boot_slot
+pub const HANDOFF_MAX_MEMORY_REGIONS: usize = 8;
+pub const HANDOFF_COMMAND_LINE_LEN: usize = 256;
+pub const HANDOFF_FIRMWARE_VERSION_LEN: usize = 16;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[repr(u32)]
+pub enum BootDevice {
+    Unknown = 0,
+    Emmc = 1,
+    SdCard = 2,
+    Serial = 3, // loady/loadx ile UART'tan yüklendi
+}
+
+impl BootDevice {
+    /// CLI/boot yolundaki aygıt adından ("emic", "sd", "uart").
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "emic" => BootDevice::Emmc,
+            "sd" => BootDevice::SdCard,
+            "uart" => BootDevice::Serial,
+            _ => BootDevice::Unknown,
+        }
+    }
+}
+
+/// Bir depolama aygıtının durumu.
+#[derive(Debug, Copy, Clone)]
+#[repr(C)]
+pub struct StorageDeviceInfo {
+    pub device: u32,  // BootDevice
+    pub present: u32, // 1: başlatıldı
+    pub block_count: u64,
+}
+
+#[derive(Debug)]
+#[repr(C)]
+pub struct BootHandoff {
+    pub magic: u32,
+    pub version: u32,
+    pub size: u32, // size_of::<BootHandoff>()
+    pub hart_id: u32,
+    pub firmware_version: [u8; HANDOFF_FIRMWARE_VERSION_LEN], // UTF-8, sonu 0 ile dolgulu
+    pub memory_region_count: u32,
+    pub reserved0: u32,
+    pub memory_map: [MemoryRegion; HANDOFF_MAX_MEMORY_REGIONS],
+    pub boot_device: u32, // BootDevice
+    pub boot_partition: u32,
+    pub storage: [StorageDeviceInfo; 2], // [eMMC, SD]
+    pub psu_power_good: u32,
+    pub reserved1: u32,
+    pub console_uart_base: u64,
+    pub boot_log: u64, // measured::BootLog adresi
+    pub image_load_address: u64,
+    pub image_size: u64,
+    pub command_line: [u8; HANDOFF_COMMAND_LINE_LEN], // UTF-8, sonu 0 ile biten
+    pub fdt: u64,      // Flattened Device Tree adresi (a2 ile de verilir), yoksa 0
+    pub fdt_size: u64,
+    pub boot_slot: u32, // 0: A/B slotu kullanılmadı, 1: A, 2: B
+    pub reserved2: u32,
+}
+
+const EMPTY_STORAGE_INFO: StorageDeviceInfo = StorageDeviceInfo { device: 0, present: 0, block_count: 0 };
+
+impl BootHandoff {
+    pub const fn new() -> Self {
+        BootHandoff {
+            magic: HANDOFF_MAGIC,
+            version: HANDOFF_VERSION,
+            size: core::mem::size_of::<BootHandoff>() as u32,
+            hart_id: 0,
+            firmware_version: [0; HANDOFF_FIRMWARE_VERSION_LEN],
+            memory_region_count: 0,
+            reserved0: 0,
+            memory_map: [MemoryRegion::EMPTY; HANDOFF_MAX_MEMORY_REGIONS],
+            boot_device: BootDevice::Unknown as u32,
+            boot_partition: 0,
+            storage: [EMPTY_STORAGE_INFO; 2],
+            psu_power_good: 0,
+            reserved1: 0,
+            console_uart_base: 0,
+            boot_log: 0,
+            image_load_address: 0,
+            image_size: 0,
+            command_line: [0; HANDOFF_COMMAND_LINE_LEN],
+            fdt: 0,
+            fdt_size: 0,
+            boot_slot: 0,
+            reserved2: 0,
+        }
+    }
+
+    fn push_memory_region(&mut self, region: MemoryRegion) {
+        let count = self.memory_region_count as usize;
+        if count < HANDOFF_MAX_MEMORY_REGIONS {
+            self.memory_map[count] = region;
+            self.memory_region_count += 1;
+        }
+    }
+
+    /// Temel bellek haritasını yazar. Yüklenen imaj, içinde bulunduğu kullanılabilir bölgeden ayrılır:
+    /// bölge imajın önü ve arkası olarak ikiye bölünür, böylece haritadaki bölgeler çakışmaz.
+    pub fn set_memory_map(&mut self, image_start: u64, image_size: u64) {
+        let image_end = image_start + image_size;
+        let mut image_placed = false;
+        for region in memory::memory_map() {
+            let region_end = region.base + region.size;
+            if image_placed || region.kind != MemoryKind::Usable as u32 || image_start < region.base || image_end > region_end {
+                self.push_memory_region(region);
+                continue;
+            }
+            if image_start > region.base {
+                self.push_memory_region(MemoryRegion { size: image_start - region.base, ..region });
+            }
+            self.push_memory_region(MemoryRegion { base: image_start, size: image_size, kind: MemoryKind::LoadedImage as u32, ..region });
+            if region_end > image_end {
+                self.push_memory_region(MemoryRegion { base: image_end, size: region_end - image_end, ..region });
+            }
+            image_placed = true;
+        }
+        if !image_placed {
+            // Kullanılabilir RAM dışındaki imaj (yükleme aralığı doğrulamasından geçmez, yine de bildirilir).
+            self.push_memory_region(MemoryRegion { base: image_start, size: image_size, kind: MemoryKind::LoadedImage as u32, reserved: 0 });
+        }
+    }
+
+    /// Komut satırını kopyalar; sığmayan kısım kesilir (son bayt her zaman 0 kalır).
+    pub fn set_command_line(&mut self, command_line: &str) {
+        copy_str(&mut self.command_line, command_line);
+    }
+
+    pub fn command_line(&self) -> &str {
+        let len = self.command_line.iter().position(|&b| b == 0).unwrap_or(HANDOFF_COMMAND_LINE_LEN);
+        core::str::from_utf8(&self.command_line[..len]).unwrap_or("")
+    }
+}
+
+fn copy_str(dest: &mut [u8], src: &str) {
+    dest.fill(0);
+    let len = core::cmp::min(src.len(), dest.len() - 1);
+    dest[..len].copy_from_slice(&src.as_bytes()[..len]);
+}
+
+/// Çalışan hart'ın kimliği.
+#[cfg(not(feature = "hosted"))]
+pub fn current_hart_id() -> usize {
+    riscv::register::mhartid::read()
+}
+
+/// Host simülasyonunda tek bir hart (0) vardır.
+#[cfg(feature = "hosted")]
+pub fn current_hart_id() -> usize {
+    0
+}
+
+// --- Global Handoff Tablosu ---
+// Firmware'in statik verisiyle birlikte ayrılmış bölgede durur.
+// # Safety: Global mutable static kullanimi unsafe'dir.
+pub static mut BOOT_HANDOFF: BootHandoff = BootHandoff::new();
+
+/// Yüklenen imaj için handoff tablosunu doldurur ve adresini döndürür.
+/// `fdt` üretilen device tree'nin adresi ve boyutudur (üretilemediyse None).
+/// # Safety
+/// Global aygıt durumlarını okur ve global handoff tablosunu değiştirir.
+pub unsafe fn prepare(image: &LoadedImage, boot_path: &BootPath, command_line: &str, fdt: Option<(usize, usize)>) -> usize {
+    let handoff = &mut BOOT_HANDOFF;
+    *handoff = BootHandoff::new();
+    handoff.hart_id = current_hart_id() as u32;
+    copy_str(&mut handoff.firmware_version, crate::firmware_common::FIRMWARE_VERSION);
+
+    handoff.set_memory_map(image.header.load_address, image.header.payload_size as u64);
+
+    handoff.boot_device = BootDevice::from_name(boot_path.device) as u32;
+    handoff.boot_partition = boot_path.partition as u32;
+    if let Some(emic) = &mut crate::storage::EMIC_STORAGE_GLOBAL {
+        handoff.storage[0] = StorageDeviceInfo { device: BootDevice::Emmc as u32, present: 1, block_count: emic.block_count().unwrap_or(0) };
+    }
+    if let Some(sd) = &mut crate::storage::SD_CARD_STORAGE_GLOBAL {
+        handoff.storage[1] = StorageDeviceInfo { device: BootDevice::SdCard as u32, present: 1, block_count: sd.block_count().unwrap_or(0) };
+    }
+    if let Some(psu) = &mut crate::psu::PSU_MONITOR_GLOBAL {
+        handoff.psu_power_good = psu.is_power_good().unwrap_or(false) as u32;
+    }
+
+    handoff.console_uart_base = crate::uart::UART0_BASE_ADDRESS as u64;
+    handoff.boot_log = core::ptr::addr_of!(crate::measured::BOOT_LOG) as u64;
+    handoff.image_load_address = image.header.load_address;
+    handoff.image_size = image.header.payload_size as u64;
+    handoff.set_command_line(command_line);
+    if let Some((address, size)) = fdt {
+        handoff.fdt = address as u64;
+        handoff.fdt_size = size as u64;
+    }
+    handoff.boot_slot = match crate::slots::booted_slot() {
+        Some(crate::slots::Slot::A) => 1,
+        Some(crate::slots::Slot::B) => 2,
+        None => 0,
+    };
+
+    handoff as *const BootHandoff as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::mem::{offset_of, size_of};
+    use crate::memory::{FIRMWARE_RESERVED_BYTES, FIRMWARE_RESERVED_END, LPDDR1_BASE_ADDRESS, LPDDR1_END_ADDRESS};
+
+    // Tablo işletim sistemiyle paylaşılan bir ABI'dır; yerleşim değişirse sürüm de artırılmalıdır.
+    #[test]
+    fn layout_is_stable() {
+        assert_eq!(size_of::<MemoryRegion>(), 24);
+        assert_eq!(size_of::<StorageDeviceInfo>(), 16);
+        assert_eq!(size_of::<BootHandoff>(), 592);
+        assert_eq!(offset_of!(BootHandoff, firmware_version), 16);
+        assert_eq!(offset_of!(BootHandoff, memory_region_count), 32);
+        assert_eq!(offset_of!(BootHandoff, memory_map), 40);
+        assert_eq!(offset_of!(BootHandoff, boot_device), 232);
+        assert_eq!(offset_of!(BootHandoff, storage), 240);
+        assert_eq!(offset_of!(BootHandoff, console_uart_base), 280);
+        assert_eq!(offset_of!(BootHandoff, boot_log), 288);
+        assert_eq!(offset_of!(BootHandoff, command_line), 312);
+        assert_eq!(offset_of!(BootHandoff, fdt), 568);
+        assert_eq!(offset_of!(BootHandoff, boot_slot), 584);
+        assert_eq!(BootHandoff::new().size as usize, size_of::<BootHandoff>());
+    }
+
+    #[test]
+    fn long_command_line_keeps_the_terminating_nul() {
+        let mut handoff = BootHandoff::new();
+        let long = "x".repeat(HANDOFF_COMMAND_LINE_LEN + 10);
+        handoff.set_command_line(&long);
+        assert_eq!(handoff.command_line(), &long[..HANDOFF_COMMAND_LINE_LEN - 1]);
+        assert_eq!(handoff.command_line[HANDOFF_COMMAND_LINE_LEN - 1], 0);
+        // Kısa bir satır öncekinin kalıntısını bırakmaz.
+        handoff.set_command_line("quiet");
+        assert_eq!(handoff.command_line(), "quiet");
+        assert!(handoff.command_line[5..].iter().all(|&b| b == 0));
+    }
+
+    fn regions(handoff: &BootHandoff) -> Vec<(u64, u64, u32)> {
+        handoff.memory_map[..handoff.memory_region_count as usize].iter().map(|r| (r.base, r.size, r.kind)).collect()
+    }
+
+    #[test]
+    fn loaded_image_is_cut_out_of_usable_ram() {
+        let (reserved, usable, image) = (MemoryKind::FirmwareReserved as u32, MemoryKind::Usable as u32, MemoryKind::LoadedImage as u32);
+        let (ram_start, usable_start, ram_end) = (LPDDR1_BASE_ADDRESS as u64, FIRMWARE_RESERVED_END as u64, LPDDR1_END_ADDRESS as u64);
+
+        let mut handoff = BootHandoff::new();
+        handoff.set_memory_map(usable_start + 0x1000, 0x2000);
+        assert_eq!(regions(&handoff), [
+            (ram_start, FIRMWARE_RESERVED_BYTES as u64, reserved),
+            (usable_start, 0x1000, usable),
+            (usable_start + 0x1000, 0x2000, image),
+            (usable_start + 0x3000, ram_end - usable_start - 0x3000, usable),
+        ]);
+
+        // İmaj kullanılabilir bölgenin başında: önünde boş bölge kalmaz.
+        let mut handoff = BootHandoff::new();
+        handoff.set_memory_map(usable_start, 0x2000);
+        assert_eq!(regions(&handoff)[1..], [(usable_start, 0x2000, image), (usable_start + 0x2000, ram_end - usable_start - 0x2000, usable)]);
+    }
+}
@@ -0,0 +1,230 @@
+#![no_std]
+
+// Host (Linux) simülasyon arka ucu.
+// Bu modül sadece `hosted` feature'ı ile derlenir. Sürücüler (UART, eMMC, SD, PSU, buzdolabı)
+// bu modu gördüklerinde MMIO yerine buradaki simüle edilmiş aygıtları kullanır:
+// - UART0      -> stdin/stdout (veya entegrasyon testleri için bellek içi kuyruk)
+// - eMMC / SD  -> imaj dosyaları (PACKETBOX_EMMC_IMAGE / PACKETBOX_SD_IMAGE)
+// - LPDDR1     -> process içinde ayrılmış bir bellek penceresi
+// - PSU/Buzdolabı -> basit durum değişkenleri
+
+extern crate std;
+
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+use std::vec::Vec;
+
+use crate::memory::LPDDR1_BASE_ADDRESS;
+use crate::storage::{StorageError, BLOCK_SIZE};
+
+// eMMC imaj dosyasının yolunu veren ortam değişkeni.
+pub const EMMC_IMAGE_ENV: &str = "PACKETBOX_EMMC_IMAGE";
+// SD kart imaj dosyasının yolunu veren ortam değişkeni.
+pub const SD_IMAGE_ENV: &str = "PACKETBOX_SD_IMAGE";
+// "1" ise PSU Power Good sinyali hiç gelmez (hata senaryolarını denemek için).
+pub const PSU_FAIL_ENV: &str = "PACKETBOX_PSU_FAIL";
+// Güvenli boot testleri için ek güvenilir anahtar: "<key_id>:<64 hex karakter Ed25519 açık anahtar>".
+pub const BOOT_PUBKEY_ENV: &str = "PACKETBOX_BOOT_PUBKEY";
+
+// Simüle edilen fiziksel RAM penceresinin boyutu (LPDDR1_BASE_ADDRESS'ten itibaren).
+// Yükleme adresleri LPDDR1'in dışına taşabildiği için pencere LPDDR1'den geniş tutulur.
+// Sayfalar işletim sistemi tarafından tembel (lazy) ayrıldığı için gerçek bellek tüketimi küçüktür.
+pub const HOSTED_RAM_WINDOW_BYTES: usize = 512 * 1024 * 1024;
+
+// --- Konsol (UART0) ---
+
+struct ConsoleState {
+    started: bool,
+    closed: bool,
+    capture: bool,
+    rx: VecDeque<u8>,
+    tx: Vec<u8>,
+}
+
+static CONSOLE: Mutex<ConsoleState> = Mutex::new(ConsoleState {
+    started: false,
+    closed: false,
+    capture: false,
+    rx: VecDeque::new(),
+    tx: Vec::new(),
+});
+
+/// Konsolu başlatır: stdin'i okuyan bir arka plan thread'i açar.
+/// Birden fazla çağrı güvenlidir, thread sadece bir kez başlatılır.
+pub fn console_init() {
+    let mut console = CONSOLE.lock().unwrap();
+    if console.started || console.capture {
+        return;
+    }
+    console.started = true;
+    drop(console);
+
+    std::thread::spawn(|| {
+        let mut stdin = std::io::stdin();
+        let mut chunk = [0u8; 256];
+        loop {
+            match stdin.read(&mut chunk) {
+                Ok(0) | Err(_) => {
+                    CONSOLE.lock().unwrap().closed = true;
+                    break;
+                }
+                Ok(n) => CONSOLE.lock().unwrap().rx.extend(&chunk[..n]),
+            }
+        }
+    });
+}
+
+/// Konsolu entegrasyon testleri için bellek içi moda alır.
+/// Bu modda stdin okunmaz, çıktı stdout yerine bir arabellekte toplanır.
+pub fn console_capture() {
+    let mut console = CONSOLE.lock().unwrap();
+    console.capture = true;
+    console.closed = false;
+}
+
+/// Konsol giriş kuyruğuna bayt ekler (test sürücüsü "klavye" olarak kullanır).
+pub fn console_push_input(bytes: &[u8]) {
+    CONSOLE.lock().unwrap().rx.extend(bytes);
+}
+
+/// Toplanan konsol çıktısını alır ve arabelleği boşaltır.
+pub fn console_take_output() -> Vec<u8> {
+    core::mem::take(&mut CONSOLE.lock().unwrap().tx)
+}
+
+/// Giriş kaynağı kapandıysa (stdin EOF) ve okunacak bayt kalmadıysa true döner.
+pub fn console_closed() -> bool {
+    let console = CONSOLE.lock().unwrap();
+    console.closed && console.rx.is_empty()
+}
+
+pub fn console_read_byte() -> Option<u8> {
+    CONSOLE.lock().unwrap().rx.pop_front()
+}
+
+pub fn console_write_byte(byte: u8) {
+    let mut console = CONSOLE.lock().unwrap();
+    if console.capture {
+        console.tx.push(byte);
+    } else {
+        let mut stdout = std::io::stdout();
+        let _ = stdout.write_all(&[byte]);
+        let _ = stdout.flush();
+    }
+}
+
+// --- Blok aygıtları (imaj dosyaları) ---
+
+/// Bir imaj dosyasını 512 baytlık bloklar halinde sunan simüle edilmiş blok aygıtı.
+pub struct BlockImage {
+    file: File,
+    blocks: u64,
+}
+
+impl BlockImage {
+    /// Ortam değişkeninde verilen imaj dosyasını açar. Değişken yoksa veya dosya açılamazsa hata döner.
+    pub fn open_from_env(var: &str) -> Result<Self, StorageError> {
+        let path = std::env::var(var).map_err(|_| StorageError::UnsupportedDevice)?;
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(&path)
+            .map_err(|_| StorageError::InitializationError)?;
+        let len = file.metadata().map_err(|_| StorageError::InitializationError)?.len();
+        Ok(BlockImage { file, blocks: len / BLOCK_SIZE as u64 })
+    }
+
+    pub fn block_count(&self) -> u64 {
+        self.blocks
+    }
+
+    pub fn read_block(&mut self, lba: u64, buffer: &mut [u8]) -> Result<(), StorageError> {
+        if lba >= self.blocks { return Err(StorageError::InvalidLba); }
+        self.file.seek(SeekFrom::Start(lba * BLOCK_SIZE as u64)).map_err(|_| StorageError::ReadError)?;
+        self.file.read_exact(&mut buffer[..BLOCK_SIZE]).map_err(|_| StorageError::ReadError)
+    }
+
+    pub fn write_block(&mut self, lba: u64, data: &[u8]) -> Result<(), StorageError> {
+        if lba >= self.blocks { return Err(StorageError::InvalidLba); }
+        self.file.seek(SeekFrom::Start(lba * BLOCK_SIZE as u64)).map_err(|_| StorageError::WriteError)?;
+        self.file.write_all(&data[..BLOCK_SIZE]).map_err(|_| StorageError::WriteError)
+    }
+}
+
+// --- Fiziksel bellek penceresi ---
+
+static RAM_WINDOW: Mutex<usize> = Mutex::new(0);
+
+/// Simüle edilen RAM penceresini ayırır (LPDDR1 başlatmanın host karşılığı).
+pub fn ram_init() {
+    let mut base = RAM_WINDOW.lock().unwrap();
+    if *base == 0 {
+        let window: &'static mut [u8] = std::vec![0u8; HOSTED_RAM_WINDOW_BYTES].leak();
+        *base = window.as_mut_ptr() as usize;
+    }
+}
+
+/// Firmware'in gördüğü fiziksel adres aralığını host işaretçisine çevirir.
+/// Aralık simüle edilen pencerenin dışındaysa None döner.
+pub fn ram_ptr(phys_addr: usize, len: usize) -> Option<*mut u8> {
+    let base = *RAM_WINDOW.lock().unwrap();
+    if base == 0 || phys_addr < LPDDR1_BASE_ADDRESS {
+        return None;
+    }
+    let offset = phys_addr - LPDDR1_BASE_ADDRESS;
+    if offset.checked_add(len)? > HOSTED_RAM_WINDOW_BYTES {
+        return None;
+    }
+    Some((base + offset) as *mut u8)
+}
+
+// --- Zaman ---
+
+static START: OnceLock<Instant> = OnceLock::new();
+
+/// Simülasyonun başlangıcından beri geçen süre (mikrosaniye); CLINT mtime sayacının karşılığı.
+pub fn elapsed_micros() -> u64 {
+    START.get_or_init(Instant::now).elapsed().as_micros() as u64
+}
+
+// --- PSU ---
+
+static PSU_ON: Mutex<bool> = Mutex::new(false);
+
+pub fn psu_set_on(on: bool) {
+    *PSU_ON.lock().unwrap() = on;
+}
+
+pub fn psu_power_good() -> bool {
+    let fail = std::env::var(PSU_FAIL_ENV).map(|v| v == "1").unwrap_or(false);
+    *PSU_ON.lock().unwrap() && !fail
+}
+
+// --- Güvenli boot test anahtarı ---
+
+/// BOOT_PUBKEY_ENV ile verilen test anahtarını ayrıştırır. Değişken yoksa veya biçim bozuksa None döner.
+pub fn boot_pubkey_from_env() -> Option<(u32, [u8; 32])> {
+    let value = std::env::var(BOOT_PUBKEY_ENV).ok()?;
+    let (id, hex) = value.trim().split_once(':')?;
+    let id = id.parse::<u32>().ok()?;
+    if hex.len() != 64 {
+        return None;
+    }
+    let mut key = [0u8; 32];
+    for (i, byte) in key.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).ok()?;
+    }
+    Some((id, key))
+}
+
+// --- Süreç kontrolü ---
+
+/// Simülasyonu verilen çıkış koduyla sonlandırır.
+/// Gerçek donanımda "kontrolü yüklenen imaja devretmek" burada süreçten çıkmak anlamına gelir.
+pub fn exit(code: i32) -> ! {
+    let _ = std::io::stdout().flush();
+    std::process::exit(code)
+}
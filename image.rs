@@ -0,0 +1,294 @@
+#![no_std]
+
+// PacketBox boot imajı başlık biçimi.
+// İmaj = başlık + yük (payload). Yük, başlığın hemen arkasından (`header_size` ofsetinden) başlar.
+// Tüm çok baytlı alanlar little-endian'dır. Başlık düzeni (sürüm 1, 64 bayt):
+//
+//   0x00  magic          [u8; 4]  "PBOX"
+//   0x04  version        u16
+//   0x06  header_size    u16      Başlığın bayt boyutu (>= 64, <= 512)
+//   0x08  header_crc32   u32      Bu alan sıfırlanmış haliyle başlığın tamamı üzerinden CRC-32
+//   0x0C  flags          u32
+//   0x10  payload_size   u32
+//   0x14  payload_crc32  u32
+//   0x18  load_address   u64      Yükün RAM'de kopyalanacağı fiziksel adres
+//   0x20  entry_offset   u32      Giriş noktasının `load_address`'e göre ofseti
+//   0x24  target_hart    u32      İmajı çalıştıracak hart
+//   0x28  reserved       [u8; 24] Sıfır olmalı
+//
+// IMAGE_FLAG_FIRMWARE set edilmişse imaj bir firmware güncellemesidir (bkz. update.rs): yükleme aralığı
+// firmware'e ayrılmış RAM'in içinde olmalıdır ve `boot` ile çalıştırılamaz.
+//
+// IMAGE_FLAG_SIGNED set edilmişse başlık en az 136 bayttır ve imza bölümü içerir:
+//
+//   0x40  key_id         u32      İmzalayan anahtarın kimliği (secureboot::TRUSTED_KEYS)
+//   0x44  reserved       u32
+//   0x48  signature      [u8; 64] Ed25519 imzası (bkz. secureboot.rs)
+
+use crate::crc::Crc32;
+use crate::memory::{self, MemoryError};
+
+pub const IMAGE_MAGIC: [u8; 4] = *b"PBOX";
+pub const IMAGE_VERSION: u16 = 1;
+pub const IMAGE_HEADER_MIN_SIZE: usize = 64;
+// Başlık en fazla bir blok olabilir (ileride imza vb. alanlar için yer).
+pub const IMAGE_HEADER_MAX_SIZE: usize = 512;
+
+// Bayraklar: bilinmeyen bir bayrak set edilmişse imaj reddedilir.
+pub const IMAGE_FLAG_SIGNED: u32 = 1 << 0;
+pub const IMAGE_FLAG_FIRMWARE: u32 = 1 << 1;
+pub const IMAGE_KNOWN_FLAGS: u32 = IMAGE_FLAG_SIGNED | IMAGE_FLAG_FIRMWARE;
+
+// İmza bölümünün yerleşimi.
+pub const IMAGE_KEY_ID_OFFSET: usize = 0x40;
+pub const IMAGE_SIGNATURE_OFFSET: usize = 0x48;
+pub const IMAGE_SIGNATURE_LEN: usize = 64;
+pub const IMAGE_SIGNED_HEADER_MIN_SIZE: usize = IMAGE_SIGNATURE_OFFSET + IMAGE_SIGNATURE_LEN;
+
+// SiFive S21 tek çekirdeklidir; yalnızca hart 0 hedeflenebilir.
+pub const IMAGE_HART_COUNT: u32 = 1;
+
+#[derive(Debug)]
+pub enum ImageError {
+    Truncated,           // Başlık veya yük beklenenden kısa
+    BadMagic,
+    UnsupportedVersion(u16),
+    InvalidHeaderSize,
+    HeaderCrcMismatch,
+    UnknownFlags(u32),
+    EmptyPayload,
+    InvalidEntryOffset,  // Giriş noktası yükün dışında
+    InvalidHart(u32),
+    LoadRange(MemoryError), // Yükleme aralığı RAM dışında veya firmware ile çakışıyor
+    PayloadCrcMismatch,
+    FirmwareImage,       // Firmware güncelleme imajı boot edilemez
+}
+
+/// Ayrıştırılmış ve doğrulanmış imaj başlığı.
+#[derive(Debug, Copy, Clone)]
+pub struct ImageHeader {
+    pub version: u16,
+    pub header_size: u16,
+    pub flags: u32,
+    pub payload_size: u32,
+    pub payload_crc32: u32,
+    pub load_address: u64,
+    pub entry_offset: u32,
+    pub target_hart: u32,
+    pub signature: Option<ImageSignature>, // Sadece IMAGE_FLAG_SIGNED ile
+}
+
+/// Başlıktaki imza bölümü.
+#[derive(Debug, Copy, Clone)]
+pub struct ImageSignature {
+    pub key_id: u32,
+    pub signature: [u8; IMAGE_SIGNATURE_LEN],
+}
+
+fn le_u16(b: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([b[offset], b[offset + 1]])
+}
+
+fn le_u32(b: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([b[offset], b[offset + 1], b[offset + 2], b[offset + 3]])
+}
+
+fn le_u64(b: &[u8], offset: usize) -> u64 {
+    (le_u32(b, offset) as u64) | ((le_u32(b, offset + 4) as u64) << 32)
+}
+
+impl ImageHeader {
+    /// İmajın başından okunan baytları başlık olarak ayrıştırır ve doğrular:
+    /// sihirli sayı, sürüm, boyut, başlık CRC'si, bayraklar, giriş ofseti, hedef hart ve
+    /// yükleme aralığı (`memory::validate_load_range`, firmware imajlarında `memory::validate_firmware_range`).
+    pub fn parse(bytes: &[u8]) -> Result<Self, ImageError> {
+        if bytes.len() < IMAGE_HEADER_MIN_SIZE {
+            return Err(ImageError::Truncated);
+        }
+        if bytes[0..4] != IMAGE_MAGIC {
+            return Err(ImageError::BadMagic);
+        }
+        let version = le_u16(bytes, 4);
+        if version != IMAGE_VERSION {
+            return Err(ImageError::UnsupportedVersion(version));
+        }
+        let header_size = le_u16(bytes, 6) as usize;
+        if !(IMAGE_HEADER_MIN_SIZE..=IMAGE_HEADER_MAX_SIZE).contains(&header_size) {
+            return Err(ImageError::InvalidHeaderSize);
+        }
+        if bytes.len() < header_size {
+            return Err(ImageError::Truncated);
+        }
+
+        // Başlık CRC'si, CRC alanı sıfırlanmış haliyle başlığın tamamı üzerinden hesaplanır.
+        let stored_crc = le_u32(bytes, 8);
+        let mut crc = Crc32::new();
+        crc.update(&bytes[0..8]);
+        crc.update(&[0u8; 4]);
+        crc.update(&bytes[12..header_size]);
+        if crc.finish() != stored_crc {
+            return Err(ImageError::HeaderCrcMismatch);
+        }
+
+        let flags = le_u32(bytes, 12);
+        let signature = if flags & IMAGE_FLAG_SIGNED != 0 {
+            if header_size < IMAGE_SIGNED_HEADER_MIN_SIZE {
+                return Err(ImageError::InvalidHeaderSize);
+            }
+            let mut signature = [0u8; IMAGE_SIGNATURE_LEN];
+            signature.copy_from_slice(&bytes[IMAGE_SIGNATURE_OFFSET..IMAGE_SIGNED_HEADER_MIN_SIZE]);
+            Some(ImageSignature { key_id: le_u32(bytes, IMAGE_KEY_ID_OFFSET), signature })
+        } else {
+            None
+        };
+
+        let header = ImageHeader {
+            version,
+            header_size: header_size as u16,
+            flags,
+            payload_size: le_u32(bytes, 16),
+            payload_crc32: le_u32(bytes, 20),
+            load_address: le_u64(bytes, 24),
+            entry_offset: le_u32(bytes, 32),
+            target_hart: le_u32(bytes, 36),
+            signature,
+        };
+        header.validate()?;
+        Ok(header)
+    }
+
+    fn validate(&self) -> Result<(), ImageError> {
+        let unknown = self.flags & !IMAGE_KNOWN_FLAGS;
+        if unknown != 0 {
+            return Err(ImageError::UnknownFlags(unknown));
+        }
+        if self.payload_size == 0 {
+            return Err(ImageError::EmptyPayload);
+        }
+        if self.entry_offset >= self.payload_size {
+            return Err(ImageError::InvalidEntryOffset);
+        }
+        if self.target_hart >= IMAGE_HART_COUNT {
+            return Err(ImageError::InvalidHart(self.target_hart));
+        }
+        let load_address = usize::try_from(self.load_address).map_err(|_| ImageError::LoadRange(MemoryError::OutsideRam))?;
+        if self.is_firmware() {
+            memory::validate_firmware_range(load_address, self.payload_size as usize).map_err(ImageError::LoadRange)
+        } else {
+            memory::validate_load_range(load_address, self.payload_size as usize).map_err(ImageError::LoadRange)
+        }
+    }
+
+    pub fn is_firmware(&self) -> bool {
+        self.flags & IMAGE_FLAG_FIRMWARE != 0
+    }
+
+    pub fn load_address(&self) -> usize {
+        self.load_address as usize
+    }
+
+    pub fn entry_address(&self) -> usize {
+        self.load_address as usize + self.entry_offset as usize
+    }
+
+    pub fn payload_size(&self) -> usize {
+        self.payload_size as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crc::crc32;
+    use crate::memory::{FIRMWARE_RESERVED_END, LPDDR1_BASE_ADDRESS, LPDDR1_END_ADDRESS};
+
+    const PAYLOAD_SIZE: u32 = 0x1000;
+
+    fn set_u16(header: &mut [u8], offset: usize, value: u16) {
+        header[offset..offset + 2].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn set_u32(header: &mut [u8], offset: usize, value: u32) {
+        header[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn set_u64(header: &mut [u8], offset: usize, value: u64) {
+        header[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+    }
+
+    // Geçerli bir 64 baytlık başlık oluşturur, `edit` ile değiştirir ve CRC'yi yeniden hesaplar.
+    fn header(edit: impl FnOnce(&mut Vec<u8>)) -> Vec<u8> {
+        let mut header = vec![0u8; IMAGE_HEADER_MIN_SIZE];
+        header[0..4].copy_from_slice(&IMAGE_MAGIC);
+        set_u16(&mut header, 4, IMAGE_VERSION);
+        set_u16(&mut header, 6, IMAGE_HEADER_MIN_SIZE as u16);
+        set_u32(&mut header, 16, PAYLOAD_SIZE);
+        set_u64(&mut header, 24, FIRMWARE_RESERVED_END as u64);
+        edit(&mut header);
+        set_u32(&mut header, 8, 0);
+        let crc = crc32(&header);
+        set_u32(&mut header, 8, crc);
+        header
+    }
+
+    #[test]
+    fn valid_header_is_parsed() {
+        let parsed = ImageHeader::parse(&header(|h| set_u32(h, 32, 0x80))).unwrap();
+        assert_eq!(parsed.payload_size(), PAYLOAD_SIZE as usize);
+        assert_eq!(parsed.entry_address(), FIRMWARE_RESERVED_END + 0x80);
+        assert!(!parsed.is_firmware());
+        assert!(parsed.signature.is_none());
+    }
+
+    #[test]
+    fn header_crc_mismatch_is_rejected() {
+        let mut bytes = header(|_| {});
+        bytes[8] ^= 1;
+        assert!(matches!(ImageHeader::parse(&bytes), Err(ImageError::HeaderCrcMismatch)));
+        // CRC alanı doğru ama kapsadığı bir alan değişmiş.
+        let mut bytes = header(|_| {});
+        bytes[0x28] = 1;
+        assert!(matches!(ImageHeader::parse(&bytes), Err(ImageError::HeaderCrcMismatch)));
+    }
+
+    #[test]
+    fn invalid_headers_are_rejected() {
+        type Edit = fn(&mut Vec<u8>);
+        type Check = fn(&ImageError) -> bool;
+        let cases: [(&str, Edit, Check); 11] = [
+            ("bad magic", |h| h[0] = b'X', |e| matches!(e, ImageError::BadMagic)),
+            ("header size below minimum", |h| set_u16(h, 6, 63), |e| matches!(e, ImageError::InvalidHeaderSize)),
+            ("header size above maximum", |h| {
+                h.resize(IMAGE_HEADER_MAX_SIZE + 4, 0);
+                set_u16(h, 6, IMAGE_HEADER_MAX_SIZE as u16 + 4);
+            }, |e| matches!(e, ImageError::InvalidHeaderSize)),
+            ("header shorter than header_size", |h| set_u16(h, 6, 128), |e| matches!(e, ImageError::Truncated)),
+            ("signed flag without signature block", |h| set_u32(h, 12, IMAGE_FLAG_SIGNED), |e| matches!(e, ImageError::InvalidHeaderSize)),
+            ("unknown flag", |h| set_u32(h, 12, 1 << 7), |e| matches!(e, ImageError::UnknownFlags(0x80))),
+            ("empty payload", |h| set_u32(h, 16, 0), |e| matches!(e, ImageError::EmptyPayload)),
+            ("entry at payload end", |h| set_u32(h, 32, PAYLOAD_SIZE), |e| matches!(e, ImageError::InvalidEntryOffset)),
+            ("load range overlaps firmware", |h| set_u64(h, 24, FIRMWARE_RESERVED_END as u64 - 0x10), |e| matches!(e, ImageError::LoadRange(MemoryError::OverlapsFirmware))),
+            ("load range past RAM end", |h| set_u64(h, 24, (LPDDR1_END_ADDRESS - PAYLOAD_SIZE as usize / 2) as u64), |e| matches!(e, ImageError::LoadRange(MemoryError::OutsideRam))),
+            ("firmware image outside reserved RAM", |h| set_u32(h, 12, IMAGE_FLAG_FIRMWARE), |e| matches!(e, ImageError::LoadRange(MemoryError::OutsideFirmware))),
+        ];
+        for (name, edit, check) in cases {
+            match ImageHeader::parse(&header(edit)) {
+                Err(error) => assert!(check(&error), "{}: unexpected {:?}", name, error),
+                Ok(_) => panic!("{}: accepted", name),
+            }
+        }
+    }
+
+    #[test]
+    fn firmware_image_inside_reserved_ram_is_accepted() {
+        let parsed = ImageHeader::parse(&header(|h| {
+            set_u32(h, 12, IMAGE_FLAG_FIRMWARE);
+            set_u64(h, 24, LPDDR1_BASE_ADDRESS as u64);
+        }))
+        .unwrap();
+        assert!(parsed.is_firmware());
+        // Aynı aralık normal bir imaj için firmware ile çakışır.
+        let normal = header(|h| set_u64(h, 24, LPDDR1_BASE_ADDRESS as u64));
+        assert!(matches!(ImageHeader::parse(&normal), Err(ImageError::LoadRange(MemoryError::OverlapsFirmware))));
+    }
+}
@@ -0,0 +1,321 @@
+#![no_std]
+
+// CLI komut satırı ayrıştırıcısı. Satır `;` ile ayrılmış komutlardan, komutlar kelimelerden oluşur:
+//   boşluk / sekme        kelimeleri ayırır
+//   'metin'               olduğu gibi alınır (kaçış ve genişletme yok)
+//   "metin"               içinde yalnızca \" \\ \$ kaçışları ve $değişken genişletmesi yapılır
+//   \x                    tırnak dışında sonraki karakteri olduğu gibi alır
+//   $ad, ${ad}            ortam değişkeninin etkin değeri (tanımsızsa boş); ad harf, rakam, '_' ve '.'
+//                         içerir, diğer karakterler için ${...} kullanılır. Değer kelimelere bölünmez.
+//   ;                     komutları ayırır
+// Heap kullanılmaz: kelimeler sabit boyutlu `Tokens` arabelleğine yazılır. Değişkenler her komut
+// ayrıştırılırken genişletilir, böylece `setenv x 1; echo $x` yeni değeri görür.
+// Hatalardan yalnızca `LineTooLong` değişken değerlerine bağlıdır; diğerleri satırın yazımından
+// kaynaklanır ve satır hangi değerlerle ayrıştırılırsa ayrıştırılsın aynı yerde oluşur.
+
+use crate::env;
+use crate::nvram;
+
+// Bir komuttaki en fazla kelime sayısı (komut adı dahil).
+pub const MAX_TOKENS: usize = 11;
+// Genişletilmiş kelimelerin toplam boyutu (ör. 255 baytlık bootargs iki kez sığar).
+pub const TOKEN_BUFFER_SIZE: usize = 512;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ParseErrorKind {
+    UnterminatedSingleQuote,
+    UnterminatedDoubleQuote,
+    UnterminatedBrace,   // ${ kapatılmamış
+    InvalidVariableName, // ${} içinde geçersiz ad
+    TrailingBackslash,   // Satır \ ile bitiyor
+    EmptyCommand,        // ;; veya satır başında ;
+    TooManyArguments,
+    LineTooLong, // Genişletilmiş kelimeler TOKEN_BUFFER_SIZE'ı aşıyor
+}
+
+impl ParseErrorKind {
+    pub fn description(&self) -> &'static str {
+        match self {
+            ParseErrorKind::UnterminatedSingleQuote => "unterminated single quote",
+            ParseErrorKind::UnterminatedDoubleQuote => "unterminated double quote",
+            ParseErrorKind::UnterminatedBrace => "unterminated '${'",
+            ParseErrorKind::InvalidVariableName => "invalid variable name",
+            ParseErrorKind::TrailingBackslash => "backslash at end of line",
+            ParseErrorKind::EmptyCommand => "empty command before ';'",
+            ParseErrorKind::TooManyArguments => "too many arguments",
+            ParseErrorKind::LineTooLong => "expanded line too long",
+        }
+    }
+}
+
+/// Ayrıştırma hatası; `position` hatanın başladığı bayt konumudur (0 tabanlı).
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ParseError {
+    pub kind: ParseErrorKind,
+    pub position: usize,
+}
+
+/// Tek bir komutun kelimeleri.
+pub struct Tokens {
+    buffer: [u8; TOKEN_BUFFER_SIZE],
+    used: usize, // Arabellekte dolu bayt sayısı
+    ends: [usize; MAX_TOKENS], // Her kelimenin arabellekteki bitişi
+    count: usize,
+    in_word: bool,
+}
+
+impl Tokens {
+    pub const fn new() -> Self {
+        Tokens { buffer: [0; TOKEN_BUFFER_SIZE], used: 0, ends: [0; MAX_TOKENS], count: 0, in_word: false }
+    }
+
+    fn clear(&mut self) {
+        self.used = 0;
+        self.count = 0;
+        self.in_word = false;
+    }
+
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<&str> {
+        if index >= self.count {
+            return None;
+        }
+        let start = if index == 0 { 0 } else { self.ends[index - 1] };
+        core::str::from_utf8(&self.buffer[start..self.ends[index]]).ok()
+    }
+
+    // Kelime başlatır ("" gibi boş kelimeler de sayılır).
+    fn begin_word(&mut self) -> Result<(), ParseErrorKind> {
+        if !self.in_word {
+            if self.count == MAX_TOKENS {
+                return Err(ParseErrorKind::TooManyArguments);
+            }
+            self.in_word = true;
+        }
+        Ok(())
+    }
+
+    fn end_word(&mut self) {
+        if self.in_word {
+            self.ends[self.count] = self.used;
+            self.count += 1;
+            self.in_word = false;
+        }
+    }
+
+    fn push(&mut self, bytes: &[u8]) -> Result<(), ParseErrorKind> {
+        self.begin_word()?;
+        if self.used + bytes.len() > TOKEN_BUFFER_SIZE {
+            return Err(ParseErrorKind::LineTooLong);
+        }
+        self.buffer[self.used..self.used + bytes.len()].copy_from_slice(bytes);
+        self.used += bytes.len();
+        Ok(())
+    }
+}
+
+fn is_name_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_' || byte == b'.'
+}
+
+pub struct Lexer<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> Lexer<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Lexer { input, position: 0 }
+    }
+
+    /// Sıradaki komutu `tokens`'a ayrıştırır. Satırda komut kalmadıysa `Ok(false)` döner.
+    /// # Safety
+    /// Değişken genişletmesi global NVRAM'i okur.
+    pub unsafe fn next_command(&mut self, tokens: &mut Tokens) -> Result<bool, ParseError> {
+        tokens.clear();
+        let input = self.input;
+        let bytes = input.as_bytes();
+        while let Some(&byte) = bytes.get(self.position) {
+            let at = self.position;
+            let error = |kind| ParseError { kind, position: at };
+            match byte {
+                b' ' | b'\t' | b'\r' | b'\n' => {
+                    tokens.end_word();
+                    self.position += 1;
+                }
+                b';' => {
+                    tokens.end_word();
+                    self.position += 1;
+                    if tokens.is_empty() {
+                        return Err(error(ParseErrorKind::EmptyCommand));
+                    }
+                    return Ok(true);
+                }
+                b'\'' => {
+                    tokens.begin_word().map_err(error)?;
+                    let close = input[at + 1..].find('\'').ok_or(error(ParseErrorKind::UnterminatedSingleQuote))?;
+                    tokens.push(&bytes[at + 1..at + 1 + close]).map_err(error)?;
+                    self.position = at + close + 2;
+                }
+                b'"' => {
+                    tokens.begin_word().map_err(error)?;
+                    self.position += 1;
+                    self.double_quoted(tokens, at)?;
+                }
+                b'\\' => match bytes.get(at + 1) {
+                    Some(next) => {
+                        tokens.push(core::slice::from_ref(next)).map_err(error)?;
+                        self.position += 2;
+                    }
+                    None => return Err(error(ParseErrorKind::TrailingBackslash)),
+                },
+                b'$' => self.expand(tokens)?,
+                _ => {
+                    tokens.push(&[byte]).map_err(error)?;
+                    self.position += 1;
+                }
+            }
+        }
+        tokens.end_word();
+        Ok(!tokens.is_empty())
+    }
+
+    // Açılış tırnağından sonrasını kapanış tırnağına kadar işler.
+    unsafe fn double_quoted(&mut self, tokens: &mut Tokens, open: usize) -> Result<(), ParseError> {
+        let input = self.input;
+        let bytes = input.as_bytes();
+        loop {
+            let at = self.position;
+            let error = |kind| ParseError { kind, position: at };
+            match bytes.get(at) {
+                None => return Err(ParseError { kind: ParseErrorKind::UnterminatedDoubleQuote, position: open }),
+                Some(b'"') => {
+                    self.position += 1;
+                    return Ok(());
+                }
+                Some(b'\\') if matches!(bytes.get(at + 1), Some(b'"') | Some(b'\\') | Some(b'$')) => {
+                    tokens.push(&bytes[at + 1..at + 2]).map_err(error)?;
+                    self.position += 2;
+                }
+                Some(b'$') => self.expand(tokens)?,
+                Some(&byte) => {
+                    tokens.push(&[byte]).map_err(error)?;
+                    self.position += 1;
+                }
+            }
+        }
+    }
+
+    // `$` konumundan değişken genişletir; ardından ad gelmiyorsa `$` olduğu gibi alınır.
+    unsafe fn expand(&mut self, tokens: &mut Tokens) -> Result<(), ParseError> {
+        let dollar = self.position;
+        let error = |kind| ParseError { kind, position: dollar };
+        let input = self.input;
+        let bytes = input.as_bytes();
+        let name = if bytes.get(dollar + 1) == Some(&b'{') {
+            let close = input[dollar + 2..].find('}').ok_or(error(ParseErrorKind::UnterminatedBrace))?;
+            let name = &input[dollar + 2..dollar + 2 + close];
+            if !nvram::is_valid_key(name) {
+                return Err(error(ParseErrorKind::InvalidVariableName));
+            }
+            self.position = dollar + close + 3;
+            name
+        } else {
+            let len = bytes[dollar + 1..].iter().take_while(|&&b| is_name_byte(b)).count();
+            self.position = dollar + 1 + len;
+            if len == 0 {
+                return tokens.push(b"$").map_err(error);
+            }
+            &input[dollar + 1..dollar + 1 + len]
+        };
+        tokens.begin_word().map_err(error)?;
+        tokens.push(env::get(name).unwrap_or("").as_bytes()).map_err(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Satırı komutlara ve kelimelere ayırır. Testler global NVRAM'e yazmaz; genişletme için bilinen
+    // değişkenlerin varsayılanları (bootdelay=3, bootdevice=sd) ve tanımsız adlar kullanılır.
+    fn lex(line: &str) -> Result<Vec<Vec<String>>, ParseError> {
+        let mut lexer = Lexer::new(line);
+        let mut tokens = Tokens::new();
+        let mut commands = Vec::new();
+        while unsafe { lexer.next_command(&mut tokens)? } {
+            commands.push((0..tokens.len()).map(|i| tokens.get(i).unwrap().to_string()).collect());
+        }
+        Ok(commands)
+    }
+
+    fn words(line: &str) -> Vec<String> {
+        let mut commands = lex(line).unwrap();
+        assert_eq!(commands.len(), 1, "{:?}", commands);
+        commands.remove(0)
+    }
+
+    fn error(line: &str) -> (ParseErrorKind, usize) {
+        let e = lex(line).unwrap_err();
+        (e.kind, e.position)
+    }
+
+    #[test]
+    fn splits_words_and_commands() {
+        assert_eq!(lex(" echo  a\tb ;getenv x; ").unwrap(), [vec!["echo", "a", "b"], vec!["getenv", "x"]]);
+        assert_eq!(lex("   ").unwrap(), Vec::<Vec<String>>::new());
+    }
+
+    #[test]
+    fn quoting_and_escapes() {
+        assert_eq!(words(r#"echo '$bootdelay "x"' a\ b\;c"#), ["echo", r#"$bootdelay "x""#, "a b;c"]);
+        assert_eq!(words(r#"echo "a\"b \$x \\ \n $bootdelay""#), ["echo", r#"a"b $x \ \n 3"#]);
+        // Tırnaklar kelimeyi bölmez, boş tırnak da bir kelimedir.
+        assert_eq!(words(r#"echo pre'fix'"-$bootdelay" "" ''"#), ["echo", "prefix-3", "", ""]);
+    }
+
+    #[test]
+    fn variable_expansion() {
+        assert_eq!(words("echo ${bootdevice}x $bootdelay.x ${bootdelay}.x"), ["echo", "sdx", "", "3.x"]);
+        assert_eq!(words("echo $undefined.var ${undefined}"), ["echo", "", ""]);
+        // Ad gelmeyen `$` olduğu gibi kalır.
+        assert_eq!(words("echo $ 5$ $-"), ["echo", "$", "5$", "$-"]);
+        assert_eq!(words("echo '${bootdelay}'"), ["echo", "${bootdelay}"]);
+    }
+
+    #[test]
+    fn unterminated_quotes_and_braces_report_their_opening_position() {
+        assert_eq!(error("echo 'abc"), (ParseErrorKind::UnterminatedSingleQuote, 5));
+        assert_eq!(error("echo a\"bc \\\""), (ParseErrorKind::UnterminatedDoubleQuote, 6));
+        assert_eq!(error("echo ${bootdelay"), (ParseErrorKind::UnterminatedBrace, 5));
+        assert_eq!(error("echo \"${bootdelay\""), (ParseErrorKind::UnterminatedBrace, 6));
+    }
+
+    #[test]
+    fn other_syntax_errors() {
+        assert_eq!(error("echo ${a b}"), (ParseErrorKind::InvalidVariableName, 5));
+        assert_eq!(error("echo ${}"), (ParseErrorKind::InvalidVariableName, 5));
+        assert_eq!(error("echo a\\"), (ParseErrorKind::TrailingBackslash, 6));
+        assert_eq!(error("echo a;; echo b"), (ParseErrorKind::EmptyCommand, 7));
+        assert_eq!(error("; echo"), (ParseErrorKind::EmptyCommand, 0));
+        assert_eq!(error("echo 1 2 3 4 5 6 7 8 9 10 11"), (ParseErrorKind::TooManyArguments, 26));
+        let long = format!("echo {}", "a".repeat(TOKEN_BUFFER_SIZE));
+        assert_eq!(error(&long).0, ParseErrorKind::LineTooLong);
+    }
+
+    #[test]
+    fn error_in_a_later_command_is_found_after_earlier_ones() {
+        let mut lexer = Lexer::new("echo ok; echo 'bad");
+        let mut tokens = Tokens::new();
+        assert_eq!(unsafe { lexer.next_command(&mut tokens) }, Ok(true));
+        assert_eq!(tokens.get(1), Some("ok"));
+        assert_eq!(unsafe { lexer.next_command(&mut tokens) }.unwrap_err().kind, ParseErrorKind::UnterminatedSingleQuote);
+    }
+}
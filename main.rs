@@ -0,0 +1,13 @@
+// PacketBox firmware ikilisi. Firmware'in tamamı kütüphanededir (firmware.rs).
+#![cfg_attr(not(feature = "hosted"), no_std)]
+#![cfg_attr(not(feature = "hosted"), no_main)]
+
+// Donanımda giriş noktası (`#[entry]`) kütüphanede tanımlıdır; burada sadece bağlanır.
+#[cfg(not(feature = "hosted"))]
+use packetbox_firmware as _;
+
+// Host simülasyonu: firmware sürecin `main`'i olarak çalışır.
+#[cfg(feature = "hosted")]
+fn main() {
+    packetbox_firmware::run()
+}
@@ -0,0 +1,311 @@
+#![no_std]
+
+// MBR (Master Boot Record) bölüm tablosu ayrıştırıcısı.
+// LBA 0'daki dört birincil girdiyi çözer ve genişletilmiş (extended) bölümlerin EBR
+// zincirini takip ederek mantıksal bölümleri bulur.
+
+use crate::partition::{self, PartitionError, PartitionTable};
+use crate::storage::{BlockDevice, BLOCK_SIZE};
+
+pub const MBR_PARTITION_TABLE_OFFSET: usize = 446;
+pub const MBR_ENTRY_SIZE: usize = 16;
+pub const MBR_PRIMARY_ENTRIES: usize = 4;
+pub const MBR_DISK_SIGNATURE_OFFSET: usize = 440;
+pub const MBR_SIGNATURE_OFFSET: usize = 510;
+pub const MBR_SIGNATURE: [u8; 2] = [0x55, 0xAA];
+
+// Bölüm türleri
+pub const MBR_TYPE_EMPTY: u8 = 0x00;
+pub const MBR_TYPE_FAT12: u8 = 0x01;
+pub const MBR_TYPE_FAT16_SMALL: u8 = 0x04;
+pub const MBR_TYPE_EXTENDED_CHS: u8 = 0x05;
+pub const MBR_TYPE_FAT16: u8 = 0x06;
+pub const MBR_TYPE_FAT32_CHS: u8 = 0x0B;
+pub const MBR_TYPE_FAT32_LBA: u8 = 0x0C;
+pub const MBR_TYPE_FAT16_LBA: u8 = 0x0E;
+pub const MBR_TYPE_EXTENDED_LBA: u8 = 0x0F;
+pub const MBR_TYPE_LINUX: u8 = 0x83;
+pub const MBR_TYPE_LINUX_EXTENDED: u8 = 0x85;
+pub const MBR_TYPE_GPT_PROTECTIVE: u8 = 0xEE;
+
+// Boot bayrağı değerleri
+const BOOT_FLAG_INACTIVE: u8 = 0x00;
+const BOOT_FLAG_ACTIVE: u8 = 0x80;
+
+// Mantıksal bölümler 5'ten numaralandırılır (Linux geleneği).
+pub const FIRST_LOGICAL_INDEX: usize = 5;
+// EBR zincirinde izlenecek en fazla halka sayısı (bozuk/döngülü zincirlere karşı).
+pub const MAX_EBR_CHAIN_LENGTH: usize = 64;
+
+/// CHS (Cylinder/Head/Sector) adresi. Modern aygıtlarda sadece bilgi amaçlıdır, LBA kullanılır.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Chs {
+    pub cylinder: u16,
+    pub head: u8,
+    pub sector: u8,
+}
+
+impl Chs {
+    fn from_bytes(raw: &[u8]) -> Self {
+        Chs {
+            head: raw[0],
+            sector: raw[1] & 0x3F,
+            cylinder: (((raw[1] & 0xC0) as u16) << 2) | raw[2] as u16,
+        }
+    }
+}
+
+/// Tek bir 16 baytlık MBR bölüm girdisi.
+#[derive(Debug, Copy, Clone)]
+pub struct MbrEntry {
+    pub bootable: bool,
+    pub chs_start: Chs,
+    pub partition_type: u8,
+    pub chs_end: Chs,
+    pub lba_start: u32,
+    pub sector_count: u32,
+}
+
+impl MbrEntry {
+    /// Kullanılmayan girdi (tür 0x00).
+    pub const EMPTY: MbrEntry = MbrEntry {
+        bootable: false,
+        chs_start: Chs { cylinder: 0, head: 0, sector: 0 },
+        partition_type: MBR_TYPE_EMPTY,
+        chs_end: Chs { cylinder: 0, head: 0, sector: 0 },
+        lba_start: 0,
+        sector_count: 0,
+    };
+
+    /// 16 baytlık girdiyi ayrıştırır. Boot bayrağı 0x00 veya 0x80 değilse `InvalidEntry` döner.
+    pub fn parse(raw: &[u8]) -> Result<Self, PartitionError> {
+        if raw.len() < MBR_ENTRY_SIZE {
+            return Err(PartitionError::InvalidEntry);
+        }
+        let bootable = match raw[0] {
+            BOOT_FLAG_INACTIVE => false,
+            BOOT_FLAG_ACTIVE => true,
+            _ => return Err(PartitionError::InvalidEntry),
+        };
+        Ok(MbrEntry {
+            bootable,
+            chs_start: Chs::from_bytes(&raw[1..4]),
+            partition_type: raw[4],
+            chs_end: Chs::from_bytes(&raw[5..8]),
+            lba_start: u32::from_le_bytes([raw[8], raw[9], raw[10], raw[11]]),
+            sector_count: u32::from_le_bytes([raw[12], raw[13], raw[14], raw[15]]),
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.partition_type == MBR_TYPE_EMPTY || self.sector_count == 0
+    }
+
+    pub fn is_extended(&self) -> bool {
+        matches!(self.partition_type, MBR_TYPE_EXTENDED_CHS | MBR_TYPE_EXTENDED_LBA | MBR_TYPE_LINUX_EXTENDED)
+    }
+}
+
+/// Ayrıştırılmış MBR (veya EBR) sektörü.
+#[derive(Debug, Copy, Clone)]
+pub struct Mbr {
+    pub disk_signature: u32,
+    pub entries: [MbrEntry; MBR_PRIMARY_ENTRIES],
+}
+
+impl Mbr {
+    /// 512 baytlık bir sektörü MBR olarak ayrıştırır; 0x55AA imzasını doğrular.
+    pub fn parse(sector: &[u8]) -> Result<Self, PartitionError> {
+        if sector.len() < BLOCK_SIZE {
+            return Err(PartitionError::InvalidEntry);
+        }
+        if sector[MBR_SIGNATURE_OFFSET..MBR_SIGNATURE_OFFSET + 2] != MBR_SIGNATURE {
+            return Err(PartitionError::InvalidSignature);
+        }
+        // Boot bayrağı bozuk bir girdi tüm tabloyu geçersiz kılmaz; boş sayılıp atlanır.
+        let entry_at = |i: usize| {
+            let offset = MBR_PARTITION_TABLE_OFFSET + i * MBR_ENTRY_SIZE;
+            MbrEntry::parse(&sector[offset..offset + MBR_ENTRY_SIZE]).unwrap_or(MbrEntry::EMPTY)
+        };
+        let s = MBR_DISK_SIGNATURE_OFFSET;
+        Ok(Mbr {
+            disk_signature: u32::from_le_bytes([sector[s], sector[s + 1], sector[s + 2], sector[s + 3]]),
+            entries: [entry_at(0), entry_at(1), entry_at(2), entry_at(3)],
+        })
+    }
+
+    /// Aygıtın belirtilen LBA'sındaki sektörü okuyup ayrıştırır.
+    pub fn read<D: BlockDevice + ?Sized>(device: &mut D, lba: u64) -> Result<Self, PartitionError> {
+        let mut sector = [0u8; BLOCK_SIZE];
+        device.read_block(lba, &mut sector)?;
+        Mbr::parse(&sector)
+    }
+}
+
+/// MBR'yi ve varsa EBR zincirini okuyup bulunan bölümleri tabloya ekler.
+pub fn read_partitions<D: BlockDevice + ?Sized>(device: &mut D, table: &mut PartitionTable) -> Result<(), PartitionError> {
+    let mbr = Mbr::read(device, 0)?;
+
+    let mut extended: Option<MbrEntry> = None;
+    for (slot, entry) in mbr.entries.iter().enumerate() {
+        if entry.is_empty() {
+            continue;
+        }
+        if entry.is_extended() {
+            // Birden fazla genişletilmiş bölüm geçersizdir; ilkini kullan.
+            if extended.is_none() {
+                extended = Some(*entry);
+            }
+            continue;
+        }
+        table.push(partition::info_from_mbr_entry(slot + 1, entry, 0, false))?;
+    }
+
+    if let Some(ext) = extended {
+        read_logical_partitions(device, table, ext.lba_start as u64)?;
+    }
+    Ok(())
+}
+
+// EBR zincirini takip eder. Her EBR'nin ilk girdisi mantıksal bölümü (EBR'ye göre),
+// ikinci girdisi bir sonraki EBR'yi (genişletilmiş bölümün başına göre) gösterir.
+fn read_logical_partitions<D: BlockDevice + ?Sized>(device: &mut D, table: &mut PartitionTable, extended_base: u64) -> Result<(), PartitionError> {
+    let mut ebr_lba = extended_base;
+    let mut index = FIRST_LOGICAL_INDEX;
+
+    for _ in 0..MAX_EBR_CHAIN_LENGTH {
+        let ebr = Mbr::read(device, ebr_lba)?;
+
+        let logical = &ebr.entries[0];
+        if !logical.is_empty() {
+            table.push(partition::info_from_mbr_entry(index, logical, ebr_lba, true))?;
+            index += 1;
+        }
+
+        let next = &ebr.entries[1];
+        if next.is_empty() || !next.is_extended() {
+            return Ok(());
+        }
+        let next_lba = extended_base + next.lba_start as u64;
+        // Zincir her zaman ileri gitmelidir; geri dönen bağlantı döngü demektir.
+        if next_lba <= ebr_lba {
+            return Err(PartitionError::ExtendedChainLoop);
+        }
+        ebr_lba = next_lba;
+    }
+    Err(PartitionError::ExtendedChainLoop)
+}
+
+/// Bölüm türü için kısa, okunabilir ad.
+pub fn partition_type_name(partition_type: u8) -> &'static str {
+    match partition_type {
+        MBR_TYPE_EMPTY => "Empty",
+        MBR_TYPE_FAT12 => "FAT12",
+        MBR_TYPE_FAT16_SMALL | MBR_TYPE_FAT16 | MBR_TYPE_FAT16_LBA => "FAT16",
+        MBR_TYPE_FAT32_CHS | MBR_TYPE_FAT32_LBA => "FAT32",
+        MBR_TYPE_EXTENDED_CHS | MBR_TYPE_EXTENDED_LBA | MBR_TYPE_LINUX_EXTENDED => "Extended",
+        MBR_TYPE_LINUX => "Linux",
+        MBR_TYPE_GPT_PROTECTIVE => "GPT protective",
+        _ => "Unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::partition::PartitionKind;
+    use crate::storage::{RamDisk, StorageError};
+
+    const DISK_BLOCKS: usize = 400;
+
+    fn put_entry(disk: &mut [u8], lba: usize, slot: usize, flag: u8, partition_type: u8, start: u32, count: u32) {
+        let offset = lba * BLOCK_SIZE + MBR_PARTITION_TABLE_OFFSET + slot * MBR_ENTRY_SIZE;
+        let entry = &mut disk[offset..offset + MBR_ENTRY_SIZE];
+        entry[0] = flag;
+        entry[4] = partition_type;
+        entry[8..12].copy_from_slice(&start.to_le_bytes());
+        entry[12..16].copy_from_slice(&count.to_le_bytes());
+        let signature = lba * BLOCK_SIZE + MBR_SIGNATURE_OFFSET;
+        disk[signature..signature + 2].copy_from_slice(&MBR_SIGNATURE);
+    }
+
+    fn read(disk: &mut [u8]) -> Result<PartitionTable, PartitionError> {
+        let mut table = PartitionTable::new();
+        read_partitions(&mut RamDisk::new(disk), &mut table)?;
+        Ok(table)
+    }
+
+    // (index, start_lba, block_count, bootable, logical)
+    fn summary(table: &PartitionTable) -> Vec<(usize, u64, u64, bool, bool)> {
+        table.iter().map(|p| match p.kind {
+            PartitionKind::Mbr { bootable, logical, .. } => (p.index, p.start_lba, p.block_count, bootable, logical),
+            PartitionKind::Gpt { .. } => panic!("unexpected GPT entry"),
+        }).collect()
+    }
+
+    #[test]
+    fn primary_table() {
+        let mut disk = vec![0u8; DISK_BLOCKS * BLOCK_SIZE];
+        put_entry(&mut disk, 0, 0, BOOT_FLAG_ACTIVE, MBR_TYPE_FAT32_LBA, 8, 100);
+        put_entry(&mut disk, 0, 2, BOOT_FLAG_INACTIVE, MBR_TYPE_LINUX, 200, 150);
+        disk[MBR_DISK_SIGNATURE_OFFSET..MBR_DISK_SIGNATURE_OFFSET + 4].copy_from_slice(&0x1234_5678u32.to_le_bytes());
+
+        assert_eq!(Mbr::read(&mut RamDisk::new(&mut disk), 0).unwrap().disk_signature, 0x1234_5678);
+        let table = read(&mut disk).unwrap();
+        assert_eq!(summary(&table), [(1, 8, 100, true, false), (3, 200, 150, false, false)]);
+        assert_eq!(table.first_bootable().map(|p| p.index), Some(1));
+    }
+
+    #[test]
+    fn ebr_chain_yields_logical_partitions() {
+        let mut disk = vec![0u8; DISK_BLOCKS * BLOCK_SIZE];
+        put_entry(&mut disk, 0, 0, BOOT_FLAG_INACTIVE, MBR_TYPE_FAT16, 8, 50);
+        put_entry(&mut disk, 0, 1, BOOT_FLAG_INACTIVE, MBR_TYPE_EXTENDED_LBA, 100, 300);
+        // Mantıksal bölüm EBR'ye, sonraki EBR genişletilmiş bölümün başına göredir.
+        put_entry(&mut disk, 100, 0, BOOT_FLAG_INACTIVE, MBR_TYPE_LINUX, 10, 50);
+        put_entry(&mut disk, 100, 1, BOOT_FLAG_INACTIVE, MBR_TYPE_EXTENDED_CHS, 200, 100);
+        put_entry(&mut disk, 300, 0, BOOT_FLAG_ACTIVE, MBR_TYPE_LINUX, 10, 20);
+
+        let table = read(&mut disk).unwrap();
+        assert_eq!(summary(&table), [(1, 8, 50, false, false), (5, 110, 50, false, true), (6, 310, 20, true, true)]);
+    }
+
+    #[test]
+    fn looping_ebr_chain_is_rejected() {
+        let mut disk = vec![0u8; DISK_BLOCKS * BLOCK_SIZE];
+        put_entry(&mut disk, 0, 0, BOOT_FLAG_INACTIVE, MBR_TYPE_EXTENDED_LBA, 100, 300);
+        put_entry(&mut disk, 100, 0, BOOT_FLAG_INACTIVE, MBR_TYPE_LINUX, 10, 50);
+        put_entry(&mut disk, 100, 1, BOOT_FLAG_INACTIVE, MBR_TYPE_EXTENDED_LBA, 0, 100); // kendisini gösterir
+
+        assert!(matches!(read(&mut disk), Err(PartitionError::ExtendedChainLoop)));
+    }
+
+    #[test]
+    fn ebr_chain_leaving_the_disk_is_rejected() {
+        let mut disk = vec![0u8; DISK_BLOCKS * BLOCK_SIZE];
+        put_entry(&mut disk, 0, 0, BOOT_FLAG_INACTIVE, MBR_TYPE_EXTENDED_LBA, 100, 300);
+        put_entry(&mut disk, 100, 1, BOOT_FLAG_INACTIVE, MBR_TYPE_EXTENDED_LBA, 10_000, 100);
+
+        assert!(matches!(read(&mut disk), Err(PartitionError::Storage(StorageError::InvalidLba))));
+    }
+
+    #[test]
+    fn missing_signature_is_rejected() {
+        let mut disk = vec![0u8; DISK_BLOCKS * BLOCK_SIZE];
+        put_entry(&mut disk, 0, 0, BOOT_FLAG_INACTIVE, MBR_TYPE_LINUX, 8, 100);
+        disk[MBR_SIGNATURE_OFFSET + 1] = 0x00;
+
+        assert!(matches!(read(&mut disk), Err(PartitionError::InvalidSignature)));
+    }
+
+    #[test]
+    fn entry_with_bad_boot_flag_is_skipped() {
+        let mut disk = vec![0u8; DISK_BLOCKS * BLOCK_SIZE];
+        put_entry(&mut disk, 0, 0, 0x12, MBR_TYPE_LINUX, 8, 100);
+        put_entry(&mut disk, 0, 1, BOOT_FLAG_ACTIVE, MBR_TYPE_FAT32_LBA, 200, 100);
+
+        assert!(matches!(MbrEntry::parse(&disk[MBR_PARTITION_TABLE_OFFSET..]), Err(PartitionError::InvalidEntry)));
+        let table = read(&mut disk).unwrap();
+        assert_eq!(summary(&table), [(2, 200, 100, true, false)]);
+    }
+}
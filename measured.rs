@@ -0,0 +1,223 @@
+#![no_std]
+
+// Ölçülü boot (measured boot) kaydı.
+// Firmware'in yüklediği her aşama (bootloader, kernel, yapılandırma) SHA-256 ile özetlenir ve
+// TPM PCR'larındaki gibi bir zincire eklenir: PCR = SHA-256(PCR || özet). Her ölçüm ayrıca olay
+// kaydına yazılır. Kayıt `#[repr(C)]` olduğu için adresi olduğu gibi işletim sistemine verilebilir;
+// işletim sistemi olayları yeniden oynatarak (replay) PCR değerini doğrulayabilir.
+
+use core::fmt;
+
+use sha2::{Digest, Sha256};
+
+pub const BOOT_LOG_MAGIC: u32 = 0x474C_4250; // "PBLG"
+pub const BOOT_LOG_VERSION: u32 = 1;
+pub const BOOT_LOG_MAX_EVENTS: usize = 16;
+pub const BOOT_EVENT_DESCRIPTION_LEN: usize = 64;
+pub const DIGEST_LEN: usize = 32;
+
+// Olay bayrakları
+pub const EVENT_FLAG_SIGNATURE_VERIFIED: u32 = 1 << 0;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[repr(u32)]
+pub enum BootStage {
+    Bootloader = 1, // Ham bölümden yüklenen imaj
+    Kernel = 2,     // Dosya sisteminden yüklenen imaj
+    Config = 3,     // Ortam değişkenleri, boot betikleri vb.
+}
+
+impl BootStage {
+    pub fn name(&self) -> &'static str {
+        match self {
+            BootStage::Bootloader => "bootloader",
+            BootStage::Kernel => "kernel",
+            BootStage::Config => "config",
+        }
+    }
+
+    fn from_raw(raw: u32) -> Option<Self> {
+        match raw {
+            1 => Some(BootStage::Bootloader),
+            2 => Some(BootStage::Kernel),
+            3 => Some(BootStage::Config),
+            _ => None,
+        }
+    }
+}
+
+/// Tek bir ölçüm olayı.
+#[derive(Debug, Copy, Clone)]
+#[repr(C)]
+pub struct BootEvent {
+    pub stage: u32,
+    pub flags: u32,
+    pub load_address: u64, // Yapılandırma gibi RAM'e yüklenmeyen veriler için 0
+    pub size: u64,
+    pub digest: [u8; DIGEST_LEN],
+    pub description: [u8; BOOT_EVENT_DESCRIPTION_LEN], // UTF-8, sonu 0 ile dolgulu
+}
+
+impl BootEvent {
+    const EMPTY: BootEvent = BootEvent {
+        stage: 0,
+        flags: 0,
+        load_address: 0,
+        size: 0,
+        digest: [0; DIGEST_LEN],
+        description: [0; BOOT_EVENT_DESCRIPTION_LEN],
+    };
+
+    pub fn stage(&self) -> Option<BootStage> {
+        BootStage::from_raw(self.stage)
+    }
+
+    pub fn description(&self) -> &str {
+        let len = self.description.iter().position(|&b| b == 0).unwrap_or(BOOT_EVENT_DESCRIPTION_LEN);
+        core::str::from_utf8(&self.description[..len]).unwrap_or("?")
+    }
+}
+
+/// İşletim sistemine verilen ölçüm kaydı.
+#[derive(Debug)]
+#[repr(C)]
+pub struct BootLog {
+    pub magic: u32,
+    pub version: u32,
+    pub event_count: u32,
+    pub dropped_events: u32, // Kayıt dolduktan sonra gelen olaylar (PCR'a yine de eklenir)
+    pub pcr: [u8; DIGEST_LEN],
+    pub events: [BootEvent; BOOT_LOG_MAX_EVENTS],
+}
+
+impl BootLog {
+    pub const fn new() -> Self {
+        BootLog {
+            magic: BOOT_LOG_MAGIC,
+            version: BOOT_LOG_VERSION,
+            event_count: 0,
+            dropped_events: 0,
+            pcr: [0; DIGEST_LEN],
+            events: [BootEvent::EMPTY; BOOT_LOG_MAX_EVENTS],
+        }
+    }
+
+    pub fn events(&self) -> &[BootEvent] {
+        &self.events[..self.event_count as usize]
+    }
+
+    /// Özeti PCR zincirine ekler ve olayı kaydeder.
+    pub fn record(&mut self, stage: BootStage, flags: u32, load_address: u64, size: u64, digest: [u8; DIGEST_LEN], description: fmt::Arguments<'_>) {
+        let mut hasher = Sha256::new();
+        hasher.update(self.pcr);
+        hasher.update(digest);
+        self.pcr = hasher.finalize().into();
+
+        if self.event_count as usize >= BOOT_LOG_MAX_EVENTS {
+            self.dropped_events += 1;
+            return;
+        }
+        let mut writer = DescriptionWriter { buffer: [0; BOOT_EVENT_DESCRIPTION_LEN], len: 0 };
+        let _ = fmt::write(&mut writer, description);
+        self.events[self.event_count as usize] = BootEvent {
+            stage: stage as u32,
+            flags,
+            load_address,
+            size,
+            digest,
+            description: writer.buffer,
+        };
+        self.event_count += 1;
+    }
+}
+
+// Açıklamayı sabit boyutlu alana yazar; sığmayan kısım kesilir (son bayt her zaman 0 kalır).
+struct DescriptionWriter {
+    buffer: [u8; BOOT_EVENT_DESCRIPTION_LEN],
+    len: usize,
+}
+
+impl fmt::Write for DescriptionWriter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for &byte in s.as_bytes() {
+            if self.len + 1 >= BOOT_EVENT_DESCRIPTION_LEN {
+                break;
+            }
+            self.buffer[self.len] = byte;
+            self.len += 1;
+        }
+        Ok(())
+    }
+}
+
+/// Parçaların art arda eklenmiş halinin SHA-256 özeti.
+pub fn digest_of(parts: &[&[u8]]) -> [u8; DIGEST_LEN] {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize().into()
+}
+
+// --- Global Ölçüm Kaydı ---
+// # Safety: Global mutable static kullanimi unsafe'dir.
+pub static mut BOOT_LOG: BootLog = BootLog::new();
+
+/// Verilen parçaları ölçer ve global kayda ekler.
+/// # Safety
+/// Global ölçüm kaydını değiştirir.
+pub unsafe fn measure(stage: BootStage, flags: u32, load_address: u64, parts: &[&[u8]], description: fmt::Arguments<'_>) {
+    let size = parts.iter().map(|p| p.len() as u64).sum();
+    let digest = digest_of(parts);
+    BOOT_LOG.record(stage, flags, load_address, size, digest, description);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pcr_matches_replay_of_recorded_digests() {
+        let mut log = BootLog::new();
+        let parts: [&[u8]; 3] = [b"bootloader", b"kernel", b"console=ttyS0"];
+        for (stage, part) in [BootStage::Bootloader, BootStage::Kernel, BootStage::Config].into_iter().zip(parts) {
+            log.record(stage, 0, 0, part.len() as u64, digest_of(&[part]), format_args!("test"));
+        }
+
+        // İşletim sisteminin yapacağı gibi: sıfır PCR'dan başlayıp olay özetlerini zincirle.
+        let mut pcr = [0u8; DIGEST_LEN];
+        for event in log.events() {
+            pcr = digest_of(&[&pcr, &event.digest]);
+        }
+        assert_eq!(log.events().len(), 3);
+        assert_eq!(log.pcr, pcr);
+        assert_eq!(log.events()[2].stage(), Some(BootStage::Config));
+        assert_eq!(log.events()[2].digest, digest_of(&[b"console=ttyS0"]));
+    }
+
+    #[test]
+    fn events_past_capacity_are_dropped_but_still_extend_the_pcr() {
+        let mut log = BootLog::new();
+        for i in 0..BOOT_LOG_MAX_EVENTS + 2 {
+            log.record(BootStage::Kernel, 0, 0, 1, digest_of(&[&[i as u8]]), format_args!("event {}", i));
+        }
+        assert_eq!(log.events().len(), BOOT_LOG_MAX_EVENTS);
+        assert_eq!(log.dropped_events, 2);
+        assert_eq!(log.events()[BOOT_LOG_MAX_EVENTS - 1].description(), "event 15");
+
+        let mut pcr = [0u8; DIGEST_LEN];
+        for i in 0..BOOT_LOG_MAX_EVENTS + 2 {
+            pcr = digest_of(&[&pcr, &digest_of(&[&[i as u8]])]);
+        }
+        assert_eq!(log.pcr, pcr);
+    }
+
+    #[test]
+    fn long_description_is_cut_and_nul_terminated() {
+        let mut log = BootLog::new();
+        log.record(BootStage::Config, 0, 0, 0, [0; DIGEST_LEN], format_args!("{}", "x".repeat(100)));
+        let event = &log.events()[0];
+        assert_eq!(event.description().len(), BOOT_EVENT_DESCRIPTION_LEN - 1);
+        assert_eq!(event.description[BOOT_EVENT_DESCRIPTION_LEN - 1], 0);
+    }
+}
@@ -0,0 +1,222 @@
+#![no_std]
+
+// MMIO yazmaç erişim katmanı.
+// Sürücüler ham `*mut u32` işaretçileri oluşturmak yerine `RegisterBus` trait'i üzerinden
+// yazmaçlara erişir. Gerçek donanımda `Mmio` (volatile erişim), testlerde ise `RecordingBus`
+// kullanılır; böylece bir sürücünün yaptığı yazmaç yazma sırası birebir doğrulanabilir.
+
+/// Bir yazmaç içindeki bit alanını tanımlar (başlangıç biti ve genişlik).
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Field {
+    pub shift: u32,
+    pub width: u32,
+}
+
+impl Field {
+    pub const fn new(shift: u32, width: u32) -> Self {
+        Field { shift, width }
+    }
+
+    /// Tek bitlik alan.
+    pub const fn bit(n: u32) -> Self {
+        Field { shift: n, width: 1 }
+    }
+
+    /// Alanın yazmaç içindeki maskesi.
+    pub const fn mask(&self) -> u32 {
+        if self.width >= 32 { u32::MAX } else { ((1u32 << self.width) - 1) << self.shift }
+    }
+
+    /// Yazmaç değerinden alanın değerini çıkarır.
+    pub const fn get(&self, reg_value: u32) -> u32 {
+        (reg_value & self.mask()) >> self.shift
+    }
+
+    /// Yazmaç değerinde alanı verilen değerle değiştirir, diğer bitlere dokunmaz.
+    pub const fn set(&self, reg_value: u32, field_value: u32) -> u32 {
+        (reg_value & !self.mask()) | ((field_value << self.shift) & self.mask())
+    }
+}
+
+/// Yazmaç erişim arka ucu.
+/// `read32`/`write32` zorunludur; diğer işlemler bunların üzerine kurulur.
+pub trait RegisterBus {
+    /// # Safety
+    /// `addr` geçerli bir yazmaç adresi olmalıdır.
+    unsafe fn read32(&mut self, addr: usize) -> u32;
+
+    /// # Safety
+    /// `addr` geçerli bir yazmaç adresi olmalıdır.
+    unsafe fn write32(&mut self, addr: usize, value: u32);
+
+    /// Oku-değiştir-yaz.
+    /// # Safety
+    /// `addr` geçerli bir yazmaç adresi olmalıdır.
+    unsafe fn modify32<F: FnOnce(u32) -> u32>(&mut self, addr: usize, f: F) {
+        let value = self.read32(addr);
+        self.write32(addr, f(value));
+    }
+
+    /// Maskedeki bitleri 1 yapar.
+    /// # Safety
+    /// `addr` geçerli bir yazmaç adresi olmalıdır.
+    unsafe fn set_bits(&mut self, addr: usize, mask: u32) {
+        self.modify32(addr, |v| v | mask);
+    }
+
+    /// Maskedeki bitleri 0 yapar.
+    /// # Safety
+    /// `addr` geçerli bir yazmaç adresi olmalıdır.
+    unsafe fn clear_bits(&mut self, addr: usize, mask: u32) {
+        self.modify32(addr, |v| v & !mask);
+    }
+
+    /// Bir bit alanını okur.
+    /// # Safety
+    /// `addr` geçerli bir yazmaç adresi olmalıdır.
+    unsafe fn read_field(&mut self, addr: usize, field: Field) -> u32 {
+        field.get(self.read32(addr))
+    }
+
+    /// Bir bit alanını yazar (oku-değiştir-yaz).
+    /// # Safety
+    /// `addr` geçerli bir yazmaç adresi olmalıdır.
+    unsafe fn write_field(&mut self, addr: usize, field: Field, value: u32) {
+        self.modify32(addr, |v| field.set(v, value));
+    }
+
+    /// En az `us` mikrosaniye bekler (başlatma dizilerindeki zorunlu gecikmeler için).
+    /// Varsayılan uygulama zaman kaynağıyla (`timer`) meşgul bekler.
+    /// # Safety
+    /// Yazmaç erişimi yapmaz; arka uçların kendi uygulamaları için "unsafe"dir.
+    unsafe fn delay_us(&mut self, us: u32) {
+        crate::timer::delay_us(us as u64);
+    }
+
+    /// Koşul sağlanana kadar yazmacı yoklar (polling).
+    /// # Safety
+    /// `addr` geçerli bir yazmaç adresi olmalıdır.
+    unsafe fn wait_until<F: Fn(u32) -> bool>(&mut self, addr: usize, condition: F) {
+        while !condition(self.read32(addr)) { /* Bekle */ }
+    }
+}
+
+/// Gerçek donanım arka ucu: volatile MMIO erişimi.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Mmio;
+
+impl RegisterBus for Mmio {
+    unsafe fn read32(&mut self, addr: usize) -> u32 {
+        (addr as *const u32).read_volatile()
+    }
+
+    unsafe fn write32(&mut self, addr: usize, value: u32) {
+        (addr as *mut u32).write_volatile(value)
+    }
+}
+
+// --- Test/Mock arka ucu ---
+
+// RecordingBus'ın saklayabileceği en fazla erişim sayısı.
+pub const RECORDING_BUS_MAX_ACCESSES: usize = 128;
+// RecordingBus'ın değerini hatırlayabileceği en fazla yazmaç sayısı.
+pub const RECORDING_BUS_MAX_REGISTERS: usize = 32;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum AccessKind {
+    Read,
+    Write,
+    Delay, // `delay_us`; `addr` 0, `value` mikrosaniye
+}
+
+/// Kaydedilen tek bir yazmaç erişimi.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Access {
+    pub kind: AccessKind,
+    pub addr: usize,
+    pub value: u32,
+}
+
+/// Yazmaç erişimlerini sırasıyla kaydeden sahte (mock) arka uç.
+/// Okumalar, önceden `preset` ile verilen veya en son yazılan değeri döndürür (yoksa 0).
+/// Heap kullanmaz; kapasite aşılırsa fazla erişimler sayılır ama saklanmaz.
+pub struct RecordingBus {
+    accesses: [Access; RECORDING_BUS_MAX_ACCESSES],
+    access_count: usize,
+    registers: [(usize, u32); RECORDING_BUS_MAX_REGISTERS],
+    register_count: usize,
+}
+
+impl RecordingBus {
+    pub const fn new() -> Self {
+        RecordingBus {
+            accesses: [Access { kind: AccessKind::Read, addr: 0, value: 0 }; RECORDING_BUS_MAX_ACCESSES],
+            access_count: 0,
+            registers: [(0, 0); RECORDING_BUS_MAX_REGISTERS],
+            register_count: 0,
+        }
+    }
+
+    /// Bir yazmacın okunduğunda döndüreceği değeri ayarlar (örn. "hazır" durum biti).
+    pub fn preset(&mut self, addr: usize, value: u32) {
+        self.store(addr, value);
+    }
+
+    /// Kaydedilen tüm erişimler (okuma ve yazma), gerçekleşme sırasıyla.
+    pub fn accesses(&self) -> &[Access] {
+        &self.accesses[..core::cmp::min(self.access_count, RECORDING_BUS_MAX_ACCESSES)]
+    }
+
+    /// Sadece yazma erişimleri, gerçekleşme sırasıyla.
+    pub fn writes(&self) -> impl Iterator<Item = (usize, u32)> + '_ {
+        self.accesses().iter().filter(|a| a.kind == AccessKind::Write).map(|a| (a.addr, a.value))
+    }
+
+    /// Kapasite aşıldıysa true döner (bazı erişimler kaydedilmemiştir).
+    pub fn overflowed(&self) -> bool {
+        self.access_count > RECORDING_BUS_MAX_ACCESSES
+    }
+
+    /// Kayıtları temizler; preset edilen yazmaç değerleri korunur.
+    pub fn clear(&mut self) {
+        self.access_count = 0;
+    }
+
+    fn record(&mut self, kind: AccessKind, addr: usize, value: u32) {
+        if self.access_count < RECORDING_BUS_MAX_ACCESSES {
+            self.accesses[self.access_count] = Access { kind, addr, value };
+        }
+        self.access_count += 1;
+    }
+
+    fn load(&self, addr: usize) -> u32 {
+        self.registers[..self.register_count].iter().find(|(a, _)| *a == addr).map(|(_, v)| *v).unwrap_or(0)
+    }
+
+    fn store(&mut self, addr: usize, value: u32) {
+        if let Some(slot) = self.registers[..self.register_count].iter_mut().find(|(a, _)| *a == addr) {
+            slot.1 = value;
+        } else if self.register_count < RECORDING_BUS_MAX_REGISTERS {
+            self.registers[self.register_count] = (addr, value);
+            self.register_count += 1;
+        }
+    }
+}
+
+impl RegisterBus for RecordingBus {
+    unsafe fn read32(&mut self, addr: usize) -> u32 {
+        let value = self.load(addr);
+        self.record(AccessKind::Read, addr, value);
+        value
+    }
+
+    unsafe fn write32(&mut self, addr: usize, value: u32) {
+        self.record(AccessKind::Write, addr, value);
+        self.store(addr, value);
+    }
+
+    // Beklemez, sadece kaydeder.
+    unsafe fn delay_us(&mut self, us: u32) {
+        self.record(AccessKind::Delay, 0, us);
+    }
+}
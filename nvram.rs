@@ -0,0 +1,636 @@
+#![no_std]
+
+// Kalıcı değişken deposu (NVRAM benzeri anahtar/değer kaydı).
+// eMMC'nin ayrılmış bölgesinde (storage::EMMC_NVRAM_START_LBA) iki bank halinde tutulur ve
+// `BlockDevice` üzerinden erişilir.
+//
+// Her bank:
+//   blok 0      : bank başlığı ("PBNV", sürüm, nesil, CRC32) - compaction sırasında EN SON yazılır
+//   blok 1..N   : sadece sona eklenen (append-only) kayıtlar
+//
+// Kayıt: 16 baytlık başlık (sihirli sayı, bayraklar, anahtar/değer uzunlukları, bank nesli, CRC32)
+// + anahtar + değer, 4 bayta hizalı. Kayıtlar blok sınırını aşmaz; bloğun kalanı sıfırla doldurulur.
+//
+// Güç kaybına dayanıklılık:
+// - Bir `commit` her zaman yeni bloklara yazılır; içinde onaylanmış kayıt olan bir blok asla yeniden
+//   yazılmaz. Bir işlemin (transaction) son kaydı COMMIT bayrağı taşır; açılışta son COMMIT'ten
+//   sonraki kayıtlar (yarım kalmış yazma) yok sayılır, yani bir commit ya tamamen ya hiç uygulanır.
+// - Bank dolduğunda canlı değişkenler diğer banka tek bir işlem olarak yazılır, ardından yeni neslin
+//   başlığı yazılır. Başlık yazılana kadar eski bank geçerli kalır; açılışta geçerli başlığı olan en
+//   yüksek nesilli bank kullanılır.
+// - Kayıtlar bank neslini taşır; bankın önceki kullanımından kalan eski kayıtlar böylece ayırt edilir.
+// Yazmalar bloklar ve banklar arasında sırayla ilerlediği için aşınma eMMC bölgesine yayılır.
+
+use crate::crc::Crc32;
+use crate::partition;
+use crate::storage::{BlockDevice, StorageError, BLOCK_SIZE, EMMC_NVRAM_BLOCKS, EMMC_NVRAM_START_LBA};
+
+pub const NVRAM_MAX_KEY_LEN: usize = 32;
+pub const NVRAM_MAX_VALUE_LEN: usize = 255;
+pub const NVRAM_MAX_VARS: usize = 32;
+
+const NVRAM_BANK_COUNT: u64 = 2;
+const NVRAM_BANK_BLOCKS: u64 = EMMC_NVRAM_BLOCKS / NVRAM_BANK_COUNT;
+const BANK_MAGIC: [u8; 4] = *b"PBNV";
+const BANK_VERSION: u32 = 1;
+
+const RECORD_MAGIC: u16 = 0x564E; // "NV"
+const RECORD_HEADER_SIZE: usize = 16;
+const RECORD_FLAG_COMMIT: u8 = 1 << 0; // İşlemin son kaydı
+const RECORD_FLAG_DELETE: u8 = 1 << 1; // Değişken silindi (değer boş)
+
+#[derive(Debug)]
+pub enum NvramError {
+    Storage(StorageError),
+    NotAvailable,     // eMMC yok veya depo yüklenmedi
+    RegionInUse,      // Ayrılmış bölge bir bölümle çakışıyor; veri bozulmasın diye kullanılmaz
+    InvalidKey,       // Boş, çok uzun veya yazdırılamayan karakter içeren anahtar
+    ValueTooLong,
+    TooManyVariables,
+    NoSpace,          // Canlı değişkenler compaction sonrasında bile banka sığmıyor
+}
+
+impl From<StorageError> for NvramError {
+    fn from(err: StorageError) -> Self { NvramError::Storage(err) }
+}
+
+#[derive(Copy, Clone)]
+struct Entry {
+    used: bool,
+    dirty: bool,   // Henüz commit edilmedi
+    deleted: bool, // Commit'te silme kaydı yazılacak
+    key: [u8; NVRAM_MAX_KEY_LEN],
+    key_len: u8,
+    value: [u8; NVRAM_MAX_VALUE_LEN],
+    value_len: u8,
+}
+
+impl Entry {
+    const EMPTY: Entry = Entry {
+        used: false,
+        dirty: false,
+        deleted: false,
+        key: [0; NVRAM_MAX_KEY_LEN],
+        key_len: 0,
+        value: [0; NVRAM_MAX_VALUE_LEN],
+        value_len: 0,
+    };
+
+    fn key(&self) -> &[u8] {
+        &self.key[..self.key_len as usize]
+    }
+
+    fn value(&self) -> &[u8] {
+        &self.value[..self.value_len as usize]
+    }
+
+    fn is_live(&self) -> bool {
+        self.used && !self.deleted
+    }
+
+    fn record_size(&self) -> usize {
+        record_size(self.key_len as usize, if self.deleted { 0 } else { self.value_len as usize })
+    }
+}
+
+fn record_size(key_len: usize, value_len: usize) -> usize {
+    (RECORD_HEADER_SIZE + key_len + value_len + 3) & !3
+}
+
+fn le_u16(b: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([b[offset], b[offset + 1]])
+}
+
+fn le_u32(b: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([b[offset], b[offset + 1], b[offset + 2], b[offset + 3]])
+}
+
+fn record_crc(header: &[u8], key: &[u8], value: &[u8]) -> u32 {
+    let mut crc = Crc32::new();
+    crc.update(&header[0..12]);
+    crc.update(key);
+    crc.update(value);
+    crc.finish()
+}
+
+/// Bloktaki `offset`'te geçerli bir kayıt varsa (bayraklar, anahtar, değer, sonraki ofset) döndürür.
+fn decode_record(block: &[u8], offset: usize, generation: u32) -> Option<(u8, &[u8], &[u8], usize)> {
+    if offset + RECORD_HEADER_SIZE > BLOCK_SIZE {
+        return None;
+    }
+    let header = &block[offset..offset + RECORD_HEADER_SIZE];
+    if le_u16(header, 0) != RECORD_MAGIC || le_u32(header, 8) != generation {
+        return None;
+    }
+    let flags = header[2];
+    let key_len = header[3] as usize;
+    let value_len = le_u16(header, 4) as usize;
+    let end = offset + record_size(key_len, value_len);
+    if key_len == 0 || key_len > NVRAM_MAX_KEY_LEN || value_len > NVRAM_MAX_VALUE_LEN || end > BLOCK_SIZE {
+        return None;
+    }
+    let key = &block[offset + RECORD_HEADER_SIZE..offset + RECORD_HEADER_SIZE + key_len];
+    let value = &block[offset + RECORD_HEADER_SIZE + key_len..offset + RECORD_HEADER_SIZE + key_len + value_len];
+    if record_crc(header, key, value) != le_u32(header, 12) {
+        return None;
+    }
+    Some((flags, key, value, end))
+}
+
+fn encode_record(block: &mut [u8], offset: usize, generation: u32, flags: u8, key: &[u8], value: &[u8]) -> usize {
+    let end = offset + record_size(key.len(), value.len());
+    let record = &mut block[offset..end];
+    record.fill(0);
+    record[0..2].copy_from_slice(&RECORD_MAGIC.to_le_bytes());
+    record[2] = flags;
+    record[3] = key.len() as u8;
+    record[4..6].copy_from_slice(&(value.len() as u16).to_le_bytes());
+    record[8..12].copy_from_slice(&generation.to_le_bytes());
+    record[RECORD_HEADER_SIZE..RECORD_HEADER_SIZE + key.len()].copy_from_slice(key);
+    record[RECORD_HEADER_SIZE + key.len()..RECORD_HEADER_SIZE + key.len() + value.len()].copy_from_slice(value);
+    let crc = record_crc(&record[0..RECORD_HEADER_SIZE], key, value);
+    record[12..16].copy_from_slice(&crc.to_le_bytes());
+    end
+}
+
+fn bank_start_lba(bank: u64) -> u64 {
+    EMMC_NVRAM_START_LBA + bank * NVRAM_BANK_BLOCKS
+}
+
+/// Bank başlığını okur; geçerliyse neslini döndürür.
+fn read_bank_generation<D: BlockDevice + ?Sized>(device: &mut D, bank: u64) -> Result<Option<u32>, NvramError> {
+    let mut block = [0u8; BLOCK_SIZE];
+    device.read_block(bank_start_lba(bank), &mut block)?;
+    let mut crc = Crc32::new();
+    crc.update(&block[0..12]);
+    if block[0..4] != BANK_MAGIC || le_u32(&block, 4) != BANK_VERSION || crc.finish() != le_u32(&block, 12) {
+        return Ok(None);
+    }
+    Ok(Some(le_u32(&block, 8)))
+}
+
+fn write_bank_header<D: BlockDevice + ?Sized>(device: &mut D, bank: u64, generation: u32) -> Result<(), NvramError> {
+    let mut block = [0u8; BLOCK_SIZE];
+    block[0..4].copy_from_slice(&BANK_MAGIC);
+    block[4..8].copy_from_slice(&BANK_VERSION.to_le_bytes());
+    block[8..12].copy_from_slice(&generation.to_le_bytes());
+    let mut crc = Crc32::new();
+    crc.update(&block[0..12]);
+    block[12..16].copy_from_slice(&crc.finish().to_le_bytes());
+    device.write_block(bank_start_lba(bank), &block)?;
+    Ok(())
+}
+
+/// Anahtarlar yazdırılabilir ASCII olmalı, boşluk ve '=' içermemelidir.
+pub fn is_valid_key(key: &str) -> bool {
+    !key.is_empty() && key.len() <= NVRAM_MAX_KEY_LEN && key.bytes().all(|b| b > b' ' && b < 0x7F && b != b'=')
+}
+
+#[derive(Debug, Copy, Clone)]
+struct ActiveBank {
+    bank: u64,
+    generation: u32,
+    next_block: u64, // Bir sonraki commit'in yazılacağı blok (bank içi)
+}
+
+/// Değişkenlerin RAM'deki kopyası ve eMMC'deki log'un durumu.
+/// `set`/`remove` sadece RAM'i değiştirir; değişiklikler `commit` ile tek bir işlem olarak kalıcı olur.
+pub struct Nvram {
+    entries: [Entry; NVRAM_MAX_VARS],
+    active: Option<ActiveBank>,
+    loaded: bool,
+}
+
+impl Nvram {
+    pub const fn new() -> Self {
+        Nvram { entries: [Entry::EMPTY; NVRAM_MAX_VARS], active: None, loaded: false }
+    }
+
+    /// Depo aygıttan okunduysa true (okunamadıysa değişkenler sadece RAM'de tutulur).
+    pub fn is_loaded(&self) -> bool {
+        self.loaded
+    }
+
+    /// Bankları tarar ve en yeni geçerli banktaki onaylanmış kayıtları RAM'e yükler.
+    pub fn load<D: BlockDevice + ?Sized>(&mut self, device: &mut D) -> Result<(), NvramError> {
+        self.entries = [Entry::EMPTY; NVRAM_MAX_VARS];
+        self.active = None;
+        self.loaded = false;
+        check_region_free(device)?;
+
+        for bank in 0..NVRAM_BANK_COUNT {
+            if let Some(generation) = read_bank_generation(device, bank)? {
+                if self.active.is_none_or(|a| generation > a.generation) {
+                    self.active = Some(ActiveBank { bank, generation, next_block: 1 });
+                }
+            }
+        }
+        if let Some(active) = self.active {
+            self.replay(device, active)?;
+        }
+        self.loaded = true;
+        Ok(())
+    }
+
+    // İki geçiş: önce son COMMIT kaydının konumu bulunur, sonra o konuma kadarki kayıtlar uygulanır.
+    fn replay<D: BlockDevice + ?Sized>(&mut self, device: &mut D, active: ActiveBank) -> Result<(), NvramError> {
+        let mut block = [0u8; BLOCK_SIZE];
+        let mut committed_end = (1u64, 0usize);
+        'scan: for index in 1..NVRAM_BANK_BLOCKS {
+            device.read_block(bank_start_lba(active.bank) + index, &mut block)?;
+            let mut offset = 0;
+            let mut found = false;
+            while let Some((flags, _, _, end)) = decode_record(&block, offset, active.generation) {
+                found = true;
+                if flags & RECORD_FLAG_COMMIT != 0 {
+                    committed_end = (index, end);
+                }
+                offset = end;
+            }
+            if !found {
+                break 'scan; // Boş veya yarım yazılmış blok: log burada biter
+            }
+        }
+
+        for index in 1..=committed_end.0 {
+            if index == committed_end.0 && committed_end.1 == 0 {
+                break;
+            }
+            device.read_block(bank_start_lba(active.bank) + index, &mut block)?;
+            let limit = if index == committed_end.0 { committed_end.1 } else { BLOCK_SIZE };
+            let mut offset = 0;
+            while offset < limit {
+                let (flags, key, value, end) = match decode_record(&block, offset, active.generation) {
+                    Some(record) => record,
+                    None => break,
+                };
+                // Tarama ile aynı kayıtlar olduğu için anahtar/değer sınırları zaten doğrulanmıştır.
+                if flags & RECORD_FLAG_DELETE != 0 {
+                    if let Some(i) = self.find(key) {
+                        self.entries[i] = Entry::EMPTY;
+                    }
+                } else {
+                    let _ = self.store(key, value, false);
+                }
+                offset = end;
+            }
+        }
+
+        let next_block = if committed_end.1 == 0 { committed_end.0 } else { committed_end.0 + 1 };
+        self.active = Some(ActiveBank { next_block, ..active });
+        Ok(())
+    }
+
+    fn find(&self, key: &[u8]) -> Option<usize> {
+        self.entries.iter().position(|e| e.used && e.key() == key)
+    }
+
+    fn store(&mut self, key: &[u8], value: &[u8], dirty: bool) -> Result<(), NvramError> {
+        let index = match self.find(key) {
+            Some(i) => i,
+            None => self.entries.iter().position(|e| !e.used).ok_or(NvramError::TooManyVariables)?,
+        };
+        let entry = &mut self.entries[index];
+        entry.used = true;
+        entry.dirty = dirty;
+        entry.deleted = false;
+        entry.key[..key.len()].copy_from_slice(key);
+        entry.key_len = key.len() as u8;
+        entry.value[..value.len()].copy_from_slice(value);
+        entry.value_len = value.len() as u8;
+        Ok(())
+    }
+
+    pub fn get(&self, key: &str) -> Option<&[u8]> {
+        self.find(key.as_bytes()).map(|i| &self.entries[i]).filter(|e| !e.deleted).map(|e| e.value())
+    }
+
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        self.get(key).and_then(|v| core::str::from_utf8(v).ok())
+    }
+
+    /// Değişkeni RAM'de ayarlar (kalıcı olması için `commit` gerekir).
+    pub fn set(&mut self, key: &str, value: &[u8]) -> Result<(), NvramError> {
+        if !is_valid_key(key) {
+            return Err(NvramError::InvalidKey);
+        }
+        if value.len() > NVRAM_MAX_VALUE_LEN {
+            return Err(NvramError::ValueTooLong);
+        }
+        self.store(key.as_bytes(), value, true)
+    }
+
+    /// Değişkeni siler (kalıcı olması için `commit` gerekir). Değişken yoksa false döner.
+    pub fn remove(&mut self, key: &str) -> bool {
+        match self.find(key.as_bytes()) {
+            Some(i) if !self.entries[i].deleted => {
+                self.entries[i].deleted = true;
+                self.entries[i].dirty = true;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Commit edilmemiş değişiklik var mı.
+    pub fn is_dirty(&self) -> bool {
+        self.entries.iter().any(|e| e.used && e.dirty)
+    }
+
+    /// Canlı değişkenleri (anahtar, değer) olarak dolaşır.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &[u8])> {
+        self.entries.iter()
+            .filter(|e| e.is_live())
+            .map(|e| (core::str::from_utf8(e.key()).unwrap_or("?"), e.value()))
+    }
+
+    /// Değişiklikleri tek bir işlem olarak kalıcı hale getirir.
+    /// Etkin bankta yer yoksa canlı değişkenler diğer banka sıkıştırılır (compaction).
+    pub fn commit<D: BlockDevice + ?Sized>(&mut self, device: &mut D) -> Result<(), NvramError> {
+        if !self.loaded {
+            return Err(NvramError::NotAvailable);
+        }
+        if !self.is_dirty() {
+            return Ok(());
+        }
+        match self.active {
+            Some(active) if active.next_block + self.blocks_needed(|e| e.used && e.dirty) <= NVRAM_BANK_BLOCKS => {
+                let next_block = self.write_records(device, active.bank, active.generation, active.next_block, |e| e.used && e.dirty)?;
+                self.active = Some(ActiveBank { next_block, ..active });
+            }
+            _ => self.compact(device)?,
+        }
+        for entry in self.entries.iter_mut() {
+            if entry.deleted {
+                *entry = Entry::EMPTY;
+            }
+            entry.dirty = false;
+        }
+        Ok(())
+    }
+
+    /// Canlı değişkenleri boş banka yazar ve bu bankı yeni nesil olarak etkinleştirir.
+    pub fn compact<D: BlockDevice + ?Sized>(&mut self, device: &mut D) -> Result<(), NvramError> {
+        if 1 + self.blocks_needed(Entry::is_live) > NVRAM_BANK_BLOCKS {
+            return Err(NvramError::NoSpace);
+        }
+        let (bank, generation) = match self.active {
+            Some(active) => ((active.bank + 1) % NVRAM_BANK_COUNT, active.generation.wrapping_add(1)),
+            None => (0, 1),
+        };
+        let next_block = self.write_records(device, bank, generation, 1, Entry::is_live)?;
+        // Başlık en son yazılır: bu noktadan önce güç kesilirse eski bank geçerliliğini korur.
+        write_bank_header(device, bank, generation)?;
+        self.active = Some(ActiveBank { bank, generation, next_block });
+        Ok(())
+    }
+
+    fn blocks_needed(&self, select: impl Fn(&Entry) -> bool) -> u64 {
+        let mut blocks = 0;
+        let mut offset = BLOCK_SIZE; // İlk kayıt yeni blok açar
+        for entry in self.entries.iter().filter(|e| select(e)) {
+            let size = entry.record_size();
+            if offset + size > BLOCK_SIZE {
+                blocks += 1;
+                offset = 0;
+            }
+            offset += size;
+        }
+        blocks
+    }
+
+    // Seçilen girdileri `first_block`'tan itibaren kayıt olarak yazar; son kayıt COMMIT taşır.
+    // Bir sonraki boş bloğu döndürür.
+    fn write_records<D: BlockDevice + ?Sized>(&self, device: &mut D, bank: u64, generation: u32, first_block: u64, select: impl Fn(&Entry) -> bool) -> Result<u64, NvramError> {
+        let last = match self.entries.iter().rposition(&select) {
+            Some(i) => i,
+            None => return Ok(first_block),
+        };
+        let mut block = [0u8; BLOCK_SIZE];
+        let mut index = first_block;
+        let mut offset = 0;
+        for (i, entry) in self.entries.iter().enumerate().filter(|(_, e)| select(e)) {
+            if offset + entry.record_size() > BLOCK_SIZE {
+                device.write_block(bank_start_lba(bank) + index, &block)?;
+                block = [0u8; BLOCK_SIZE];
+                index += 1;
+                offset = 0;
+            }
+            let mut flags = if i == last { RECORD_FLAG_COMMIT } else { 0 };
+            let value = if entry.deleted {
+                flags |= RECORD_FLAG_DELETE;
+                &[][..]
+            } else {
+                entry.value()
+            };
+            offset = encode_record(&mut block, offset, generation, flags, entry.key(), value);
+        }
+        device.write_block(bank_start_lba(bank) + index, &block)?;
+        Ok(index + 1)
+    }
+}
+
+/// Ayrılmış bölgeyle çakışan bir bölüm varsa depoyu kullanmayı reddeder.
+/// Bölüm tablosu yoksa (boş eMMC) bölge serbest kabul edilir.
+fn check_region_free<D: BlockDevice + ?Sized>(device: &mut D) -> Result<(), NvramError> {
+    if device.block_count().is_none_or(|count| count < EMMC_NVRAM_START_LBA + EMMC_NVRAM_BLOCKS) {
+        return Err(NvramError::NotAvailable);
+    }
+    let table = match partition::read_partition_table(device) {
+        Ok(table) => table,
+        Err(_) => return Ok(()),
+    };
+    let region_end = EMMC_NVRAM_START_LBA + EMMC_NVRAM_BLOCKS;
+    if table.iter().any(|p| p.start_lba < region_end && p.start_lba + p.block_count > EMMC_NVRAM_START_LBA) {
+        return Err(NvramError::RegionInUse);
+    }
+    Ok(())
+}
+
+// --- Global NVRAM ---
+// # Safety: Global mutable static kullanimi unsafe'dir.
+pub static mut NVRAM_GLOBAL: Nvram = Nvram::new();
+
+/// Depoyu eMMC'den yükler. eMMC yoksa veya bölge kullanılamıyorsa değişkenler sadece RAM'de tutulur.
+/// # Safety
+/// Global eMMC aygıtına ve global NVRAM'e erişir.
+pub unsafe fn init() -> Result<(), NvramError> {
+    let device = crate::storage::device_by_name("emic").map_err(|_| NvramError::NotAvailable)?;
+    NVRAM_GLOBAL.load(device)
+}
+
+/// Global NVRAM'deki değişiklikleri eMMC'ye yazar.
+/// # Safety
+/// Global eMMC aygıtına ve global NVRAM'e erişir.
+pub unsafe fn commit() -> Result<(), NvramError> {
+    let device = crate::storage::device_by_name("emic").map_err(|_| NvramError::NotAvailable)?;
+    NVRAM_GLOBAL.commit(device)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::RamDisk;
+
+    // NVRAM bölgesinin sonuna kadar; bölüm tablosu yok, bölge serbest kabul edilir.
+    const DISK_BYTES: usize = (EMMC_NVRAM_START_LBA + EMMC_NVRAM_BLOCKS) as usize * BLOCK_SIZE;
+    const BIG: [u8; NVRAM_MAX_VALUE_LEN] = [b'x'; NVRAM_MAX_VALUE_LEN];
+
+    type State = Vec<(String, Vec<u8>)>;
+
+    fn state(nvram: &Nvram) -> State {
+        let mut vars: State = nvram.iter().map(|(k, v)| (k.to_string(), v.to_vec())).collect();
+        vars.sort();
+        vars
+    }
+
+    fn reload(image: &mut [u8]) -> Nvram {
+        let mut nvram = Nvram::new();
+        nvram.load(&mut RamDisk::new(image)).expect("load after power loss must succeed");
+        nvram
+    }
+
+    fn committed(image: &mut [u8], change: impl Fn(&mut Nvram)) -> Nvram {
+        let mut nvram = reload(image);
+        change(&mut nvram);
+        nvram.commit(&mut RamDisk::new(image)).unwrap();
+        nvram
+    }
+
+    // Değişikliği her olası yazma sayısında güç kesilerek dener: kesilen her denemeden sonra `load`
+    // bir önceki onaylanmış durumu, tamamlanan denemeden sonra yeni durumu görmelidir.
+    // Tamamlanmış commit'in yazdığı blok sayısını döndürür.
+    fn cut_power_at_every_write(image: &[u8], change: impl Fn(&mut Nvram)) -> usize {
+        let before = state(&reload(&mut image.to_vec()));
+        for limit in 0.. {
+            let mut copy = image.to_vec();
+            let mut nvram = reload(&mut copy);
+            change(&mut nvram);
+            let expected = state(&nvram);
+            let mut disk = RamDisk::new(&mut copy);
+            disk.set_write_limit(Some(limit));
+            match nvram.commit(&mut disk) {
+                Ok(()) => {
+                    assert_eq!(state(&reload(&mut copy)), expected);
+                    return limit;
+                }
+                Err(err) => {
+                    assert!(matches!(err, NvramError::Storage(StorageError::WriteError)), "{:?}", err);
+                    assert_eq!(state(&reload(&mut copy)), before, "power cut after {} writes", limit);
+                }
+            }
+        }
+        unreachable!()
+    }
+
+    fn base_image() -> Vec<u8> {
+        let mut image = vec![0u8; DISK_BYTES];
+        committed(&mut image, |nv| {
+            nv.set("bootdevice", b"emic").unwrap();
+            nv.set("bootargs", b"console=ttyS0").unwrap();
+        });
+        image
+    }
+
+    #[test]
+    fn values_survive_reload() {
+        let mut image = base_image();
+        committed(&mut image, |nv| {
+            nv.set("bootargs", b"quiet").unwrap();
+            assert!(nv.remove("bootdevice"));
+        });
+        let nvram = reload(&mut image);
+        assert_eq!(nvram.get("bootargs"), Some(&b"quiet"[..]));
+        assert_eq!(nvram.get("bootdevice"), None);
+        assert!(!nvram.is_dirty());
+    }
+
+    #[test]
+    fn multi_block_commit_is_all_or_nothing() {
+        let image = base_image();
+        let writes = cut_power_at_every_write(&image, |nv| {
+            for key in ["big0", "big1", "big2"] {
+                nv.set(key, &BIG).unwrap();
+            }
+            nv.remove("bootargs");
+        });
+        // Üç büyük kayıt aynı bloğa sığmaz: COMMIT'siz bloklar gerçekten diske ulaştı.
+        assert!(writes >= 3, "{}", writes);
+    }
+
+    #[test]
+    fn records_without_commit_flag_are_ignored() {
+        let mut image = base_image();
+        let mut block = [0u8; BLOCK_SIZE];
+        let end = encode_record(&mut block, 0, 1, 0, b"bootargs", b"half-written");
+        encode_record(&mut block, end, 1, 0, b"extra", b"1");
+        // Tek bank başlığı + ilk commit'in bloğu: sıradaki blok 2.
+        let lba = bank_start_lba(0) + 2;
+        RamDisk::new(&mut image).write_block(lba, &block).unwrap();
+
+        let mut nvram = reload(&mut image);
+        assert_eq!(nvram.get("bootargs"), Some(&b"console=ttyS0"[..]));
+        assert_eq!(nvram.get("extra"), None);
+
+        // Sonraki commit onaylanmamış bloğun üzerine yazar ve yeniden yüklenince görünür.
+        nvram.set("extra", b"2").unwrap();
+        nvram.commit(&mut RamDisk::new(&mut image)).unwrap();
+        let nvram = reload(&mut image);
+        assert_eq!(nvram.get("extra"), Some(&b"2"[..]));
+        assert_eq!(nvram.get("bootargs"), Some(&b"console=ttyS0"[..]));
+    }
+
+    #[test]
+    fn replay_stops_at_torn_last_record() {
+        let mut image = base_image();
+        committed(&mut image, |nv| nv.set("bootargs", b"torn").unwrap());
+        // Son commit'in bloğu yarıya kadar yazılmış gibi: kaydın CRC'si tutmaz.
+        let offset = (bank_start_lba(0) + 2) as usize * BLOCK_SIZE;
+        image[offset + 20..offset + BLOCK_SIZE].fill(0xFF);
+
+        let mut nvram = reload(&mut image);
+        assert_eq!(nvram.get("bootargs"), Some(&b"console=ttyS0"[..]));
+
+        nvram.set("bootdevice", b"sd").unwrap();
+        nvram.commit(&mut RamDisk::new(&mut image)).unwrap();
+        let nvram = reload(&mut image);
+        assert_eq!(nvram.get("bootdevice"), Some(&b"sd"[..]));
+        assert_eq!(nvram.get("bootargs"), Some(&b"console=ttyS0"[..]));
+    }
+
+    #[test]
+    fn compaction_is_atomic_until_the_new_header_is_written() {
+        let mut image = base_image();
+        committed(&mut image, |nv| {
+            for key in ["big0", "big1", "big2", "big3"] {
+                nv.set(key, &BIG).unwrap();
+            }
+        });
+        // Tek bloklu commit'lerle bank 0'ı doldur: başlık, base, dört büyük kayıt bloğu ve sayaçlar.
+        let used = 1 + 1 + 4;
+        for i in 0..NVRAM_BANK_BLOCKS - used {
+            committed(&mut image, |nv| nv.set("counter", i.to_string().as_bytes()).unwrap());
+        }
+        assert_eq!(read_bank_generation(&mut RamDisk::new(&mut image), 1).unwrap(), None);
+
+        let change = |nv: &mut Nvram| nv.set("counter", b"done").unwrap();
+        let writes = cut_power_at_every_write(&image, change);
+        // Diğer banka yedi canlı değişken dört kayıt bloğuna sıkıştırıldı, ardından başlık yazıldı.
+        assert_eq!(writes, 4 + 1);
+
+        // Başlık yazılmadan kesilen compaction'dan sonra eski bank geçerli kalır ve yeniden denenebilir.
+        let mut copy = image.clone();
+        let mut nvram = reload(&mut copy);
+        change(&mut nvram);
+        let mut disk = RamDisk::new(&mut copy);
+        disk.set_write_limit(Some(writes - 1));
+        assert!(nvram.commit(&mut disk).is_err());
+        assert_eq!(read_bank_generation(&mut RamDisk::new(&mut copy), 1).unwrap(), None);
+        let nvram = committed(&mut copy, change);
+        assert_eq!(nvram.get("counter"), Some(&b"done"[..]));
+        let mut disk = RamDisk::new(&mut copy);
+        assert_eq!(read_bank_generation(&mut disk, 1).unwrap(), Some(2));
+        assert_eq!(read_bank_generation(&mut disk, 0).unwrap(), Some(1));
+        assert_eq!(state(&reload(&mut copy)), state(&nvram));
+    }
+}
@@ -0,0 +1,186 @@
+#![no_std]
+
+// Bölüm (partition) tablosu ortak katmanı.
+// Tablo biçimine (MBR/GPT) özgü ayrıştırma ayrı modüllerdedir; burada bulunan bölümler ortak bir
+// `PartitionInfo` listesinde toplanır ve her biri kendi `BlockDevice` görünümü olarak açılabilir.
+
+use crate::gpt::{self, Guid, GptName};
+use crate::mbr::{self, Mbr, MbrEntry};
+use crate::storage::{BlockDevice, StorageError};
+
+// Bir aygıtta takip edilebilecek en fazla bölüm sayısı (birincil + mantıksal).
+pub const MAX_PARTITIONS: usize = 32;
+
+#[derive(Debug)]
+pub enum PartitionError {
+    Storage(StorageError),
+    InvalidSignature,   // 0x55AA imzası yok
+    InvalidEntry,       // Bozuk bölüm girdisi (boot bayrağı, boyut vb.)
+    ExtendedChainLoop,  // Genişletilmiş bölüm zinciri döngüye giriyor veya çok uzun
+    TooManyPartitions,
+    NotFound,           // İstenen indekste bölüm yok
+    NoBootablePartition,
+    InvalidGptHeader,         // GPT imzası/boyutu/alanları geçersiz
+    GptHeaderCrcMismatch,
+    GptEntryArrayCrcMismatch,
+}
+
+impl From<StorageError> for PartitionError {
+    fn from(err: StorageError) -> Self { PartitionError::Storage(err) }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub enum PartitionKind {
+    // MBR birincil veya mantıksal (extended zincirinden gelen) bölüm
+    Mbr { partition_type: u8, bootable: bool, logical: bool },
+    // GPT girdisi
+    Gpt { type_guid: Guid, unique_guid: Guid, attributes: u64, name: GptName },
+}
+
+/// Bulunan bir bölümün aygıt üzerindeki konumu.
+/// `index` 1 tabanlıdır: MBR'de birincil bölümler 1-4, mantıksal bölümler 5'ten başlar;
+/// GPT'de girdi dizisindeki sıradır.
+#[derive(Debug, Copy, Clone)]
+pub struct PartitionInfo {
+    pub index: usize,
+    pub start_lba: u64,
+    pub block_count: u64,
+    pub kind: PartitionKind,
+}
+
+impl PartitionInfo {
+    pub fn is_bootable(&self) -> bool {
+        match self.kind {
+            PartitionKind::Mbr { bootable, .. } => bootable,
+            PartitionKind::Gpt { attributes, .. } => attributes & gpt::GPT_ATTR_LEGACY_BIOS_BOOTABLE != 0,
+        }
+    }
+
+    /// Bölüm türünün kısa, okunabilir adı (CLI çıktısı için).
+    pub fn type_name(&self) -> &'static str {
+        match self.kind {
+            PartitionKind::Mbr { partition_type, .. } => mbr::partition_type_name(partition_type),
+            PartitionKind::Gpt { ref type_guid, .. } => gpt::partition_type_name(type_guid),
+        }
+    }
+}
+
+/// Sabit boyutlu bölüm listesi (heap kullanmaz).
+pub struct PartitionTable {
+    entries: [Option<PartitionInfo>; MAX_PARTITIONS],
+    count: usize,
+}
+
+impl PartitionTable {
+    pub const fn new() -> Self {
+        PartitionTable { entries: [None; MAX_PARTITIONS], count: 0 }
+    }
+
+    pub fn push(&mut self, info: PartitionInfo) -> Result<(), PartitionError> {
+        if self.count >= MAX_PARTITIONS {
+            return Err(PartitionError::TooManyPartitions);
+        }
+        self.entries[self.count] = Some(info);
+        self.count += 1;
+        Ok(())
+    }
+
+    pub fn clear(&mut self) {
+        self.entries = [None; MAX_PARTITIONS];
+        self.count = 0;
+    }
+
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &PartitionInfo> {
+        self.entries[..self.count].iter().filter_map(|e| e.as_ref())
+    }
+
+    /// 1 tabanlı bölüm indeksine göre arar.
+    pub fn get(&self, index: usize) -> Option<&PartitionInfo> {
+        self.iter().find(|p| p.index == index)
+    }
+
+    /// Aktif (bootable) işaretli ilk bölümü döndürür.
+    pub fn first_bootable(&self) -> Option<&PartitionInfo> {
+        self.iter().find(|p| p.is_bootable())
+    }
+}
+
+/// Aygıtın bölüm tablosunu okur.
+/// MBR'de koruyucu (0xEE) girdi varsa tablo GPT olarak, yoksa klasik MBR olarak ayrıştırılır.
+pub fn read_partition_table<D: BlockDevice + ?Sized>(device: &mut D) -> Result<PartitionTable, PartitionError> {
+    let mut table = PartitionTable::new();
+    let protective_mbr = Mbr::read(device, 0)?;
+    if protective_mbr.entries.iter().any(|e| e.partition_type == mbr::MBR_TYPE_GPT_PROTECTIVE) {
+        gpt::read_partitions(device, &mut table)?;
+    } else {
+        mbr::read_partitions(device, &mut table)?;
+    }
+    Ok(table)
+}
+
+/// Verilen indeksteki bölümü kendi `BlockDevice` görünümü olarak açar.
+pub fn open_partition<D: BlockDevice + ?Sized>(device: &mut D, index: usize) -> Result<PartitionView<'_, D>, PartitionError> {
+    let table = read_partition_table(device)?;
+    let info = *table.get(index).ok_or(PartitionError::NotFound)?;
+    Ok(PartitionView::new(device, info))
+}
+
+/// Bir bölümü, LBA 0'ı bölümün ilk bloğu olan ayrı bir blok aygıtı gibi sunar.
+/// Bölüm dışına taşan erişimler `InvalidLba` ile reddedilir.
+pub struct PartitionView<'a, D: BlockDevice + ?Sized> {
+    device: &'a mut D,
+    info: PartitionInfo,
+}
+
+impl<'a, D: BlockDevice + ?Sized> PartitionView<'a, D> {
+    pub fn new(device: &'a mut D, info: PartitionInfo) -> Self {
+        PartitionView { device, info }
+    }
+
+    pub fn info(&self) -> &PartitionInfo {
+        &self.info
+    }
+}
+
+impl<'a, D: BlockDevice + ?Sized> BlockDevice for PartitionView<'a, D> {
+    fn init(&mut self) -> Result<u64, StorageError> {
+        // Alttaki aygıt zaten başlatılmıştır; görünümün boyutu bölüm boyutudur.
+        Ok(self.info.block_count)
+    }
+
+    fn read_block(&mut self, lba: u64, buffer: &mut [u8]) -> Result<(), StorageError> {
+        if lba >= self.info.block_count {
+            return Err(StorageError::InvalidLba);
+        }
+        self.device.read_block(self.info.start_lba + lba, buffer)
+    }
+
+    fn write_block(&mut self, lba: u64, data: &[u8]) -> Result<(), StorageError> {
+        if lba >= self.info.block_count {
+            return Err(StorageError::InvalidLba);
+        }
+        self.device.write_block(self.info.start_lba + lba, data)
+    }
+
+    fn block_count(&self) -> Option<u64> {
+        Some(self.info.block_count)
+    }
+}
+
+// MBR girdisinden ortak bölüm bilgisini oluşturur. `base_lba` mantıksal bölümler için EBR konumudur.
+pub(crate) fn info_from_mbr_entry(index: usize, entry: &MbrEntry, base_lba: u64, logical: bool) -> PartitionInfo {
+    PartitionInfo {
+        index,
+        start_lba: base_lba + entry.lba_start as u64,
+        block_count: entry.sector_count as u64,
+        kind: PartitionKind::Mbr { partition_type: entry.partition_type, bootable: entry.bootable, logical },
+    }
+}
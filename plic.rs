@@ -0,0 +1,96 @@
+#![no_std]
+
+// Platform-Level Interrupt Controller (PLIC): çevre birimi kesmelerini hart'ın makine modu harici
+// kesmesine (MEIP) bağlar. Kaynak önceliği 0 ise kaynak kapalıdır; öncelik eşikten büyük olan
+// bekleyen kesmeler `claim` ile alınır ve işlendikten sonra `complete` ile bildirilir.
+
+#[cfg(not(feature = "hosted"))]
+use crate::mmio::Mmio;
+use crate::mmio::RegisterBus;
+
+pub const PLIC_BASE: usize = 0x0C00_0000; // SiFive S21 PLIC Base Adresi - VERİ SAYFASINDAN BULUN!
+pub const PLIC_PRIORITY: usize = 0x0000; // Kaynak başına 4 bayt
+pub const PLIC_ENABLE: usize = 0x2000; // Hart 0 M-mod bağlamı, 32 kaynak/kelime
+pub const PLIC_THRESHOLD: usize = 0x20_0000; // Hart 0 M-mod bağlamı
+pub const PLIC_CLAIM: usize = 0x20_0004; // Okuma: claim, yazma: complete
+
+// Kesme kaynak numaraları - VERİ SAYFASINDAN BULUN!
+pub const UART0_IRQ: u32 = 3;
+pub const EMMC_IRQ: u32 = 4;
+pub const SD_IRQ: u32 = 5;
+pub const GPIO_IRQ: u32 = 6;
+pub const I2C_IRQ: u32 = 7;
+// Kaynak sayısı (device tree'deki `riscv,ndev`) ve yazmaç penceresinin boyutu - VERİ SAYFASINDAN BULUN!
+pub const PLIC_SOURCE_COUNT: u32 = 31;
+pub const PLIC_REG_SIZE: usize = 0x400_0000;
+
+pub const PLIC_MAX_PRIORITY: u32 = 7;
+
+/// Kaynağı verilen öncelikle etkinleştirir.
+/// # Safety
+/// PLIC yazmaçlarına doğrudan erişir.
+pub unsafe fn enable_with<B: RegisterBus>(bus: &mut B, source: u32, priority: u32) {
+    bus.write32(PLIC_BASE + PLIC_PRIORITY + 4 * source as usize, priority);
+    bus.set_bits(PLIC_BASE + PLIC_ENABLE + 4 * (source / 32) as usize, 1 << (source % 32));
+}
+
+/// Eşiği 0 yapar: önceliği 0'dan büyük tüm kaynaklar kesme üretebilir.
+/// # Safety
+/// PLIC yazmaçlarına doğrudan erişir.
+pub unsafe fn init_with<B: RegisterBus>(bus: &mut B) {
+    bus.write32(PLIC_BASE + PLIC_THRESHOLD, 0);
+}
+
+/// En yüksek öncelikli bekleyen kaynağı alır (yoksa None).
+/// # Safety
+/// PLIC yazmaçlarına doğrudan erişir; alınan kaynak `complete_with` ile bildirilmelidir.
+pub unsafe fn claim_with<B: RegisterBus>(bus: &mut B) -> Option<u32> {
+    match bus.read32(PLIC_BASE + PLIC_CLAIM) {
+        0 => None,
+        source => Some(source),
+    }
+}
+
+/// # Safety
+/// PLIC yazmaçlarına doğrudan erişir.
+pub unsafe fn complete_with<B: RegisterBus>(bus: &mut B, source: u32) {
+    bus.write32(PLIC_BASE + PLIC_CLAIM, source);
+}
+
+/// PLIC'i ve UART0 kaynağını yapılandırır, makine modu harici kesmeleri açar.
+/// # Safety
+/// PLIC yazmaçlarına ve mie/mstatus CSR'larına erişir.
+#[cfg(not(feature = "hosted"))]
+pub unsafe fn init() {
+    init_with(&mut Mmio);
+    enable_with(&mut Mmio, UART0_IRQ, 1);
+    riscv::register::mie::set_mext();
+    riscv::register::mstatus::set_mie();
+}
+
+/// Harici kesmeleri kapatır (kontrol işletim sistemine devredilmeden önce).
+/// # Safety
+/// PLIC yazmaçlarına ve mie/mstatus CSR'larına erişir.
+#[cfg(not(feature = "hosted"))]
+pub unsafe fn shutdown() {
+    riscv::register::mstatus::clear_mie();
+    riscv::register::mie::clear_mext();
+    Mmio.write32(PLIC_BASE + PLIC_PRIORITY + 4 * UART0_IRQ as usize, 0);
+    Mmio.clear_bits(PLIC_BASE + PLIC_ENABLE + 4 * (UART0_IRQ / 32) as usize, 1 << (UART0_IRQ % 32));
+}
+
+/// Makine modu harici kesme işleyicisi (riscv-rt bu adla çağırır).
+/// Bekleyen tüm kaynakları alır, sürücüsüne iletir ve tamamlar.
+/// # Safety
+/// Yalnızca riscv-rt kesme dağıtıcısı tarafından, trap bağlamında çağrılmalıdır.
+#[cfg(not(feature = "hosted"))]
+#[no_mangle]
+pub unsafe extern "C" fn MachineExternal() {
+    while let Some(source) = claim_with(&mut Mmio) {
+        // Beklenmeyen kaynaklar yalnızca tamamlanıp yok sayılır
+        if source == UART0_IRQ {
+            crate::uart::UART0_GLOBAL.handle_interrupt();
+        }
+        complete_with(&mut Mmio, source);
+    }
+}
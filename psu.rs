@@ -1,91 +1,182 @@
-#![no_std]
-
- use crate::firmware_common::{self, Error};
-
-#[derive(Debug)]
-pub enum PsuError {
-    PowerGoodSignalError,
-    VoltageMeasurementError,
-    ControlPinError,
-    NotInitialized,
-    GpioConfigurationError, // GPIO başlatma hatası
-}
-
-pub const PSU_WATTAGE_WATTS: u16 = 200;
-pub const PSU_EFFICIENCY_CERTIFICATION: &str = "80 Plus Gold";
-pub const PSU_INPUT_CONNECTOR: &str = "IEC 60320 C13";
-
-pub struct PsuMonitor {
-    is_initialized: bool,
-    // Power Good ve PS_ON# sinyallerine bağlı GPIO pinlerine donanım referansları veya HAL nesneleri
-    // power_good_pin: GPIO Input Pin Nesnesi,
-    // ps_on_pin: GPIO Output Pin Nesnesi,
-    // ... voltaj ölçümü için ADC kanal referansı
-}
-
-impl PsuMonitor {
-    pub const fn new(/* GPIO/ADC donanım referansları */) -> Self {
-        PsuMonitor { is_initialized: false }
-    }
-
-    /// PSU izleme donanımını başlatır.
-    /// # Safety
-    /// Donanım pinlerini ve çevre birimlerini yapılandırır, "unsafe"dir.
-    pub unsafe fn init(&mut self) -> Result<(), PsuError> {
-        // --- GERÇEK BAŞLATMA KODU BURAYA GELECEK ---
-        // SiFive S21'in GPIO ve ADC çevre birimlerini yapılandırın.
-
-        // 1. Power Good pinini Giriş (Input) olarak yapılandırma.
-        // Örnek: GPIO kontrolcüsü registerlarına yazarak pini giriş moduna ayarlayın, pull-up/down direncini ayarlayın.
-         const GPIO_BASE: usize = 0xBBBB_0000; // SiFive S21 GPIO Base Adresi - VERİ SAYFASINDAN BULUN!
-         const GPIO_INPUT_EN: usize = 0x04; // Giriş Etkinleştirme Register Ofseti - VERİ SAYFASINDAN BULUN!
-         const PSU_PG_GPIO_PIN_IDX: u32 = 5; // Power Good sinyalinin bağlı olduğu GPIO pin numarası - ŞEMADAN BULUN!
-        //
-         let input_en_ptr = (GPIO_BASE + GPIO_INPUT_EN) as *mut u32;
-         let mut input_en_val = input_en_ptr.read_volatile();
-         input_en_val |= (1 << PSU_PG_GPIO_PIN_IDX); // İlgili pini giriş olarak etkinleştir
-         input_en_ptr.write_volatile(input_en_val);
-
-
-        // 2. PS_ON# pinini Çıkış (Output) olarak yapılandırma ve başlangıç durumunu ayarlama (genellikle yüksek -> PSU kapalı).
-         const GPIO_OUTPUT_EN: usize = 0x08; // Çıkış Etkinleştirme Register Ofseti - VERİ SAYFASINDAN BULUN!
-         const GPIO_OUTPUT_VAL: usize = 0x0C; // Çıkış Değeri Register Ofseti - VERİ SAYFASINDAN BULUN!
-         const PSU_PSON_GPIO_PIN_IDX: u32 = 6; // PS_ON# sinyalinin bağlı olduğu GPIO pin numarası - ŞEMADAN BULUN!
-        //
-         let output_en_ptr = (GPIO_BASE + GPIO_OUTPUT_EN) as *mut u32;
-         let output_val_ptr = (GPIO_BASE + GPIO_OUTPUT_VAL) as *mut u32;
-        //
-         let mut output_en_val = output_en_ptr.read_volatile();
-         output_en_val |= (1 << PSU_PSON_GPIO_PIN_IDX); // İlgili pini çıkış olarak etkinleştir
-         output_en_ptr.write_volatile(output_en_val);
-        //
-        // // Başlangıçta PS_ON# pinini yüksek yap (PSU kapalı)
-         let mut output_val = output_val_ptr.read_volatile();
-         output_val |= (1 << PSU_PSON_GPIO_PIN_IDX);
-         output_val_ptr.write_volatile(output_val);
-
-
-        // 3. Voltaj ölçümü gerekiyorsa ADC kanalını yapılandırma.
-         const ADC_BASE: usize = 0xCCCC_0000; // SiFive S21 ADC Base Adresi - VERİ SAYFASINDAN BULUN!
-        // ... ADC register ayarlamaları ...
-
-
-        self.is_initialized = true;
-        Ok(())
-        // Hata olursa (örneğin, GPIO konfigürasyon hatası)
-         Err(PsuError::GpioConfigurationError)
-        // --- GERÇEK BAŞLATMA KODU BURAYA KADAR ---
-    }
-
-    // is_power_good, turn_on, turn_off gibi fonksiyonların içleri de benzer şekilde
-    // gerçek GPIO veya ADC okuma/yazma kodları ile doldurulmalıdır.
-}
-
-pub static mut PSU_MONITOR_GLOBAL: Option<PsuMonitor> = None;
-
-// Örnek voltaj rayları enum'u
-
-#[derive(Debug, Copy, Clone)]
-pub enum VoltageRail {
-    Volt3_3, Volt5, Volt12,
-}
+#![no_std]
+
+#[cfg(not(feature = "hosted"))]
+use crate::mmio::Field;
+use crate::mmio::{Mmio, RegisterBus};
+
+#[derive(Debug)]
+pub enum PsuError {
+    PowerGoodSignalError,
+    VoltageMeasurementError,
+    ControlPinError,
+    NotInitialized,
+    GpioConfigurationError, // GPIO başlatma hatası
+}
+
+pub const PSU_WATTAGE_WATTS: u16 = 200;
+pub const PSU_EFFICIENCY_CERTIFICATION: &str = "80 Plus Gold";
+pub const PSU_INPUT_CONNECTOR: &str = "IEC 60320 C13";
+
+// GPIO yazmaçları ve pin atamaları - VERİ SAYFASINDAN / ŞEMADAN BULUN!
+pub const GPIO_BASE: usize = 0xBBBB_0000; // SiFive S21 GPIO Base Adresi - VERİ SAYFASINDAN BULUN!
+pub const GPIO_INPUT_VAL: usize = 0x00; // Giriş Değeri Register Ofseti - VERİ SAYFASINDAN BULUN!
+pub const GPIO_INPUT_EN: usize = 0x04; // Giriş Etkinleştirme Register Ofseti - VERİ SAYFASINDAN BULUN!
+pub const GPIO_OUTPUT_EN: usize = 0x08; // Çıkış Etkinleştirme Register Ofseti - VERİ SAYFASINDAN BULUN!
+pub const GPIO_OUTPUT_VAL: usize = 0x0C; // Çıkış Değeri Register Ofseti - VERİ SAYFASINDAN BULUN!
+pub const PSU_PG_GPIO_PIN_IDX: u32 = 5; // Power Good sinyalinin bağlı olduğu GPIO pin numarası - ŞEMADAN BULUN!
+pub const PSU_PSON_GPIO_PIN_IDX: u32 = 6; // PS_ON# sinyalinin bağlı olduğu GPIO pin numarası - ŞEMADAN BULUN!
+// PS_ON# aktif edildikten sonra Power Good sinyali için beklenecek en uzun süre (ATX: en fazla 500 ms).
+pub const PSU_POWER_GOOD_TIMEOUT_MS: u64 = 500;
+
+pub struct PsuMonitor<B: RegisterBus = Mmio> {
+    is_initialized: bool,
+    // Power Good ve PS_ON# sinyallerine bağlı GPIO pinlerine yazmaç arka ucu üzerinden erişilir.
+    bus: B,
+    // ... voltaj ölçümü için ADC kanal referansı
+}
+
+impl PsuMonitor<Mmio> {
+    pub const fn new(/* GPIO/ADC donanım referansları */) -> Self {
+        PsuMonitor { is_initialized: false, bus: Mmio }
+    }
+}
+
+impl<B: RegisterBus> PsuMonitor<B> {
+    /// Belirli bir yazmaç arka ucu ile PSU izleyici oluşturur (örn. testler için `RecordingBus`).
+    pub const fn with_bus(bus: B) -> Self {
+        PsuMonitor { is_initialized: false, bus }
+    }
+
+    /// Yazmaç arka ucuna erişim (testlerde kaydedilen erişimleri incelemek için).
+    pub fn bus(&self) -> &B {
+        &self.bus
+    }
+
+    /// Host simülasyonu: GPIO yerine simüle edilmiş PSU durumu kullanılır. PSU kapalı başlar.
+    /// # Safety
+    /// Simülasyon dışında GPIO pinleri yapılandırılacağı için "unsafe"dir.
+    #[cfg(feature = "hosted")]
+    pub unsafe fn init(&mut self) -> Result<(), PsuError> {
+        crate::hosted::psu_set_on(false);
+        self.is_initialized = true;
+        Ok(())
+    }
+
+    /// PSU izleme donanımını başlatır.
+    /// # Safety
+    /// Donanım pinlerini ve çevre birimlerini yapılandırır, "unsafe"dir.
+    #[cfg(not(feature = "hosted"))]
+    pub unsafe fn init(&mut self) -> Result<(), PsuError> {
+        // --- GERÇEK BAŞLATMA KODU BURAYA GELECEK ---
+        // SiFive S21'in GPIO ve ADC çevre birimlerini yapılandırın.
+
+        // 1. Power Good pinini Giriş (Input) olarak yapılandırma.
+        // Örnek: GPIO kontrolcüsü registerlarına yazarak pini giriş moduna ayarlayın, pull-up/down direncini ayarlayın.
+         self.bus.set_bits(GPIO_BASE + GPIO_INPUT_EN, 1 << PSU_PG_GPIO_PIN_IDX); // İlgili pini giriş olarak etkinleştir
+
+
+        // 2. PS_ON# pinini Çıkış (Output) olarak yapılandırma ve başlangıç durumunu ayarlama (genellikle yüksek -> PSU kapalı).
+         self.bus.set_bits(GPIO_BASE + GPIO_OUTPUT_EN, 1 << PSU_PSON_GPIO_PIN_IDX); // İlgili pini çıkış olarak etkinleştir
+        //
+        // // Başlangıçta PS_ON# pinini yüksek yap (PSU kapalı)
+         self.bus.set_bits(GPIO_BASE + GPIO_OUTPUT_VAL, 1 << PSU_PSON_GPIO_PIN_IDX);
+
+
+        // 3. Voltaj ölçümü gerekiyorsa ADC kanalını yapılandırma.
+        // const ADC_BASE: usize = 0xCCCC_0000; // SiFive S21 ADC Base Adresi - VERİ SAYFASINDAN BULUN!
+        // ... ADC register ayarlamaları ...
+
+
+        // Hata olursa (örneğin, GPIO konfigürasyon hatası):
+        // return Err(PsuError::GpioConfigurationError);
+
+        self.is_initialized = true;
+        Ok(())
+        // --- GERÇEK BAŞLATMA KODU BURAYA KADAR ---
+    }
+
+    /// Power Good sinyalinin durumunu döndürür.
+    /// # Safety
+    /// GPIO yazmacı okunduğu için "unsafe"dir.
+    #[cfg(not(feature = "hosted"))]
+    pub unsafe fn is_power_good(&mut self) -> Result<bool, PsuError> {
+        if !self.is_initialized { return Err(PsuError::NotInitialized); }
+        Ok(self.bus.read_field(GPIO_BASE + GPIO_INPUT_VAL, Field::bit(PSU_PG_GPIO_PIN_IDX)) != 0)
+    }
+
+    /// PS_ON# sinyalini aktif (düşük) yaparak PSU'yu açar.
+    /// # Safety
+    /// GPIO yazmacına yazıldığı için "unsafe"dir.
+    #[cfg(not(feature = "hosted"))]
+    pub unsafe fn turn_on(&mut self) -> Result<(), PsuError> {
+        if !self.is_initialized { return Err(PsuError::NotInitialized); }
+        self.bus.clear_bits(GPIO_BASE + GPIO_OUTPUT_VAL, 1 << PSU_PSON_GPIO_PIN_IDX);
+        Ok(())
+    }
+
+    /// PS_ON# sinyalini pasif (yüksek) yaparak PSU'yu kapatır.
+    /// # Safety
+    /// GPIO yazmacına yazıldığı için "unsafe"dir.
+    #[cfg(not(feature = "hosted"))]
+    pub unsafe fn turn_off(&mut self) -> Result<(), PsuError> {
+        if !self.is_initialized { return Err(PsuError::NotInitialized); }
+        self.bus.set_bits(GPIO_BASE + GPIO_OUTPUT_VAL, 1 << PSU_PSON_GPIO_PIN_IDX);
+        Ok(())
+    }
+
+    /// Power Good sinyalinin durumunu döndürür.
+    /// # Safety
+    /// Simülasyon dışında GPIO yazmacı okunacağı için "unsafe"dir.
+    #[cfg(feature = "hosted")]
+    pub unsafe fn is_power_good(&mut self) -> Result<bool, PsuError> {
+        if !self.is_initialized { return Err(PsuError::NotInitialized); }
+        Ok(crate::hosted::psu_power_good())
+    }
+
+    /// PS_ON# sinyalini aktif yaparak PSU'yu açar.
+    /// # Safety
+    /// Simülasyon dışında GPIO yazmacına yazılacağı için "unsafe"dir.
+    #[cfg(feature = "hosted")]
+    pub unsafe fn turn_on(&mut self) -> Result<(), PsuError> {
+        if !self.is_initialized { return Err(PsuError::NotInitialized); }
+        crate::hosted::psu_set_on(true);
+        Ok(())
+    }
+
+    /// PS_ON# sinyalini pasif yaparak PSU'yu kapatır.
+    /// # Safety
+    /// Simülasyon dışında GPIO yazmacına yazılacağı için "unsafe"dir.
+    #[cfg(feature = "hosted")]
+    pub unsafe fn turn_off(&mut self) -> Result<(), PsuError> {
+        if !self.is_initialized { return Err(PsuError::NotInitialized); }
+        crate::hosted::psu_set_on(false);
+        Ok(())
+    }
+}
+
+impl<B: RegisterBus> PsuMonitor<B> {
+    /// Power Good sinyali gelene kadar en fazla `timeout_ms` bekler.
+    /// # Safety
+    /// GPIO yazmacı okunduğu için "unsafe"dir.
+    pub unsafe fn wait_for_power_good(&mut self, timeout_ms: u64) -> Result<(), PsuError> {
+        let deadline = crate::timer::Deadline::after_ms(timeout_ms);
+        loop {
+            if self.is_power_good()? {
+                return Ok(());
+            }
+            if deadline.expired() {
+                return Err(PsuError::PowerGoodSignalError);
+            }
+            core::hint::spin_loop();
+        }
+    }
+}
+
+pub static mut PSU_MONITOR_GLOBAL: Option<PsuMonitor> = None;
+
+// Örnek voltaj rayları enum'u
+
+#[derive(Debug, Copy, Clone)]
+pub enum VoltageRail {
+    Volt3_3, Volt5, Volt12,
+}
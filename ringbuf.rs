@@ -0,0 +1,127 @@
+#![no_std]
+
+// Tek üretici / tek tüketici (SPSC) kilitsiz bayt halka tamponu.
+// Üretici sadece `head`'i, tüketici sadece `tail`'i yazar; indeksler serbest sayar ve kapasiteye
+// (2'nin kuvveti) göre maskelenir. Kesme işleyicisi ile ana döngü arasında kilit veya kesme
+// kapatmadan veri aktarmak için kullanılır (örn. UART RX: üretici kesme, tüketici CLI).
+// Aynı tarafı (örn. iki tüketici) aynı anda çalıştırmak güvenli DEĞİLDİR.
+
+use core::cell::UnsafeCell;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+pub struct RingBuffer<const N: usize> {
+    buffer: UnsafeCell<[u8; N]>,
+    head: AtomicUsize, // Bir sonraki yazılacak konum (üretici)
+    tail: AtomicUsize, // Bir sonraki okunacak konum (tüketici)
+}
+
+// Erişim kuralları yukarıdaki SPSC sözleşmesiyle sağlanır.
+unsafe impl<const N: usize> Sync for RingBuffer<N> {}
+
+impl<const N: usize> RingBuffer<N> {
+    const MASK: usize = {
+        assert!(N.is_power_of_two(), "ring buffer capacity must be a power of two");
+        N - 1
+    };
+
+    pub const fn new() -> Self {
+        RingBuffer { buffer: UnsafeCell::new([0; N]), head: AtomicUsize::new(0), tail: AtomicUsize::new(0) }
+    }
+
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    pub fn len(&self) -> usize {
+        self.head.load(Ordering::Acquire).wrapping_sub(self.tail.load(Ordering::Acquire))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len() == N
+    }
+
+    /// Bayt ekler (sadece üretici). Tampon doluysa false döner ve bayt eklenmez.
+    pub fn push(&self, byte: u8) -> bool {
+        let head = self.head.load(Ordering::Relaxed);
+        if head.wrapping_sub(self.tail.load(Ordering::Acquire)) == N {
+            return false;
+        }
+        unsafe { (*self.buffer.get())[head & Self::MASK] = byte };
+        self.head.store(head.wrapping_add(1), Ordering::Release);
+        true
+    }
+
+    /// En eski baytı alır (sadece tüketici).
+    pub fn pop(&self) -> Option<u8> {
+        let tail = self.tail.load(Ordering::Relaxed);
+        if tail == self.head.load(Ordering::Acquire) {
+            return None;
+        }
+        let byte = unsafe { (*self.buffer.get())[tail & Self::MASK] };
+        self.tail.store(tail.wrapping_add(1), Ordering::Release);
+        Some(byte)
+    }
+
+    /// Bekleyen tüm baytları atar (sadece tüketici).
+    pub fn clear(&self) {
+        self.tail.store(self.head.load(Ordering::Acquire), Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bytes_come_out_in_order_across_the_wrap() {
+        let ring = RingBuffer::<4>::new();
+        // İndeksleri kapasitenin ötesine taşımak için birkaç tur doldurup boşalt.
+        for round in 0..3u8 {
+            for i in 0..3 {
+                assert!(ring.push(round * 10 + i));
+            }
+            assert_eq!(ring.len(), 3);
+            for i in 0..3 {
+                assert_eq!(ring.pop(), Some(round * 10 + i));
+            }
+        }
+        assert!(ring.is_empty());
+    }
+
+    #[test]
+    fn push_to_full_buffer_is_refused() {
+        let ring = RingBuffer::<4>::new();
+        for i in 0..4 {
+            assert!(ring.push(i));
+        }
+        assert!(ring.is_full());
+        assert!(!ring.push(9));
+        assert_eq!(ring.pop(), Some(0)); // Reddedilen bayt eskileri ezmez
+        assert!(ring.push(9));
+    }
+
+    #[test]
+    fn pop_from_empty_buffer_returns_none() {
+        let ring = RingBuffer::<4>::new();
+        assert_eq!(ring.pop(), None);
+        ring.push(1);
+        ring.pop();
+        assert_eq!(ring.pop(), None);
+    }
+
+    #[test]
+    fn clear_discards_pending_bytes() {
+        let ring = RingBuffer::<4>::new();
+        ring.push(1);
+        ring.push(2);
+        ring.clear();
+        assert!(ring.is_empty());
+        assert_eq!(ring.pop(), None);
+        assert!(ring.push(3));
+        assert_eq!(ring.pop(), Some(3));
+    }
+}
@@ -0,0 +1,309 @@
+#![no_std]
+
+// Komut betikleri: FAT bölümündeki bir metin dosyasındaki CLI komutlarını sırayla çalıştırır.
+// Her satır bir CLI satırıdır (`cli::run_line`: tırnak, $değişken ve `;` geçerlidir); boş satırlar ve
+// `#` ile başlayan satırlar atlanır. Koşullu bloklar:
+//
+//   if <komut satırı>
+//       ...            komut başarılı olduysa
+//   else
+//       ...            başarısız olduysa (isteğe bağlı)
+//   end
+//
+// Bloklar iç içe olabilir. Başarısız bir komut betiği durdurmaz; durumu `if` ile sınanır.
+// Blok yapısı çalıştırmadan önce denetlenir, hatalı betiğin hiçbir satırı çalışmaz.
+//
+// Açılışta `bootscript` değişkenindeki betik, otomatik boot geri sayımından sonra ve boot sırasından
+// önce çalıştırılır (`run_boot_script`). Betikler ölçüm kaydına `config` aşaması olarak eklenir.
+
+use crate::boot::{BootError, BootPath};
+use crate::fat::{FatError, FatVolume};
+use crate::measured::{self, BootStage};
+use crate::secureboot::{self, SecureBootPolicy};
+use crate::uart::Uart0;
+
+pub const SCRIPT_MAX_BYTES: usize = 4096;
+// İç içe `source` derinliği (her seviye kendi arabelleğini kullanır).
+pub const SCRIPT_MAX_DEPTH: usize = 3;
+// İç içe `if` blok sınırı.
+pub const SCRIPT_MAX_NESTING: usize = 8;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SyntaxError {
+    MissingCondition, // `if` ardından komut yok
+    ElseWithoutIf,
+    DuplicateElse,
+    EndWithoutIf,
+    MissingEnd,        // Dosya sonunda kapatılmamış `if`
+    NestingTooDeep,    // SCRIPT_MAX_NESTING aşıldı
+    UnexpectedArguments, // `else`/`end` ardından metin
+}
+
+#[derive(Debug)]
+pub enum ScriptError {
+    Boot(BootError),  // Yol geçersiz, aygıt/bölüm/dosya bulunamadı veya okunamadı
+    TooLarge(u32),    // Dosya boyutu SCRIPT_MAX_BYTES'ı aşıyor
+    NotText,          // Geçerli UTF-8 değil
+    TooDeep,          // İç içe `source` SCRIPT_MAX_DEPTH'i aştı
+    Syntax { line: usize, error: SyntaxError }, // `line` 1 tabanlı
+}
+
+impl From<BootError> for ScriptError {
+    fn from(err: BootError) -> Self {
+        ScriptError::Boot(err)
+    }
+}
+
+// Her `source` seviyesinin betik arabelleği ve şu anki seviye.
+static mut SCRIPT_BUFFERS: [[u8; SCRIPT_MAX_BYTES]; SCRIPT_MAX_DEPTH] = [[0; SCRIPT_MAX_BYTES]; SCRIPT_MAX_DEPTH];
+static mut SCRIPT_DEPTH: usize = 0;
+
+/// `bootscript` değeri için doğrulama: boş (kapalı) veya `<cihaz>:<bölüm>/<dosya>`.
+pub fn is_valid_script_path(value: &str) -> bool {
+    value.is_empty() || BootPath::parse(value).is_ok_and(|path| path.is_file())
+}
+
+enum Line<'a> {
+    Empty,
+    If(&'a str),
+    Else,
+    End,
+    Command(&'a str),
+}
+
+fn classify(line: &str) -> Result<Line<'_>, SyntaxError> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return Ok(Line::Empty);
+    }
+    let (word, rest) = line.split_once([' ', '\t']).unwrap_or((line, ""));
+    let rest = rest.trim();
+    match word {
+        "if" if rest.is_empty() => Err(SyntaxError::MissingCondition),
+        "if" => Ok(Line::If(rest)),
+        "else" | "end" if !rest.is_empty() => Err(SyntaxError::UnexpectedArguments),
+        "else" => Ok(Line::Else),
+        "end" => Ok(Line::End),
+        _ => Ok(Line::Command(line)),
+    }
+}
+
+// Blok yapısını denetler.
+fn check(text: &str) -> Result<(), ScriptError> {
+    let mut has_else = [false; SCRIPT_MAX_NESTING];
+    let mut depth = 0;
+    let mut last_line = 0;
+    for (index, line) in text.lines().enumerate() {
+        last_line = index + 1;
+        let syntax = |error| ScriptError::Syntax { line: index + 1, error };
+        match classify(line).map_err(syntax)? {
+            Line::If(_) => {
+                if depth == SCRIPT_MAX_NESTING {
+                    return Err(syntax(SyntaxError::NestingTooDeep));
+                }
+                has_else[depth] = false;
+                depth += 1;
+            }
+            Line::Else => {
+                if depth == 0 {
+                    return Err(syntax(SyntaxError::ElseWithoutIf));
+                }
+                if has_else[depth - 1] {
+                    return Err(syntax(SyntaxError::DuplicateElse));
+                }
+                has_else[depth - 1] = true;
+            }
+            Line::End => {
+                if depth == 0 {
+                    return Err(syntax(SyntaxError::EndWithoutIf));
+                }
+                depth -= 1;
+            }
+            Line::Empty | Line::Command(_) => {}
+        }
+    }
+    if depth > 0 {
+        return Err(ScriptError::Syntax { line: last_line, error: SyntaxError::MissingEnd });
+    }
+    Ok(())
+}
+
+#[derive(Copy, Clone)]
+struct Block {
+    parent_active: bool, // Bloğu içeren satırlar çalışıyor mu
+    condition: bool,
+    in_else: bool,
+}
+
+impl Block {
+    fn active(&self) -> bool {
+        self.parent_active && self.condition != self.in_else
+    }
+}
+
+// Denetlenmiş betiği çalıştırır.
+unsafe fn execute(uart: &mut Uart0, text: &str) {
+    let mut blocks = [Block { parent_active: true, condition: true, in_else: false }; SCRIPT_MAX_NESTING];
+    let mut depth = 0;
+    for line in text.lines() {
+        let active = depth == 0 || blocks[depth - 1].active();
+        match classify(line) {
+            Ok(Line::If(condition)) => {
+                // Etkin olmayan dalda koşul komutu çalıştırılmaz.
+                let condition = active && crate::cli::run_line(uart, condition).is_ok();
+                blocks[depth] = Block { parent_active: active, condition, in_else: false };
+                depth += 1;
+            }
+            Ok(Line::Else) => blocks[depth - 1].in_else = true,
+            Ok(Line::End) => depth -= 1,
+            Ok(Line::Command(command)) if active => {
+                let _ = crate::cli::run_line(uart, command);
+            }
+            _ => {}
+        }
+    }
+}
+
+// Betik dosyasını arabelleğe okur ve boyutunu döndürür.
+unsafe fn read_file(path: &BootPath, buffer: &mut [u8]) -> Result<usize, ScriptError> {
+    let device = crate::storage::device_by_name(path.device).map_err(BootError::from)?;
+    let view = crate::partition::open_partition(device, path.partition).map_err(BootError::from)?;
+    let mut volume = FatVolume::mount(view).map_err(BootError::from)?;
+    let mut file = volume.open(path.path).map_err(BootError::from)?;
+    let size = file.size();
+    if size as usize > buffer.len() {
+        return Err(ScriptError::TooLarge(size));
+    }
+    let mut done = 0;
+    while done < size as usize {
+        let n = volume.read(&mut file, &mut buffer[done..size as usize]).map_err(BootError::from)?;
+        if n == 0 {
+            break;
+        }
+        done += n;
+    }
+    Ok(done)
+}
+
+/// `<cihaz>:<bölüm>/<dosya>` betiğini okur, denetler ve çalıştırır.
+/// # Safety
+/// Global depolama aygıtlarına ve UART'a erişir; betikteki komutlar global durumu değiştirebilir.
+pub unsafe fn run(uart: &mut Uart0, spec: &str) -> Result<(), ScriptError> {
+    let path = BootPath::parse(spec)?;
+    if !path.is_file() {
+        return Err(BootError::InvalidBootPath.into());
+    }
+    if SCRIPT_DEPTH == SCRIPT_MAX_DEPTH {
+        return Err(ScriptError::TooDeep);
+    }
+    let buffer = &mut SCRIPT_BUFFERS[SCRIPT_DEPTH];
+    let len = read_file(&path, buffer)?;
+    let text = core::str::from_utf8(&buffer[..len]).map_err(|_| ScriptError::NotText)?;
+    measured::measure(BootStage::Config, 0, 0, &[text.as_bytes()], format_args!("script {}", spec));
+    check(text)?;
+
+    SCRIPT_DEPTH += 1;
+    execute(uart, text);
+    SCRIPT_DEPTH -= 1;
+    Ok(())
+}
+
+/// Açılış betiği (`bootscript`). Dosya yoksa sessizce atlanır. Güvenli boot politikası `enforce` iken
+/// imzasız bir betiğin politikayı değiştirmesini önlemek için çalıştırılmaz.
+/// # Safety
+/// `run` ile aynı.
+pub unsafe fn run_boot_script(uart: &mut Uart0) {
+    let spec = crate::env::get("bootscript").unwrap_or("");
+    if spec.is_empty() {
+        return;
+    }
+    if secureboot::get_policy() == SecureBootPolicy::Enforce {
+        log!("Boot script {} skipped: secure boot policy is 'enforce'.", spec);
+        return;
+    }
+    log!("Running boot script {}...", spec);
+    match run(uart, spec) {
+        Ok(()) => log!("Boot script {} finished.", spec),
+        Err(ScriptError::Boot(BootError::Filesystem(FatError::NotFound))) => log!("No boot script at {}.", spec),
+        Err(e) => log!("Boot script {} failed: {:?}", spec, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::uart::{UART0_BASE_ADDRESS, UART_CLOCK_HZ};
+
+    fn syntax_error(text: &str) -> (usize, SyntaxError) {
+        match check(text) {
+            Err(ScriptError::Syntax { line, error }) => (line, error),
+            other => panic!("expected a syntax error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn valid_blocks_pass_the_check() {
+        assert!(check("").is_ok());
+        assert!(check("# if\n\necho a\nif echo b\n  if echo c\n  else\n  end\nelse\n  echo d\nend\n").is_ok());
+        let nested = "if echo\n".repeat(SCRIPT_MAX_NESTING) + &"end\n".repeat(SCRIPT_MAX_NESTING);
+        assert!(check(&nested).is_ok());
+    }
+
+    #[test]
+    fn syntax_errors_name_their_line() {
+        assert_eq!(syntax_error("echo a\nif  \nend"), (2, SyntaxError::MissingCondition));
+        assert_eq!(syntax_error("echo a\nelse"), (2, SyntaxError::ElseWithoutIf));
+        assert_eq!(syntax_error("if echo\nelse\nelse\nend"), (3, SyntaxError::DuplicateElse));
+        assert_eq!(syntax_error("if echo\nend\nend"), (3, SyntaxError::EndWithoutIf));
+        assert_eq!(syntax_error("if echo\n  echo a\n\n"), (3, SyntaxError::MissingEnd));
+        let nested = "if echo\n".repeat(SCRIPT_MAX_NESTING + 1);
+        assert_eq!(syntax_error(&nested), (SCRIPT_MAX_NESTING + 1, SyntaxError::NestingTooDeep));
+        assert_eq!(syntax_error("if echo\nelse if echo\nend"), (2, SyntaxError::UnexpectedArguments));
+        assert_eq!(syntax_error("if echo\nend if"), (2, SyntaxError::UnexpectedArguments));
+        // `else` iç içe bloklarda kendi bloğuna aittir.
+        assert!(check("if echo\n  if echo\n  else\n  end\nelse\nend").is_ok());
+    }
+
+    // Dallar, global NVRAM'e yazan `setenv` ile gözlenir; diğer testlerle çakışmasın diye adlar
+    // `script.` ile başlar. `getenv bootdelay` (varsayılanı var) başarılı, bilinmeyen komut başarısızdır.
+    #[test]
+    fn then_and_else_branches_run_according_to_the_condition() {
+        let text = "\
+if getenv bootdelay
+    setenv script.then yes
+else
+    setenv script.else yes
+end
+no-such-command
+if no-such-command
+    setenv script.then2 yes
+else
+    setenv script.else2 yes
+    if getenv bootdelay
+        setenv script.nested yes
+    end
+end
+if no-such-command
+    # Etkin olmayan daldaki koşul çalıştırılmaz, iç bloğun iki dalı da atlanır.
+    if setenv script.cond yes
+        setenv script.inner yes
+    else
+        setenv script.inner.else yes
+    end
+end
+setenv script.after yes
+";
+        let names = ["script.then", "script.else", "script.then2", "script.else2", "script.nested", "script.cond", "script.inner", "script.inner.else", "script.after"];
+        assert!(check(text).is_ok());
+        let mut uart = Uart0::new(UART0_BASE_ADDRESS, UART_CLOCK_HZ);
+        let set: Vec<&str> = unsafe {
+            execute(&mut uart, text);
+            let set = names.iter().copied().filter(|name| crate::env::get(name).is_some()).collect();
+            for name in names {
+                crate::env::unset(name);
+            }
+            set
+        };
+        assert_eq!(set, ["script.then", "script.else2", "script.nested", "script.after"]);
+    }
+}
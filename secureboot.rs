@@ -0,0 +1,376 @@
+#![no_std]
+
+// Güvenli boot: imajların firmware'e gömülü açık anahtarlarla Ed25519 imza doğrulaması.
+//
+// İmzalanan mesaj, aşağıdaki baytların SHA-256 özetidir (32 bayt):
+//   başlığın ilk 0x48 baytı (header_crc32 alanı sıfırlanmış haliyle; key_id dahil, imza hariç)
+//   + yükün (payload) tamamı
+// Başlık CRC'si imzayı da kapsadığı için imzalama sırası: özet -> imza -> başlık CRC'si.
+//
+// Politika CLI'dan (`secureboot`) değiştirilebilir:
+//   enforce -> imzasız veya geçersiz imzalı imajlar reddedilir (varsayılan)
+//   warn    -> doğrulama yapılır, başarısızlık sadece uyarı olarak loglanır
+//   off     -> doğrulama yapılmaz
+
+use ed25519_dalek::{Signature, VerifyingKey};
+use sha2::{Digest, Sha256};
+
+use crate::image::{ImageHeader, ImageSignature, IMAGE_SIGNATURE_OFFSET};
+
+/// Firmware'e gömülü güvenilir açık anahtar.
+#[derive(Debug, Copy, Clone)]
+pub struct TrustedKey {
+    pub id: u32,
+    pub name: &'static str,
+    pub public_key: [u8; 32],
+}
+
+impl TrustedKey {
+    /// Tamamı sıfır olan (henüz üretim anahtarı yazılmamış) girdiler hiçbir imzayı doğrulamaz.
+    pub fn is_provisioned(&self) -> bool {
+        self.public_key != [0; 32]
+    }
+}
+
+// Güvenilir anahtarlar. Üretim anahtarlarının açık kısmı buraya yazılmalı - İMZALAMA SUNUCUSUNDAN ALIN!
+pub static TRUSTED_KEYS: [TrustedKey; 1] = [
+    TrustedKey { id: 0, name: "packetbox-release", public_key: [0; 32] },
+];
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SecureBootPolicy {
+    Enforce,
+    Warn,
+    Off,
+}
+
+impl SecureBootPolicy {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "enforce" => Some(SecureBootPolicy::Enforce),
+            "warn" => Some(SecureBootPolicy::Warn),
+            "off" => Some(SecureBootPolicy::Off),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SecureBootPolicy::Enforce => "enforce",
+            SecureBootPolicy::Warn => "warn",
+            SecureBootPolicy::Off => "off",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum SecureBootError {
+    Unsigned,          // İmaj IMAGE_FLAG_SIGNED taşımıyor
+    UnknownKey(u32),   // key_id güvenilir anahtarlar arasında yok
+    KeyNotProvisioned(u32), // key_id'nin yeri var ama bu derlemeye üretim anahtarı yazılmamış (tamamı sıfır)
+    InvalidKey(u32),   // Gömülü anahtar geçerli bir Ed25519 noktası değil
+    BadSignature(u32), // İmza anahtarla doğrulanamadı (imaj değiştirilmiş veya farklı anahtarla imzalanmış)
+}
+
+impl SecureBootError {
+    /// Reddetme mesajları için açıklama.
+    pub fn description(&self) -> &'static str {
+        match self {
+            SecureBootError::Unsigned => "image is not signed",
+            SecureBootError::UnknownKey(_) => "image is signed with a key that is not trusted",
+            SecureBootError::KeyNotProvisioned(_) => "trusted key is not provisioned in this firmware build",
+            SecureBootError::InvalidKey(_) => "trusted key is malformed",
+            SecureBootError::BadSignature(_) => "signature does not match the image",
+        }
+    }
+}
+
+static mut SECURE_BOOT_POLICY: SecureBootPolicy = SecureBootPolicy::Enforce;
+
+/// # Safety
+/// Global politikayı değiştirir.
+pub unsafe fn set_policy(policy: SecureBootPolicy) {
+    SECURE_BOOT_POLICY = policy;
+}
+
+/// # Safety
+/// Global politikayı okur.
+pub unsafe fn get_policy() -> SecureBootPolicy {
+    SECURE_BOOT_POLICY
+}
+
+/// İmzalanan özet: CRC alanı sıfırlanmış başlık öneki + yük.
+pub fn signed_digest(header_bytes: &[u8], payload: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(&header_bytes[0..8]);
+    hasher.update([0u8; 4]);
+    hasher.update(&header_bytes[12..IMAGE_SIGNATURE_OFFSET]);
+    hasher.update(payload);
+    hasher.finalize().into()
+}
+
+/// Verilen anahtar listesinde kimliğe göre arar. Yeri olup doldurulmamış anahtar `KeyNotProvisioned` döner.
+pub fn find_key_in(keys: &[TrustedKey], id: u32) -> Result<TrustedKey, SecureBootError> {
+    match keys.iter().find(|k| k.id == id) {
+        Some(key) if key.is_provisioned() => Ok(*key),
+        Some(_) => Err(SecureBootError::KeyNotProvisioned(id)),
+        None => Err(SecureBootError::UnknownKey(id)),
+    }
+}
+
+/// Kimliğe göre güvenilir anahtarı bulur. Host simülasyonunda ortam değişkeniyle verilen test anahtarı da aranır.
+pub fn find_key(id: u32) -> Result<TrustedKey, SecureBootError> {
+    let result = find_key_in(&TRUSTED_KEYS, id);
+    #[cfg(feature = "hosted")]
+    if result.is_err() {
+        if let Some((key_id, public_key)) = crate::hosted::boot_pubkey_from_env() {
+            if key_id == id {
+                return Ok(TrustedKey { id, name: "hosted-test", public_key });
+            }
+        }
+    }
+    result
+}
+
+/// Bu derlemede imza doğrulayabilecek en az bir anahtar var mı (host simülasyonunda test anahtarı da sayılır).
+/// Yoksa `enforce` politikası imzalı imajların hepsini reddeder.
+pub fn any_key_provisioned() -> bool {
+    #[cfg(feature = "hosted")]
+    if crate::hosted::boot_pubkey_from_env().is_some() {
+        return true;
+    }
+    TRUSTED_KEYS.iter().any(|k| k.is_provisioned())
+}
+
+/// Açılışta anahtar durumunu bildirir: hiçbir anahtar yazılmamışsa bunun sonucunu açıkça loglar.
+/// # Safety
+/// Global politika değişkenini okur.
+pub unsafe fn report_key_status() {
+    if any_key_provisioned() {
+        return;
+    }
+    match get_policy() {
+        SecureBootPolicy::Enforce => log!("Secure boot WARNING: no trusted key is provisioned in this build; every image will be REJECTED under 'enforce'."),
+        policy => log!("Secure boot WARNING: no trusted key is provisioned in this build; images cannot be verified (policy '{}').", policy.name()),
+    }
+}
+
+/// İmajın imzasını doğrular (politikadan bağımsız). Başarılıysa imzalayan anahtarı döndürür.
+/// `header_bytes` imajın ham başlığıdır (en az `header.header_size` bayt).
+pub fn verify(header: &ImageHeader, header_bytes: &[u8], payload: &[u8]) -> Result<TrustedKey, SecureBootError> {
+    let signature = header.signature.as_ref().ok_or(SecureBootError::Unsigned)?;
+    verify_signature(find_key(signature.key_id)?, signature, header_bytes, payload)
+}
+
+/// `verify` ile aynı, ama anahtarlar gömülü liste yerine `keys`'ten aranır (örn. testler için).
+pub fn verify_with(keys: &[TrustedKey], header: &ImageHeader, header_bytes: &[u8], payload: &[u8]) -> Result<TrustedKey, SecureBootError> {
+    let signature = header.signature.as_ref().ok_or(SecureBootError::Unsigned)?;
+    verify_signature(find_key_in(keys, signature.key_id)?, signature, header_bytes, payload)
+}
+
+fn verify_signature(key: TrustedKey, signature: &ImageSignature, header_bytes: &[u8], payload: &[u8]) -> Result<TrustedKey, SecureBootError> {
+    let verifying_key = VerifyingKey::from_bytes(&key.public_key).map_err(|_| SecureBootError::InvalidKey(key.id))?;
+    let digest = signed_digest(header_bytes, payload);
+    verifying_key
+        .verify_strict(&digest, &Signature::from_bytes(&signature.signature))
+        .map_err(|_| SecureBootError::BadSignature(key.id))?;
+    Ok(key)
+}
+
+/// İmza taşıyamayan kod (örn. seri hattan yüklenen ham ikili) için politikayı uygular:
+/// `enforce` reddeder, `warn` uyarıp izin verir.
+/// # Safety
+/// Global politika değişkenini okur.
+pub unsafe fn check_unsigned() -> Result<(), SecureBootError> {
+    match get_policy() {
+        SecureBootPolicy::Off => {
+            log!("Secure boot: off, image not verified.");
+            Ok(())
+        }
+        SecureBootPolicy::Warn => {
+            log!("Secure boot WARNING: {}; running anyway because policy is 'warn'.", SecureBootError::Unsigned.description());
+            Ok(())
+        }
+        SecureBootPolicy::Enforce => {
+            log!("Secure boot: image REJECTED: {}.", SecureBootError::Unsigned.description());
+            Err(SecureBootError::Unsigned)
+        }
+    }
+}
+
+/// Geçerli politikayı uygular. `enforce` dışında her zaman Ok döner; sonuç her durumda loglanır.
+/// İmza gerçekten doğrulandıysa `Ok(true)` döner.
+/// # Safety
+/// Global politika değişkenini okur.
+pub unsafe fn check(header: &ImageHeader, header_bytes: &[u8], payload: &[u8]) -> Result<bool, SecureBootError> {
+    apply_policy(|| verify(header, header_bytes, payload))
+}
+
+/// `check` ile aynı, ama anahtarlar gömülü liste yerine `keys`'ten aranır (örn. testler için).
+/// # Safety
+/// Global politika değişkenini okur.
+pub unsafe fn check_with(keys: &[TrustedKey], header: &ImageHeader, header_bytes: &[u8], payload: &[u8]) -> Result<bool, SecureBootError> {
+    apply_policy(|| verify_with(keys, header, header_bytes, payload))
+}
+
+unsafe fn apply_policy<F: FnOnce() -> Result<TrustedKey, SecureBootError>>(verify: F) -> Result<bool, SecureBootError> {
+    let policy = get_policy();
+    if policy == SecureBootPolicy::Off {
+        log!("Secure boot: off, image not verified.");
+        return Ok(false);
+    }
+    match verify() {
+        Ok(key) => {
+            log!("Secure boot: image verified with key {} ({}).", key.id, key.name);
+            Ok(true)
+        }
+        Err(e) if policy == SecureBootPolicy::Warn => {
+            log!("Secure boot WARNING: {} ({:?}); booting anyway because policy is 'warn'.", e.description(), e);
+            Ok(false)
+        }
+        Err(e) => {
+            log!("Secure boot: image REJECTED: {} ({:?}).", e.description(), e);
+            Err(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crc::crc32;
+    use crate::image::{IMAGE_FLAG_SIGNED, IMAGE_KEY_ID_OFFSET, IMAGE_MAGIC, IMAGE_SIGNED_HEADER_MIN_SIZE, IMAGE_VERSION};
+    use crate::memory::FIRMWARE_RESERVED_END;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    const KEY_ID: u32 = 7;
+    const PAYLOAD: &[u8] = b"\x13\x00\x00\x00 kernel payload";
+
+    fn signing_key(seed: u8) -> SigningKey {
+        SigningKey::from_bytes(&[seed; 32])
+    }
+
+    fn trusted(key: &SigningKey) -> TrustedKey {
+        TrustedKey { id: KEY_ID, name: "test", public_key: key.verifying_key().to_bytes() }
+    }
+
+    fn set_u32(header: &mut [u8], offset: usize, value: u32) {
+        header[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    // Başlık CRC'sini yeniden hesaplar (imza da CRC kapsamındadır).
+    fn reseal(header: &mut [u8]) {
+        set_u32(header, 8, 0);
+        let crc = crc32(header);
+        set_u32(header, 8, crc);
+    }
+
+    // İmzalama aracının yaptığı sırayla: alanlar -> özet -> imza -> başlık CRC'si.
+    fn signed_header(key: &SigningKey, payload: &[u8]) -> Vec<u8> {
+        let mut header = vec![0u8; IMAGE_SIGNED_HEADER_MIN_SIZE];
+        header[0..4].copy_from_slice(&IMAGE_MAGIC);
+        header[4..6].copy_from_slice(&IMAGE_VERSION.to_le_bytes());
+        header[6..8].copy_from_slice(&(IMAGE_SIGNED_HEADER_MIN_SIZE as u16).to_le_bytes());
+        set_u32(&mut header, 12, IMAGE_FLAG_SIGNED);
+        set_u32(&mut header, 16, payload.len() as u32);
+        set_u32(&mut header, 20, crc32(payload));
+        header[24..32].copy_from_slice(&(FIRMWARE_RESERVED_END as u64).to_le_bytes());
+        set_u32(&mut header, IMAGE_KEY_ID_OFFSET, KEY_ID);
+        let signature = key.sign(&signed_digest(&header, payload));
+        header[IMAGE_SIGNATURE_OFFSET..IMAGE_SIGNED_HEADER_MIN_SIZE].copy_from_slice(&signature.to_bytes());
+        reseal(&mut header);
+        header
+    }
+
+    fn verify_image(keys: &[TrustedKey], header: &[u8], payload: &[u8]) -> Result<TrustedKey, SecureBootError> {
+        let parsed = ImageHeader::parse(header).expect("header must stay structurally valid");
+        verify_with(keys, &parsed, header, payload)
+    }
+
+    #[test]
+    fn valid_signature_is_accepted() {
+        let key = signing_key(1);
+        let header = signed_header(&key, PAYLOAD);
+        assert_eq!(verify_image(&[trusted(&key)], &header, PAYLOAD).unwrap().id, KEY_ID);
+    }
+
+    #[test]
+    fn tampered_payload_is_rejected() {
+        let key = signing_key(1);
+        let header = signed_header(&key, PAYLOAD);
+        let mut payload = PAYLOAD.to_vec();
+        payload[5] ^= 0x20;
+        assert!(matches!(verify_image(&[trusted(&key)], &header, &payload), Err(SecureBootError::BadSignature(KEY_ID))));
+    }
+
+    #[test]
+    fn tampered_signed_header_field_is_rejected() {
+        let key = signing_key(1);
+        let mut header = signed_header(&key, PAYLOAD);
+        // Saldırgan yükleme adresini değiştirip CRC'yi düzeltse bile imza tutmaz.
+        header[24..32].copy_from_slice(&(FIRMWARE_RESERVED_END as u64 + 0x1000).to_le_bytes());
+        reseal(&mut header);
+        assert!(matches!(verify_image(&[trusted(&key)], &header, PAYLOAD), Err(SecureBootError::BadSignature(KEY_ID))));
+    }
+
+    #[test]
+    fn signature_from_another_key_is_rejected() {
+        let header = signed_header(&signing_key(2), PAYLOAD);
+        assert!(matches!(verify_image(&[trusted(&signing_key(1))], &header, PAYLOAD), Err(SecureBootError::BadSignature(KEY_ID))));
+    }
+
+    #[test]
+    fn unknown_and_unprovisioned_keys_are_reported() {
+        let key = signing_key(1);
+        let header = signed_header(&key, PAYLOAD);
+        let other = TrustedKey { id: KEY_ID + 1, ..trusted(&key) };
+        assert!(matches!(verify_image(&[other], &header, PAYLOAD), Err(SecureBootError::UnknownKey(KEY_ID))));
+
+        let unprovisioned = TrustedKey { public_key: [0; 32], ..trusted(&key) };
+        assert!(!unprovisioned.is_provisioned());
+        assert!(matches!(verify_image(&[unprovisioned], &header, PAYLOAD), Err(SecureBootError::KeyNotProvisioned(KEY_ID))));
+    }
+
+    #[test]
+    fn unsigned_image_is_reported() {
+        let key = signing_key(1);
+        let mut header = signed_header(&key, PAYLOAD);
+        set_u32(&mut header, 12, 0);
+        reseal(&mut header);
+        assert!(matches!(verify_image(&[trusted(&key)], &header, PAYLOAD), Err(SecureBootError::Unsigned)));
+    }
+
+    // Politika global olduğu için tüm politika durumları tek testte, sırayla denenir.
+    #[test]
+    fn policies_off_warn_and_enforce() {
+        let key = signing_key(1);
+        let keys = [trusted(&key)];
+        let header = signed_header(&key, PAYLOAD);
+        let parsed = ImageHeader::parse(&header).unwrap();
+        let tampered = b"\x13\x00\x00\x00 kernel PAYLOAD";
+        unsafe {
+            set_policy(SecureBootPolicy::Off);
+            assert!(matches!(check_with(&keys, &parsed, &header, tampered), Ok(false)));
+            assert!(matches!(check_with(&keys, &parsed, &header, PAYLOAD), Ok(false)));
+            assert!(check_unsigned().is_ok());
+
+            set_policy(SecureBootPolicy::Warn);
+            assert!(matches!(check_with(&keys, &parsed, &header, tampered), Ok(false)));
+            assert!(matches!(check_with(&keys, &parsed, &header, PAYLOAD), Ok(true)));
+            assert!(check_unsigned().is_ok());
+
+            set_policy(SecureBootPolicy::Enforce);
+            assert!(matches!(check_with(&keys, &parsed, &header, tampered), Err(SecureBootError::BadSignature(KEY_ID))));
+            assert!(matches!(check_with(&keys, &parsed, &header, PAYLOAD), Ok(true)));
+            assert!(matches!(check_unsigned(), Err(SecureBootError::Unsigned)));
+        }
+    }
+
+    #[test]
+    fn policy_names_round_trip() {
+        for policy in [SecureBootPolicy::Enforce, SecureBootPolicy::Warn, SecureBootPolicy::Off] {
+            assert_eq!(SecureBootPolicy::parse(policy.name()), Some(policy));
+        }
+        assert_eq!(SecureBootPolicy::parse("strict"), None);
+    }
+}
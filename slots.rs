@@ -0,0 +1,229 @@
+#![no_std]
+
+// A/B işletim sistemi slotları.
+// İki slotun her biri bir boot kaynağıdır (`slot.<a|b>.source`) ve kalıcı ayarlarda şu durumları taşır:
+//   priority   (0-15)  0: slot kullanılmaz; en yüksek öncelikli boot edilebilir slot seçilir
+//   tries      (0-7)   Henüz başarılı işaretlenmemiş slot için kalan deneme sayısı
+//   successful (bool)  İşletim sistemi slotun sorunsuz açıldığını onayladı
+// Slot boot edilebilir: priority > 0 ve (successful veya tries > 0).
+//
+// Başarılı işaretlenmemiş bir slot denenmeden ÖNCE tries bir azaltılır ve kaydedilir; işletim sistemi
+// açılıp `slot mark-successful` (veya eşdeğeri) çalıştırmazsa denemeler tükenir ve sonraki boot'ta
+// diğer slota geri dönülür (rollback). İmaj geçersizse veya imza doğrulaması başarısızsa slot hemen
+// boot edilemez işaretlenir. Depolama, bölüm veya dosya sistemi hatalarında (örn. kart takılı değil)
+// slotun durumu değiştirilmez (düşülen deneme hakkı dışında) ve diğer slota geçilir.
+
+use crate::boot::BootError;
+use crate::env::{self, EnvError};
+
+pub const SLOT_MAX_PRIORITY: u8 = 15;
+pub const SLOT_MAX_TRIES: u8 = 7;
+// `slot set-active` ile etkinleştirilen slotun deneme sayısı.
+pub const SLOT_DEFAULT_TRIES: u8 = 3;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Slot {
+    A,
+    B,
+}
+
+impl Slot {
+    pub const ALL: [Slot; 2] = [Slot::A, Slot::B];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "a" | "A" => Some(Slot::A),
+            "b" | "B" => Some(Slot::B),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Slot::A => "a",
+            Slot::B => "b",
+        }
+    }
+
+    pub fn other(&self) -> Slot {
+        match self {
+            Slot::A => Slot::B,
+            Slot::B => Slot::A,
+        }
+    }
+
+    fn keys(&self) -> SlotKeys {
+        match self {
+            Slot::A => SlotKeys { source: "slot.a.source", priority: "slot.a.priority", tries: "slot.a.tries", successful: "slot.a.successful" },
+            Slot::B => SlotKeys { source: "slot.b.source", priority: "slot.b.priority", tries: "slot.b.tries", successful: "slot.b.successful" },
+        }
+    }
+}
+
+struct SlotKeys {
+    source: &'static str,
+    priority: &'static str,
+    tries: &'static str,
+    successful: &'static str,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SlotState {
+    pub priority: u8,
+    pub tries: u8,
+    pub successful: bool,
+}
+
+impl SlotState {
+    pub fn is_bootable(&self) -> bool {
+        self.priority > 0 && (self.successful || self.tries > 0)
+    }
+}
+
+/// A/B seçimi açık mı (`slot.enabled`).
+/// # Safety
+/// Global NVRAM'i okur.
+pub unsafe fn enabled() -> bool {
+    env::get_bool("slot.enabled")
+}
+
+/// Slotun boot kaynağı.
+/// # Safety
+/// Global NVRAM'i okur.
+pub unsafe fn source(slot: Slot) -> &'static str {
+    env::get(slot.keys().source).unwrap_or("")
+}
+
+/// # Safety
+/// Global NVRAM'i okur.
+pub unsafe fn state(slot: Slot) -> SlotState {
+    let keys = slot.keys();
+    SlotState {
+        priority: env::get_int(keys.priority) as u8,
+        tries: env::get_int(keys.tries) as u8,
+        successful: env::get_bool(keys.successful),
+    }
+}
+
+/// Slot durumunu yazar ve hemen kaydeder (bekleyen diğer değişken değişiklikleriyle birlikte).
+/// # Safety
+/// Global NVRAM'i değiştirir ve eMMC'ye yazar.
+pub unsafe fn set_state(slot: Slot, state: SlotState) -> Result<(), EnvError> {
+    let keys = slot.keys();
+    let mut digits = [0u8; 3];
+    env::set(keys.priority, format_u8(&mut digits, state.priority.min(SLOT_MAX_PRIORITY)))?;
+    env::set(keys.tries, format_u8(&mut digits, state.tries.min(SLOT_MAX_TRIES)))?;
+    env::set(keys.successful, if state.successful { "1" } else { "0" })?;
+    env::save()
+}
+
+fn format_u8(buffer: &mut [u8; 3], value: u8) -> &str {
+    let mut len = 0;
+    let mut divisor = 100;
+    while divisor > 0 {
+        let digit = value / divisor % 10;
+        if len > 0 || digit > 0 || divisor == 1 {
+            buffer[len] = b'0' + digit;
+            len += 1;
+        }
+        divisor /= 10;
+    }
+    core::str::from_utf8(&buffer[..len]).unwrap_or("0")
+}
+
+/// Boot edilebilir slotlardan en yüksek öncelikliyi seçer (eşitlikte A).
+/// # Safety
+/// Global NVRAM'i okur.
+pub unsafe fn select() -> Option<Slot> {
+    select_except(&[])
+}
+
+// `select`, ama `skip` içindeki slotlar boot edilebilir sayılmaz.
+unsafe fn select_except(skip: &[Slot]) -> Option<Slot> {
+    let bootable = |slot: Slot| !skip.contains(&slot) && state(slot).is_bootable();
+    let a = state(Slot::A);
+    let b = state(Slot::B);
+    match (bootable(Slot::A), bootable(Slot::B)) {
+        (true, true) => Some(if b.priority > a.priority { Slot::B } else { Slot::A }),
+        (true, false) => Some(Slot::A),
+        (false, true) => Some(Slot::B),
+        (false, false) => None,
+    }
+}
+
+/// Slotu etkin yapar: en yüksek öncelik ve yeni deneme hakkı verilir, diğer slot onun altına iner.
+/// # Safety
+/// Global NVRAM'i değiştirir ve eMMC'ye yazar.
+pub unsafe fn set_active(slot: Slot) -> Result<(), EnvError> {
+    let mut other = state(slot.other());
+    if other.priority >= SLOT_MAX_PRIORITY {
+        other.priority = SLOT_MAX_PRIORITY - 1;
+    }
+    set_state(slot.other(), other)?;
+    set_state(slot, SlotState { priority: SLOT_MAX_PRIORITY, tries: SLOT_DEFAULT_TRIES, successful: false })
+}
+
+/// # Safety
+/// Global NVRAM'i değiştirir ve eMMC'ye yazar.
+pub unsafe fn mark_successful(slot: Slot) -> Result<(), EnvError> {
+    let current = state(slot);
+    set_state(slot, SlotState { successful: true, ..current })
+}
+
+/// # Safety
+/// Global NVRAM'i değiştirir ve eMMC'ye yazar.
+pub unsafe fn mark_unbootable(slot: Slot) -> Result<(), EnvError> {
+    set_state(slot, SlotState { priority: 0, tries: 0, successful: false })
+}
+
+// Bu boot'ta seçilen slot (handoff tablosu ve device tree'ye aktarılır).
+static mut BOOTED_SLOT: Option<Slot> = None;
+
+/// # Safety
+/// Global durumu okur.
+pub unsafe fn booted_slot() -> Option<Slot> {
+    BOOTED_SLOT
+}
+
+/// Slot seçimini çalıştırır. Seçilen slot boot ederse geri dönmez.
+/// Başarısız olan slot bu boot'ta tekrar denenmez ve diğer slota geçilir; imaj veya imza hatasında
+/// slot ayrıca boot edilemez işaretlenir. İkisi de başarısız olursa son hata döndürülür (boot
+/// yöneticisi normal hedeflere devam eder).
+/// # Safety
+/// NVRAM'e yazar, RAM'e imaj yükler ve başarılıysa kontrolü imaja devreder.
+pub unsafe fn boot_slots(command_line: Option<&str>) -> BootError {
+    let mut last_error = BootError::InvalidBootPath;
+    let mut tried = [Slot::A; 2];
+    let mut tried_count = 0;
+    while let Some(slot) = select_except(&tried[..tried_count]) {
+        tried[tried_count] = slot;
+        tried_count += 1;
+        let mut current = state(slot);
+        if !current.successful {
+            current.tries -= 1;
+            // Deneme hakkı imaj çalıştırılmadan önce kalıcı olarak düşülür.
+            if let Err(e) = set_state(slot, current) {
+                log!("Slot {}: cannot record boot attempt ({:?}); not booting it.", slot.name(), e);
+                return last_error;
+            }
+        }
+        log!("Slot {}: priority {}, tries left {}, successful {}.", slot.name(), current.priority, current.tries, current.successful);
+        BOOTED_SLOT = Some(slot);
+        last_error = crate::bootmgr::try_target(source(slot), command_line);
+        BOOTED_SLOT = None;
+
+        match last_error {
+            // İmajın kendisi bozuk veya güvenilmez: tekrar denemek işe yaramaz.
+            BootError::Image(_) | BootError::SecureBoot(_) => {
+                log!("Slot {} failed: {:?}; marking it unbootable.", slot.name(), last_error);
+                if mark_unbootable(slot).is_err() {
+                    return last_error;
+                }
+            }
+            // Aygıt veya dosya geçici olarak erişilemiyor olabilir; kalan deneme hakları korunur.
+            _ => log!("Slot {} failed: {:?}; trying the other slot.", slot.name(), last_error),
+        }
+    }
+    log!("No bootable slot left.");
+    last_error
+}
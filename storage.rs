@@ -1,296 +1,489 @@
-#![no_std]
-
- use crate::firmware_common::{self, Error};
-
-pub const BLOCK_SIZE: usize = 512;
-
-pub trait BlockDevice {
-    fn init(&mut self) -> Result<u64, StorageError>;
-    fn read_block(&mut self, lba: u64, buffer: &mut [u8]) -> Result<(), StorageError>;
-    fn write_block(&mut self, lba: u64, data: &[u8]) -> Result<(), StorageError>;
-    fn block_count(&self) -> Option<u64>;
-}
-
-#[derive(Debug)]
-pub enum StorageError {
-    InitializationError,
-    ReadError,
-    WriteError,
-    InvalidLba,
-    InvalidBufferLength,
-    NotInitialized,
-    UnsupportedDevice,
-    CommandError, // eMMC/SD komut hatası gibi
-    Timeout,      // İletişim zaman aşımı
-    // ...
-}
-
-pub struct EmicStorage {
-    is_initialized: bool,
-    total_blocks: Option<u64>,
-    // ... eMMC denetleyicisi donanım referansları veya HAL nesnesi
-}
-
-impl EmicStorage {
-    pub const fn new(/* eMMC denetleyicisi donanım referansları */) -> Self {
-        EmicStorage { is_initialized: false, total_blocks: None }
-    }
-
-    // eMMC düşük seviyeli driver fonksiyonları (placeholder)
-    // CMD gönderme, yanıt okuma vb.
-     unsafe fn send_emic_command(&mut self, cmd: u8, arg: u32) -> Result<u32, StorageError> {
-         // Düşük seviye eMMC komut gönderme ve yanıt alma logic'i
-         // Bu, eMMC denetleyicisi registerlarına yazma/okuma içerecektir.
-         // Örnek: denetleyici_register::write_volatile(EMMC_CMD_REG, cmd_val);
-         // ... yanıt bekleme ve okuma ...
-          Ok(0) // Placeholder yanıt
-     }
-
-     unsafe fn read_data(&mut self, buffer: &mut [u8]) -> Result<(), StorageError> {
-         // Düşük seviye eMMC veri okuma logic'i
-          denetleyici_register::read_volatile(EMMC_DATA_REG);
-          Ok(()) // Placeholder
-     }
-
-      unsafe fn write_data(&mut self, data: &[u8]) -> Result<(), StorageError> {
-         // Düşük seviye eMMC veri yazma logic'i
-          denetleyici_register::write_volatile(EMMC_DATA_REG, byte);
-          Ok(()) // Placeholder
-     }
-}
-
-impl BlockDevice for EmicStorage {
-    fn init(&mut self) -> Result<u64, StorageError> {
-        // --- GERÇEK BAŞLATMA KODU BURAYA GELECEK ---
-        // eMMC 1.0 başlatma sırasını ve SiFive S21 eMMC denetleyicisini yapılandırın.
-        // Bu, eMMC standart spesifikasyonuna göre CMD komutlarını göndermeyi içerir.
-
-        // 1. Denetleyiciyi ve Arayüzü Yapılandırma (Saat, Genişlik vb.)
-         emmc_controller_register::write_volatile(EMMC_CFG_REG, config_val);
-
-        // 2. Sıfırlama veya Başlangıç Komutları (CMD0, CMD1 vb.)
-         match unsafe { self.send_emic_command(0, 0) } { // CMD0: Go idle state
-             Ok(_) => { /* Başarılı */ },
-             Err(e) => return Err(e), // Veya StorageError::InitializationError
-         }
-         match unsafe { self.send_emic_command(1, arg) } { // CMD1: Send Op Cond (ACMD41 gibi olmayabilir, eMMC spesifik)
-             Ok(_) => { /* Başarılı */ },
-             Err(e) => return Err(e),
-         }
-        // ... diğer init komutları ...
-
-        // 3. Aygıt Kimliklerini Okuma (CID, CSD)
-         match unsafe { self.send_emic_command(2, 0) } { // CMD2: ALL_SEND_CID
-            Ok(cid_data) => { /* CID'yi parse et */ },
-            Err(e) => return Err(e),
-         }
-         match unsafe { self.send_emic_command(9, rca) } { // CMD9: SEND_CSD
-            Ok(csd_data) => { /* CSD'yi parse et ve kapasiteyi hesapla */ },
-            Err(e) => return Err(e),
-         }
-
-        // 4. Çalışma Modunu Ayarlama (Transfer Mode) ve RCA (Relative Card Address) Alma (CMD3)
-         match unsafe { self.send_emic_command(3, 0) } { // CMD3: SET_RELATIVE_ADDR
-            Ok(rca) => { /* RCA'yı sakla */ },
-            Err(e) => return Err(e),
-         }
-
-        // 5. Kapasiteyi Belirleme ve 25 MB Limiti Kontrolü
-        let calculated_block_count_from_csd = 60000; // CSD'den hesaplanan varsayımsal blok sayısı
-        let capacity_bytes_limit = 25 * 1024 * 1024;
-        let block_count_limit = capacity_bytes_limit / BLOCK_SIZE as u64;
-
-        self.total_blocks = Some(core::cmp::min(calculated_block_count_from_csd, block_count_limit));
-        self.is_initialized = true;
-
-
-        // Başarılı olursa toplam blok sayısını döndür
-        Ok(self.total_blocks.unwrap_or(0))
-
-        // Hata durumunda
-         Err(StorageError::InitializationError)
-        // --- GERÇEK BAŞLATMA KODU BURAYA KADAR ---
-    }
-
-    fn read_block(&mut self, lba: u64, buffer: &mut [u8]) -> Result<(), StorageError> {
-         if !self.is_initialized || buffer.len() < BLOCK_SIZE || lba >= self.total_blocks.unwrap_or(0) {
-             return Err(StorageError::InvalidLba);
-        }
-        // --- GERÇEK READ KODU BURAYA GELECEK ---
-        // Belirtilen LBA'dan eMMC'den 512 bayt okuma.
-        // CMD17 (READ_SINGLE_BLOCK) komutunu gönderme, LBA'yı argüman olarak verme,
-        // ardından veri transferini yönetme ve CRC kontrolü yapma.
-         match unsafe { self.send_emic_command(17, lba as u32) } { /* ... */ }
-         match unsafe { self.read_data(buffer) } { /* ... */ }
-
-        // --- GERÇEK READ KODU BURAYA KADAR ---
-         Ok(()) // Placeholder
-    }
-    fn write_block(&mut self, lba: u64, data: &[u8]) -> Result<(), StorageError> {
-         if !self.is_initialized || data.len() < BLOCK_SIZE || lba >= self.total_blocks.unwrap_or(0) {
-             return Err(StorageError::InvalidLba);
-        }
-        // --- GERÇEK WRITE KODU BURAYA GELECEK ---
-        // Belirtilen LBA'ya eMMC'ye 512 bayt yazma.
-        // CMD24 (WRITE_SINGLE_BLOCK) komutunu gönderme, LBA'yı argüman olarak verme,
-        // ardından veri transferini yönetme ve CRC kontrolü yapma.
-         match unsafe { self.send_emic_command(24, lba as u32) } { /* ... */ }
-         match unsafe { self.write_data(data) } { /* ... */ }
-
-        // --- GERÇEK WRITE KODU BURAYA KADAR ---
-         Ok(()) // Placeholder
-    }
-    fn block_count(&self) -> Option<u64> { self.total_blocks }
-}
-
-pub struct SdCardStorage {
-    is_initialized: bool,
-    total_blocks: Option<u64>,
-    // ... SD kart denetleyicisi donanım referansları veya HAL nesnesi (genellikle SPI veya SDIO)
-}
-
-impl SdCardStorage {
-    pub const fn new(/* SD kart denetleyicisi donanım referansları */) -> Self {
-        SdCardStorage { is_initialized: false, total_blocks: None }
-    }
-
-    // SD kart düşük seviyeli driver fonksiyonları (placeholder)
-    // SPI/SDIO üzerinden CMD/ACMD gönderme, yanıt okuma vb.
-     unsafe fn send_sd_command(&mut self, cmd: u8, arg: u32) -> Result<u32, StorageError> {
-         // Düşük seviye SD komut gönderme ve yanıt alma logic'i (SPI veya SDIO)
-          spi_driver::transfer(...);
-           Ok(0) // Placeholder
-     }
-
-     unsafe fn read_data(&mut self, buffer: &mut [u8]) -> Result<(), StorageError> {
-         // Düşük seviye SD veri okuma logic'i
-           Ok(()) // Placeholder
-     }
-
-      unsafe fn write_data(&mut self, data: &[u8]) -> Result<(), StorageError> {
-         // Düşük seviye SD veri yazma logic'i
-           Ok(()) // Placeholder
-     }
-}
-
-impl BlockDevice for SdCardStorage {
-    fn init(&mut self) -> Result<u64, StorageError> {
-        // --- GERÇEK BAŞLATMA KODU BURAYA GELECEK ---
-        // SD kart başlatma sırasını ve SiFive S21 SD/SPI denetleyicisini yapılandırın.
-        // Bu, SD/SPI standart spesifikasyonuna göre CMD/ACMD komutlarını göndermeyi içerir.
-
-        // 1. Denetleyiciyi ve Arayüzü Yapılandırma (SPI/SDIO, Saat, Genişlik vb.)
-        sdio_controller_register::write_volatile(SDIO_CFG_REG, config_val);
-
-        // 2. Sıfırlama veya Başlangıç Komutları (CMD0, CMD8, CMD55, ACMD41 vb.)
-         match unsafe { self.send_sd_command(0, 0) } { // CMD0: Go idle state
-             Ok(_) => { /* Başarılı */ },
-             Err(e) => return Err(e),
-         }
-         match unsafe { self.send_sd_command(8, arg) } { // CMD8: Send Interface Condition (SDHC/SDXC için)
-             Ok(response) => { /* Yaniti kontrol et */ },
-             Err(e) => return Err(e),
-         }
-        // ... ACMD41 (başlatma komutu) gönderip hazır olana kadar bekleme ...
-
-        // 3. Aygıt Kimliklerini Okuma (CID, CSD)
-         match unsafe { self.send_sd_command(2, 0) } { // CMD2: ALL_SEND_CID
-            Ok(cid_data) => { /* CID'yi parse et */ },
-            Err(e) => return Err(e),
-         }
-         match unsafe { self.send_sd_command(9, rca) } { // CMD9: SEND_CSD
-            Ok(csd_data) => { /* CSD'yi parse et ve kapasiteyi hesapla */ },
-            Err(e) => return Err(e),
-         }
-
-        // 4. Çalışma Modunu Ayarlama (Transfer Mode) ve RCA Alma (CMD3)
-         match unsafe { self.send_sd_command(3, 0) } { // CMD3: SET_RELATIVE_ADDR
-            Ok(rca) => { /* RCA'yı sakla */ },
-            Err(e) => return Err(e),
-         }
-         match unsafe { self.send_sd_command(7, rca) } { // CMD7: SELECT/DESELECT CARD
-            Ok(_) => { /* Başarılı */ },
-            Err(e) => return Err(e),
-         }
-
-
-        // 5. Kapasiteyi Belirleme ve MBR 2.2 TB Limitini Dikkat Alma
-        let calculated_block_count_from_csd_or_extcsd = 100_000_000; // CSD/Extended CSD'den hesaplanan varsayımsal blok sayısı
-        let mbr_max_blocks: u64 = (2.2 * 1024.0 * 1024.0 * 1024.0 * 1024.0 / BLOCK_SIZE as f64) as u64;
-        let effective_block_count = core::cmp::min(calculated_block_count_from_csd_or_extcsd, mbr_max_blocks);
-
-        self.total_blocks = Some(effective_block_count);
-        self.is_initialized = true;
-
-
-        // Başarılı olursa toplam blok sayısını döndür
-        Ok(self.total_blocks.unwrap_or(0))
-        // Hata durumunda
-         Err(StorageError::InitializationError)
-    }
-     fn read_block(&mut self, lba: u64, buffer: &mut [u8]) -> Result<(), StorageError> {
-         if !self.is_initialized || buffer.len() < BLOCK_SIZE || lba >= self.total_blocks.unwrap_or(0) {
-             return Err(StorageError::InvalidLba);
-        }
-        // --- GERÇEK READ KODU BURAYA GELECEK ---
-        // Belirtilen LBA'dan SD karttan 512 bayt okuma.
-        // CMD17 (READ_SINGLE_BLOCK) komutunu gönderme, LBA'yı argüman olarak verme,
-        // ardından veri transferini yönetme ve CRC kontrolü yapma (SPI ise).
-         match unsafe { self.send_sd_command(17, lba as u32) } { /* ... */ }
-         match unsafe { self.read_data(buffer) } { /* ... */ }
-
-        // --- GERÇEK READ KODU BURAYA KADAR ---
-        // unimplemented!("SD card read_block not implemented"); // Yerine yukarıdaki kod
-         Ok(()) // Placeholder
-     }
-     fn write_block(&mut self, lba: u64, data: &[u8]) -> Result<(), StorageError> {
-         if !self.is_initialized || data.len() < BLOCK_SIZE || lba >= self.total_blocks.unwrap_or(0) {
-             return Err(StorageError::InvalidLba);
-        }
-        // --- GERÇEK WRITE KODU BURAYA GELECEK ---
-        // Belirtilen LBA'ya SD karta 512 bayt yazma.
-        // CMD24 (WRITE_SINGLE_BLOCK) komutunu gönderme, LBA'yı argüman olarak verme,
-        // ardından veri transferini yönetme ve CRC kontrolü yapma (SPI ise).
-         match unsafe { self.send_sd_command(24, lba as u32) } { /* ... */ }
-         match unsafe { self.write_data(data) } { /* ... */ }
-
-        // --- GERÇEK WRITE KODU BURAYA KADAR ---
-         Ok(()) // Placeholder
-     }
-    fn block_count(&self) -> Option<u64> { self.total_blocks }
-}
-
-pub static mut EMIC_STORAGE_GLOBAL: Option<EmicStorage> = None;
-pub static mut SD_CARD_STORAGE_GLOBAL: Option<SdCardStorage> = None;
-
-/// Sistemdeki depolama aygıtlarını algılamaya ve başlatmaya çalışır.
-/// # Safety
-/// Donanım erişimi gerektiren init fonksiyonlarını çağırır.
-pub unsafe fn init_storage_devices(/* Donanım referansları */) -> Result<(Option<EmicStorage>, Option<SdCardStorage>), StorageError> {
-    // StorageDevice::new() çağrılarına gerekli donanım referanslarını (örn. SPI denetleyici nesnesi) ekleyin.
-    let mut emic_device = EmicStorage::new(/* emmc denetleyici referansi */);
-    let emic_result = emic_device.init();
-
-    let initialized_emic = match emic_result {
-        Ok(_) => Some(emic_device),
-        Err(e) => {
-             // Loglama UART başladıktan sonra yapılacak
-             log!("eMMC init failed: {:?}", e);
-            None
-        }
-    };
-
-    let mut sd_card_device = SdCardStorage::new(/* sd/spi denetleyici referansi */);
-    let sd_result = sd_card_device.init();
-
-    let initialized_sd = match sd_result {
-        Ok(_) => Some(sd_card_device),
-        Err(e) => {
-             // Loglama UART başladıktan sonra yapılacak
-             log!("SD card init failed: {:?}", e);
-            None
-        }
-    };
-
-    Ok((initialized_emic, initialized_sd))
-}
\ No newline at end of file
+#![no_std]
+
+pub const BLOCK_SIZE: usize = 512;
+
+pub trait BlockDevice {
+    fn init(&mut self) -> Result<u64, StorageError>;
+    fn read_block(&mut self, lba: u64, buffer: &mut [u8]) -> Result<(), StorageError>;
+    fn write_block(&mut self, lba: u64, data: &[u8]) -> Result<(), StorageError>;
+    fn block_count(&self) -> Option<u64>;
+}
+
+#[derive(Debug)]
+pub enum StorageError {
+    InitializationError,
+    ReadError,
+    WriteError,
+    InvalidLba,
+    InvalidBufferLength,
+    NotInitialized,
+    UnsupportedDevice,
+    CommandError, // eMMC/SD komut hatası gibi
+    Timeout,      // İletişim zaman aşımı
+    // ...
+}
+
+// eMMC 1.0 kapasite limiti (25 MB).
+pub const EMIC_CAPACITY_LIMIT_BYTES: u64 = 25 * 1024 * 1024;
+
+// Depolama denetleyicilerinin yazmaç pencereleri (device tree'de de bildirilir).
+pub const EMMC_CONTROLLER_BASE: usize = 0xCCCC_0000; // SiFive S21 eMMC denetleyici Base Adresi - VERİ SAYFASINDAN BULUN!
+pub const SD_CONTROLLER_BASE: usize = 0xCCCD_0000; // SiFive S21 SD denetleyici Base Adresi - VERİ SAYFASINDAN BULUN!
+pub const STORAGE_CONTROLLER_SIZE: u32 = 0x1000;
+
+// eMMC üzerinde firmware'e ayrılmış bölge. MBR/GPT meta verisinden sonra, ilk bölümden önceki boşluktadır;
+// eMMC bölümleri EMMC_RESERVED_END_LBA'dan (1 MiB) itibaren başlamalıdır.
+pub const EMMC_RESERVED_START_LBA: u64 = 1024;
+pub const EMMC_RESERVED_END_LBA: u64 = 2048;
+// Kalıcı değişken deposu (NVRAM, bkz. nvram.rs): iki bank, toplam 64 blok.
+pub const EMMC_NVRAM_START_LBA: u64 = EMMC_RESERVED_START_LBA;
+pub const EMMC_NVRAM_BLOCKS: u64 = 64;
+// Firmware güncelleme bankaları (bkz. update.rs): iki bank, her biri bir tanımlayıcı blok + imaj.
+pub const EMMC_FIRMWARE_START_LBA: u64 = EMMC_NVRAM_START_LBA + EMMC_NVRAM_BLOCKS;
+pub const EMMC_FIRMWARE_BANK_BLOCKS: u64 = 448;
+
+pub struct EmicStorage {
+    is_initialized: bool,
+    total_blocks: Option<u64>,
+    // ... eMMC denetleyicisi donanım referansları veya HAL nesnesi
+    // Host simülasyonunda denetleyici yerine imaj dosyası kullanılır.
+    #[cfg(feature = "hosted")]
+    image: Option<crate::hosted::BlockImage>,
+}
+
+impl EmicStorage {
+    pub const fn new(/* eMMC denetleyicisi donanım referansları */) -> Self {
+        EmicStorage {
+            is_initialized: false,
+            total_blocks: None,
+            #[cfg(feature = "hosted")]
+            image: None,
+        }
+    }
+
+    // eMMC düşük seviyeli driver fonksiyonları (placeholder)
+    // CMD gönderme, yanıt okuma vb.
+    #[cfg(not(feature = "hosted"))]
+     unsafe fn send_emic_command(&mut self, _cmd: u8, _arg: u32) -> Result<u32, StorageError> {
+         // Düşük seviye eMMC komut gönderme ve yanıt alma logic'i
+         // Bu, eMMC denetleyicisi registerlarına yazma/okuma içerecektir.
+         // Örnek: denetleyici_register::write_volatile(EMMC_CMD_REG, cmd_val);
+         // ... yanıt bekleme ve okuma ...
+          Ok(0) // Placeholder yanıt
+     }
+
+    #[cfg(not(feature = "hosted"))]
+     unsafe fn read_data(&mut self, _buffer: &mut [u8]) -> Result<(), StorageError> {
+         // Düşük seviye eMMC veri okuma logic'i
+         // denetleyici_register::read_volatile(EMMC_DATA_REG);
+          Ok(()) // Placeholder
+     }
+
+    #[cfg(not(feature = "hosted"))]
+      unsafe fn write_data(&mut self, _data: &[u8]) -> Result<(), StorageError> {
+         // Düşük seviye eMMC veri yazma logic'i
+         // denetleyici_register::write_volatile(EMMC_DATA_REG, byte);
+          Ok(()) // Placeholder
+     }
+}
+
+impl BlockDevice for EmicStorage {
+    #[cfg(feature = "hosted")]
+    fn init(&mut self) -> Result<u64, StorageError> {
+        // Host simülasyonu: kapasite imaj dosyasından gelir, 25 MB limiti yine uygulanır.
+        let image = crate::hosted::BlockImage::open_from_env(crate::hosted::EMMC_IMAGE_ENV)?;
+        let block_count_limit = EMIC_CAPACITY_LIMIT_BYTES / BLOCK_SIZE as u64;
+        self.total_blocks = Some(core::cmp::min(image.block_count(), block_count_limit));
+        self.image = Some(image);
+        self.is_initialized = true;
+        Ok(self.total_blocks.unwrap_or(0))
+    }
+
+    #[cfg(not(feature = "hosted"))]
+    fn init(&mut self) -> Result<u64, StorageError> {
+        // --- GERÇEK BAŞLATMA KODU BURAYA GELECEK ---
+        // eMMC 1.0 başlatma sırasını ve SiFive S21 eMMC denetleyicisini yapılandırın.
+        // Bu, eMMC standart spesifikasyonuna göre CMD komutlarını göndermeyi içerir.
+
+        // 1. Denetleyiciyi ve Arayüzü Yapılandırma (Saat, Genişlik vb.)
+        // emmc_controller_register::write_volatile(EMMC_CFG_REG, config_val);
+
+        // Varsayımsal değerler - eMMC SPESİFİKASYONUNDAN DOĞRULAYIN!
+        const EMMC_OCR_ARG: u32 = 0x40FF_8080; // CMD1 argümanı: sektör adresleme, 2.7-3.6 V
+        const EMMC_RCA: u32 = 1; // eMMC'de RCA'yı host atar
+        unsafe {
+            // 2. Sıfırlama veya Başlangıç Komutları (CMD0, CMD1 vb.)
+            self.send_emic_command(0, 0)?; // CMD0: Go idle state
+            self.send_emic_command(1, EMMC_OCR_ARG)?; // CMD1: Send Op Cond (ACMD41 gibi olmayabilir, eMMC spesifik)
+            // ... diğer init komutları ...
+
+            // 3. Aygıt Kimliğini Okuma (CID) ve RCA (Relative Card Address) Atama (CMD3)
+            let _cid_data = self.send_emic_command(2, 0)?; // CMD2: ALL_SEND_CID - CID'yi parse et
+            self.send_emic_command(3, EMMC_RCA << 16)?; // CMD3: SET_RELATIVE_ADDR
+
+            // 4. CSD'yi okuma ve kapasiteyi hesaplama
+            let _csd_data = self.send_emic_command(9, EMMC_RCA << 16)?; // CMD9: SEND_CSD
+        }
+
+        // 5. Kapasiteyi Belirleme ve 25 MB Limiti Kontrolü
+        let calculated_block_count_from_csd = 60000; // CSD'den hesaplanan varsayımsal blok sayısı
+        let block_count_limit = EMIC_CAPACITY_LIMIT_BYTES / BLOCK_SIZE as u64;
+
+        self.total_blocks = Some(core::cmp::min(calculated_block_count_from_csd, block_count_limit));
+        self.is_initialized = true;
+
+
+        // Başarılı olursa toplam blok sayısını döndür (hata durumunda StorageError::InitializationError)
+        Ok(self.total_blocks.unwrap_or(0))
+        // --- GERÇEK BAŞLATMA KODU BURAYA KADAR ---
+    }
+
+    fn read_block(&mut self, lba: u64, buffer: &mut [u8]) -> Result<(), StorageError> {
+         if !self.is_initialized || buffer.len() < BLOCK_SIZE || lba >= self.total_blocks.unwrap_or(0) {
+             return Err(StorageError::InvalidLba);
+        }
+        #[cfg(feature = "hosted")]
+        {
+            return self.image.as_mut().ok_or(StorageError::NotInitialized)?.read_block(lba, buffer);
+        }
+        #[cfg(not(feature = "hosted"))]
+        {
+        // --- GERÇEK READ KODU BURAYA GELECEK ---
+        // Belirtilen LBA'dan eMMC'den 512 bayt okuma.
+        // CMD17 (READ_SINGLE_BLOCK) komutunu gönderme, LBA'yı argüman olarak verme,
+        // ardından veri transferini yönetme ve CRC kontrolü yapma.
+         // Blok adresi komut argümanına (32 bit) sığmalıdır; sessizce kırpılmaz.
+         let address = u32::try_from(lba).map_err(|_| StorageError::InvalidLba)?;
+         unsafe {
+             self.send_emic_command(17, address)?;
+             self.read_data(buffer)?;
+         }
+
+        // --- GERÇEK READ KODU BURAYA KADAR ---
+         Ok(()) // Placeholder
+        }
+    }
+    fn write_block(&mut self, lba: u64, data: &[u8]) -> Result<(), StorageError> {
+         if !self.is_initialized || data.len() < BLOCK_SIZE || lba >= self.total_blocks.unwrap_or(0) {
+             return Err(StorageError::InvalidLba);
+        }
+        #[cfg(feature = "hosted")]
+        {
+            return self.image.as_mut().ok_or(StorageError::NotInitialized)?.write_block(lba, data);
+        }
+        #[cfg(not(feature = "hosted"))]
+        {
+        // --- GERÇEK WRITE KODU BURAYA GELECEK ---
+        // Belirtilen LBA'ya eMMC'ye 512 bayt yazma.
+        // CMD24 (WRITE_SINGLE_BLOCK) komutunu gönderme, LBA'yı argüman olarak verme,
+        // ardından veri transferini yönetme ve CRC kontrolü yapma.
+         let address = u32::try_from(lba).map_err(|_| StorageError::InvalidLba)?;
+         unsafe {
+             self.send_emic_command(24, address)?;
+             self.write_data(data)?;
+         }
+
+        // --- GERÇEK WRITE KODU BURAYA KADAR ---
+         Ok(()) // Placeholder
+        }
+    }
+    fn block_count(&self) -> Option<u64> { self.total_blocks }
+}
+
+// MBR'nin LBA alanları 32 bittir: en fazla 2^32 blok (~2.2 TB) adreslenebilir.
+pub const MBR_MAX_BLOCKS: u64 = 1 << 32;
+// CMD17/CMD24'ün blok adresi argümanı da 32 bittir. SDUC kartların genişletilmiş adreslemesi (CMD22)
+// desteklenene kadar GPT'li kartlarda da en fazla bu kadar blok kullanılır.
+pub const SD_MAX_ADDRESSABLE_BLOCKS: u64 = 1 << 32;
+
+/// Bir SD kartın kullanılabilir kapasitesi (bkz. `sd_capacity`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SdCapacity {
+    pub blocks: u64,
+    pub gpt: bool,        // Kartta geçerli bir GPT başlığı bulundu
+    pub mbr_capped: bool, // GPT yok; kapasite MBR sınırına kısıtlandı
+}
+
+/// Ham kapasiteye ve bölüm şemasına göre kullanılabilecek blok sayısını belirler.
+/// Geçerli bir GPT varsa MBR sınırı uygulanmaz; her durumda blok adresleri 32 bite sığmalıdır.
+/// `device` GPT başlığının okunabilmesi için başlatılmış olmalıdır.
+pub fn sd_capacity<D: BlockDevice + ?Sized>(device: &mut D, raw_blocks: u64) -> SdCapacity {
+    let gpt = crate::gpt::probe(device);
+    let mbr_capped = !gpt && raw_blocks > MBR_MAX_BLOCKS;
+    let limit = if gpt { SD_MAX_ADDRESSABLE_BLOCKS } else { MBR_MAX_BLOCKS };
+    SdCapacity { blocks: core::cmp::min(raw_blocks, limit), gpt, mbr_capped }
+}
+
+pub struct SdCardStorage {
+    is_initialized: bool,
+    total_blocks: Option<u64>,
+    gpt_detected: bool, // Kartta geçerli bir GPT başlığı bulundu
+    mbr_capped: bool,   // Kapasite MBR sınırına kısıtlandı
+    // ... SD kart denetleyicisi donanım referansları veya HAL nesnesi (genellikle SPI veya SDIO)
+    // Host simülasyonunda denetleyici yerine imaj dosyası kullanılır.
+    #[cfg(feature = "hosted")]
+    image: Option<crate::hosted::BlockImage>,
+}
+
+impl SdCardStorage {
+    pub const fn new(/* SD kart denetleyicisi donanım referansları */) -> Self {
+        SdCardStorage {
+            is_initialized: false,
+            total_blocks: None,
+            gpt_detected: false,
+            mbr_capped: false,
+            #[cfg(feature = "hosted")]
+            image: None,
+        }
+    }
+
+    /// Kartta geçerli bir GPT varsa true döner.
+    pub fn is_gpt(&self) -> bool { self.gpt_detected }
+
+    /// Kapasite MBR'nin ~2.2 TB sınırına kısıtlandıysa true döner.
+    pub fn is_mbr_capped(&self) -> bool { self.mbr_capped }
+
+    // Ham kapasiteyi ayarlar ve bölüm şemasına göre sınır uygular (bkz. `sd_capacity`).
+    // GPT başlığının okunabilmesi için aygıt önce başlatılmış kabul edilir.
+    fn apply_capacity_limit(&mut self, raw_blocks: u64) {
+        self.total_blocks = Some(raw_blocks);
+        self.is_initialized = true;
+        let capacity = sd_capacity(self, raw_blocks);
+        self.total_blocks = Some(capacity.blocks);
+        self.gpt_detected = capacity.gpt;
+        self.mbr_capped = capacity.mbr_capped;
+    }
+
+    // SD kart düşük seviyeli driver fonksiyonları (placeholder)
+    // SPI/SDIO üzerinden CMD/ACMD gönderme, yanıt okuma vb.
+    #[cfg(not(feature = "hosted"))]
+     unsafe fn send_sd_command(&mut self, _cmd: u8, _arg: u32) -> Result<u32, StorageError> {
+         // Düşük seviye SD komut gönderme ve yanıt alma logic'i (SPI veya SDIO)
+         // spi_driver::transfer(...);
+           Ok(0) // Placeholder
+     }
+
+    #[cfg(not(feature = "hosted"))]
+     unsafe fn read_data(&mut self, _buffer: &mut [u8]) -> Result<(), StorageError> {
+         // Düşük seviye SD veri okuma logic'i
+           Ok(()) // Placeholder
+     }
+
+    #[cfg(not(feature = "hosted"))]
+      unsafe fn write_data(&mut self, _data: &[u8]) -> Result<(), StorageError> {
+         // Düşük seviye SD veri yazma logic'i
+           Ok(()) // Placeholder
+     }
+}
+
+impl BlockDevice for SdCardStorage {
+    #[cfg(feature = "hosted")]
+    fn init(&mut self) -> Result<u64, StorageError> {
+        // Host simülasyonu: kapasite imaj dosyasından gelir, GPT yoksa MBR 2.2 TB limiti yine uygulanır.
+        let image = crate::hosted::BlockImage::open_from_env(crate::hosted::SD_IMAGE_ENV)?;
+        let raw_blocks = image.block_count();
+        self.image = Some(image);
+        self.apply_capacity_limit(raw_blocks);
+        Ok(self.total_blocks.unwrap_or(0))
+    }
+
+    #[cfg(not(feature = "hosted"))]
+    fn init(&mut self) -> Result<u64, StorageError> {
+        // --- GERÇEK BAŞLATMA KODU BURAYA GELECEK ---
+        // SD kart başlatma sırasını ve SiFive S21 SD/SPI denetleyicisini yapılandırın.
+        // Bu, SD/SPI standart spesifikasyonuna göre CMD/ACMD komutlarını göndermeyi içerir.
+
+        // 1. Denetleyiciyi ve Arayüzü Yapılandırma (SPI/SDIO, Saat, Genişlik vb.)
+        // sdio_controller_register::write_volatile(SDIO_CFG_REG, config_val);
+
+        // Varsayımsal değer - SD SPESİFİKASYONUNDAN DOĞRULAYIN!
+        const SD_IF_COND_ARG: u32 = 0x1AA; // CMD8 argümanı: 2.7-3.6 V, kontrol deseni 0xAA
+        unsafe {
+            // 2. Sıfırlama veya Başlangıç Komutları (CMD0, CMD8, CMD55, ACMD41 vb.)
+            self.send_sd_command(0, 0)?; // CMD0: Go idle state
+            let _if_cond = self.send_sd_command(8, SD_IF_COND_ARG)?; // CMD8: Send Interface Condition (SDHC/SDXC için) - yanıtı kontrol et
+            // ... ACMD41 (başlatma komutu) gönderip hazır olana kadar bekleme ...
+
+            // 3. Aygıt Kimliğini Okuma (CID) ve RCA Alma (CMD3)
+            let _cid_data = self.send_sd_command(2, 0)?; // CMD2: ALL_SEND_CID - CID'yi parse et
+            let rca = self.send_sd_command(3, 0)? & 0xFFFF_0000; // CMD3: SEND_RELATIVE_ADDR (R6, üst 16 bit)
+
+            // 4. CSD'yi okuma ve kapasiteyi hesaplama, ardından Transfer Mode'a geçiş
+            let _csd_data = self.send_sd_command(9, rca)?; // CMD9: SEND_CSD
+            self.send_sd_command(7, rca)?; // CMD7: SELECT/DESELECT CARD
+        }
+
+
+        // 5. Kapasiteyi Belirleme ve MBR 2.2 TB Limitini Dikkat Alma
+        // SDUC kartlar ve GPT ile biçimlendirilmiş büyük kartlarda limit uygulanmaz.
+        let calculated_block_count_from_csd_or_extcsd = 100_000_000; // CSD/Extended CSD'den hesaplanan varsayımsal blok sayısı
+        self.apply_capacity_limit(calculated_block_count_from_csd_or_extcsd);
+
+
+        // Başarılı olursa toplam blok sayısını döndür (hata durumunda StorageError::InitializationError)
+        Ok(self.total_blocks.unwrap_or(0))
+    }
+     fn read_block(&mut self, lba: u64, buffer: &mut [u8]) -> Result<(), StorageError> {
+         if !self.is_initialized || buffer.len() < BLOCK_SIZE || lba >= self.total_blocks.unwrap_or(0) {
+             return Err(StorageError::InvalidLba);
+        }
+        #[cfg(feature = "hosted")]
+        {
+            return self.image.as_mut().ok_or(StorageError::NotInitialized)?.read_block(lba, buffer);
+        }
+        #[cfg(not(feature = "hosted"))]
+        {
+        // --- GERÇEK READ KODU BURAYA GELECEK ---
+        // Belirtilen LBA'dan SD karttan 512 bayt okuma.
+        // CMD17 (READ_SINGLE_BLOCK) komutunu gönderme, LBA'yı argüman olarak verme,
+        // ardından veri transferini yönetme ve CRC kontrolü yapma (SPI ise).
+         // Blok adresi komut argümanına (32 bit) sığmalıdır; sessizce kırpılmaz.
+         let address = u32::try_from(lba).map_err(|_| StorageError::InvalidLba)?;
+         unsafe {
+             self.send_sd_command(17, address)?;
+             self.read_data(buffer)?;
+         }
+
+        // --- GERÇEK READ KODU BURAYA KADAR ---
+        // unimplemented!("SD card read_block not implemented"); // Yerine yukarıdaki kod
+         Ok(()) // Placeholder
+        }
+     }
+     fn write_block(&mut self, lba: u64, data: &[u8]) -> Result<(), StorageError> {
+         if !self.is_initialized || data.len() < BLOCK_SIZE || lba >= self.total_blocks.unwrap_or(0) {
+             return Err(StorageError::InvalidLba);
+        }
+        #[cfg(feature = "hosted")]
+        {
+            return self.image.as_mut().ok_or(StorageError::NotInitialized)?.write_block(lba, data);
+        }
+        #[cfg(not(feature = "hosted"))]
+        {
+        // --- GERÇEK WRITE KODU BURAYA GELECEK ---
+        // Belirtilen LBA'ya SD karta 512 bayt yazma.
+        // CMD24 (WRITE_SINGLE_BLOCK) komutunu gönderme, LBA'yı argüman olarak verme,
+        // ardından veri transferini yönetme ve CRC kontrolü yapma (SPI ise).
+         let address = u32::try_from(lba).map_err(|_| StorageError::InvalidLba)?;
+         unsafe {
+             self.send_sd_command(24, address)?;
+             self.write_data(data)?;
+         }
+
+        // --- GERÇEK WRITE KODU BURAYA KADAR ---
+         Ok(()) // Placeholder
+        }
+     }
+    fn block_count(&self) -> Option<u64> { self.total_blocks }
+}
+
+pub static mut EMIC_STORAGE_GLOBAL: Option<EmicStorage> = None;
+pub static mut SD_CARD_STORAGE_GLOBAL: Option<SdCardStorage> = None;
+
+/// Cihaz adına ("emic" veya "sd") göre başlatılmış global blok aygıtını döndürür.
+/// CLI ve boot kodu aygıtları bu adlarla adresler.
+/// # Safety
+/// Global mutable statiklere erişir.
+pub unsafe fn device_by_name(name: &str) -> Result<&'static mut dyn BlockDevice, StorageError> {
+    match name {
+        "emic" => EMIC_STORAGE_GLOBAL.as_mut().map(|d| d as &mut dyn BlockDevice).ok_or(StorageError::NotInitialized),
+        "sd" => SD_CARD_STORAGE_GLOBAL.as_mut().map(|d| d as &mut dyn BlockDevice).ok_or(StorageError::NotInitialized),
+        _ => Err(StorageError::UnsupportedDevice),
+    }
+}
+
+/// Sistemdeki depolama aygıtlarını algılamaya ve başlatmaya çalışır.
+/// # Safety
+/// Donanım erişimi gerektiren init fonksiyonlarını çağırır.
+pub unsafe fn init_storage_devices(/* Donanım referansları */) -> Result<(Option<EmicStorage>, Option<SdCardStorage>), StorageError> {
+    // StorageDevice::new() çağrılarına gerekli donanım referanslarını (örn. SPI denetleyici nesnesi) ekleyin.
+    let mut emic_device = EmicStorage::new(/* emmc denetleyici referansi */);
+    let emic_result = emic_device.init();
+
+    let initialized_emic = match emic_result {
+        Ok(_) => Some(emic_device),
+        Err(e) => {
+             // Loglama UART başladıktan sonra yapılacak
+             log!("eMMC init failed: {:?}", e);
+            None
+        }
+    };
+
+    let mut sd_card_device = SdCardStorage::new(/* sd/spi denetleyici referansi */);
+    let sd_result = sd_card_device.init();
+
+    let initialized_sd = match sd_result {
+        Ok(_) => Some(sd_card_device),
+        Err(e) => {
+             // Loglama UART başladıktan sonra yapılacak
+             log!("SD card init failed: {:?}", e);
+            None
+        }
+    };
+
+    Ok((initialized_emic, initialized_sd))
+}
+
+// --- Test/Mock aygıtı ---
+
+/// Verilen bellek dilimini 512 baytlık bloklar halinde sunan sahte (mock) blok aygıtı.
+/// Heap kullanmaz; testlerde disk imajlarını ayrıştırmak ve yazma sırasında güç kesilmesini
+/// (`set_write_limit`) benzetmek için kullanılır.
+pub struct RamDisk<'a> {
+    data: &'a mut [u8],
+    writes: usize,
+    write_limit: Option<usize>,
+}
+
+impl<'a> RamDisk<'a> {
+    pub fn new(data: &'a mut [u8]) -> Self {
+        RamDisk { data, writes: 0, write_limit: None }
+    }
+
+    /// Toplam `limit` blok yazıldıktan sonraki yazmaları `WriteError` ile reddeder (None: sınırsız).
+    /// Reddedilen yazma aygıta hiç ulaşmaz; güç o yazmadan hemen önce kesilmiş gibidir.
+    pub fn set_write_limit(&mut self, limit: Option<usize>) {
+        self.write_limit = limit;
+    }
+
+    /// Şimdiye kadar başarıyla yazılan blok sayısı.
+    pub fn writes(&self) -> usize {
+        self.writes
+    }
+
+    fn block_range(&self, lba: u64, buffer_len: usize) -> Result<core::ops::Range<usize>, StorageError> {
+        if buffer_len < BLOCK_SIZE {
+            return Err(StorageError::InvalidBufferLength);
+        }
+        if lba >= self.data.len() as u64 / BLOCK_SIZE as u64 {
+            return Err(StorageError::InvalidLba);
+        }
+        let start = lba as usize * BLOCK_SIZE;
+        Ok(start..start + BLOCK_SIZE)
+    }
+}
+
+impl<'a> BlockDevice for RamDisk<'a> {
+    fn init(&mut self) -> Result<u64, StorageError> {
+        Ok(self.data.len() as u64 / BLOCK_SIZE as u64)
+    }
+
+    fn read_block(&mut self, lba: u64, buffer: &mut [u8]) -> Result<(), StorageError> {
+        let range = self.block_range(lba, buffer.len())?;
+        buffer[..BLOCK_SIZE].copy_from_slice(&self.data[range]);
+        Ok(())
+    }
+
+    fn write_block(&mut self, lba: u64, data: &[u8]) -> Result<(), StorageError> {
+        let range = self.block_range(lba, data.len())?;
+        if self.write_limit.is_some_and(|limit| self.writes >= limit) {
+            return Err(StorageError::WriteError);
+        }
+        self.data[range].copy_from_slice(&data[..BLOCK_SIZE]);
+        self.writes += 1;
+        Ok(())
+    }
+
+    fn block_count(&self) -> Option<u64> {
+        Some(self.data.len() as u64 / BLOCK_SIZE as u64)
+    }
+}
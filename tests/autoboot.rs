@@ -0,0 +1,46 @@
+// Açılış geri sayımı testleri: eMMC imajına önceden kaydedilmiş değişkenlerle firmware'i başlatır.
+// Firmware global durumu tek bir açılışa izin verdiği için bu dosya (ayrı bir test süreci) tek bir
+// açılış senaryosunu çalıştırır.
+
+use std::time::{Duration, Instant};
+
+use packetbox_firmware::hosted::{console_capture, console_take_output};
+use packetbox_firmware::nvram::Nvram;
+use packetbox_firmware::storage::{RamDisk, BLOCK_SIZE};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+const EMMC_BLOCKS: usize = 4096;
+
+// NVRAM bölgesinde `variables` kayıtlı bir eMMC imajı oluşturur ve firmware'e verir.
+fn emmc_with_variables(variables: &[(&str, &str)]) {
+    let mut image = vec![0u8; EMMC_BLOCKS * BLOCK_SIZE];
+    let mut disk = RamDisk::new(&mut image);
+    let mut nvram = Box::new(Nvram::new());
+    nvram.load(&mut disk).unwrap();
+    for (name, value) in variables {
+        nvram.set(name, value.as_bytes()).unwrap();
+    }
+    nvram.commit(&mut disk).unwrap();
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("autoboot-emmc.img");
+    std::fs::write(&path, &image).unwrap();
+    std::env::set_var("PACKETBOX_EMMC_IMAGE", &path);
+}
+
+#[test]
+fn negative_bootdelay_skips_the_countdown() {
+    emmc_with_variables(&[("bootdelay", "-1")]);
+    console_capture();
+    // Giriş verilmez: geri sayım çalışsaydı tuş beklemeden boot sırasına geçerdi.
+    std::thread::spawn(|| packetbox_firmware::run());
+
+    let deadline = Instant::now() + TIMEOUT;
+    let mut output = String::new();
+    while !output.contains("PacketBox System is now Running.") {
+        assert!(Instant::now() < deadline, "timed out, console output so far:\n{}", output);
+        std::thread::sleep(Duration::from_millis(2));
+        output.push_str(&String::from_utf8_lossy(&console_take_output()));
+    }
+    assert!(output.contains("Autoboot disabled (bootdelay=-1)."), "{}", output);
+    assert!(!output.contains("Booting in"), "{}", output);
+    assert!(!output.contains("Boot target"), "{}", output);
+}
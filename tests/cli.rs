@@ -0,0 +1,394 @@
+// CLI entegrasyon testleri: firmware host simülasyonunda bir thread'de çalıştırılır, konsol bellek içi
+// moda alınır ve komutlar "klavyeden" yazılıyormuş gibi giriş kuyruğuna itilir.
+// Firmware durumu global olduğu için tüm testler aynı firmware örneğini sırayla kullanır.
+
+use std::sync::{Mutex, MutexGuard, Once};
+use std::time::{Duration, Instant};
+
+use packetbox_firmware::hosted::{console_capture, console_push_input, console_take_output};
+
+static FIRMWARE: Once = Once::new();
+static CONSOLE_LOCK: Mutex<()> = Mutex::new(());
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+// Boş eMMC imajının blok sayısı: NVRAM ve firmware bankalarını içerecek kadar büyük.
+const EMMC_BLOCKS: usize = 4096;
+
+// Çıktıda `done` sağlanana kadar konsolu okur; zaman aşımında o ana kadar toplanan çıktıyla başarısız olur.
+fn read_until(done: impl Fn(&str) -> bool) -> String {
+    let deadline = Instant::now() + TIMEOUT;
+    let mut output = String::new();
+    loop {
+        output.push_str(&String::from_utf8_lossy(&console_take_output()));
+        if done(&output) {
+            return output;
+        }
+        assert!(Instant::now() < deadline, "timed out, console output so far:\n{}", output);
+        std::thread::sleep(Duration::from_millis(2));
+    }
+}
+
+// Firmware'i (bir kez) başlatır ve konsolu kullanacak test için kilidi döner.
+fn console() -> MutexGuard<'static, ()> {
+    let guard = CONSOLE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    FIRMWARE.call_once(|| {
+        // Değişkenlerin kaydedilebilmesi için boş bir eMMC imajı verilir; SD kart takılı değildir.
+        let emmc = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("cli-emmc.img");
+        std::fs::write(&emmc, vec![0u8; EMMC_BLOCKS * 512]).unwrap();
+        std::env::set_var("PACKETBOX_EMMC_IMAGE", &emmc);
+        console_capture();
+        // Otomatik boot geri sayımını ilk tuşla durdur; aksi halde boot sırası çalışır.
+        console_push_input(b" ");
+        std::thread::spawn(|| packetbox_firmware::run());
+        let output = read_until(|o| o.contains("PacketBox System is now Running."));
+        assert!(output.contains("Autoboot interrupted."), "{}", output);
+    });
+    guard
+}
+
+// Baytları yazar ve CLI yeni prompt'u basana kadar çıktıyı toplar.
+fn type_keys(keys: &[u8]) -> String {
+    console_push_input(keys);
+    read_until(|o| o.ends_with("\n> "))
+}
+
+fn run(line: &str) -> String {
+    type_keys(format!("{}\r", line).as_bytes())
+}
+
+#[test]
+fn echo_prints_arguments() {
+    let _console = console();
+    let output = run("echo hello world");
+    assert!(output.contains("hello world\n"), "{}", output);
+}
+
+#[test]
+fn help_lists_commands() {
+    let _console = console();
+    let output = run("help");
+    for name in ["help", "setenv", "getenv", "boot", "echo"] {
+        assert!(output.contains(name), "'{}' missing from help:\n{}", name, output);
+    }
+}
+
+#[test]
+fn unknown_command_is_reported() {
+    let _console = console();
+    let output = run("frobnicate");
+    assert!(output.contains("Error: Unknown command 'frobnicate'. Type 'help'."), "{}", output);
+}
+
+#[test]
+fn setenv_value_is_visible_to_getenv_and_expansion() {
+    let _console = console();
+    run("setenv bootargs console=ttyS0");
+    let output = run("getenv bootargs");
+    assert!(output.contains("console=ttyS0"), "{}", output);
+    let output = run("echo \"[$bootargs]\"");
+    assert!(output.contains("[console=ttyS0]\n"), "{}", output);
+    run("setenv bootargs");
+}
+
+// Giriş satırı 128 baytla sınırlı; uzun değerler genişletilen değişkenlerle oluşturulur.
+fn with_long_variable(test: impl FnOnce()) {
+    run(&format!("setenv long {}", "x".repeat(100)));
+    test();
+    run("setenv long");
+}
+
+#[test]
+fn overlong_setenv_value_is_rejected_not_truncated() {
+    let _console = console();
+    run("setenv bootargs console=ttyS0");
+    with_long_variable(|| {
+        let output = run("setenv bootargs $long $long $long");
+        assert!(output.contains("Error: Value for 'bootargs' is longer than 255 bytes."), "{}", output);
+        let output = run("getenv bootargs");
+        assert!(output.contains("console=ttyS0\n"), "{}", output);
+        // Değişkenin kendi sınırını (64) aşan değer de aynı şekilde bildirilir.
+        let output = run("setenv bootdevice $long");
+        assert!(output.contains("Error: Value for 'bootdevice' is longer than 64 bytes."), "{}", output);
+    });
+    run("setenv bootargs");
+}
+
+#[test]
+fn overlong_kernel_command_line_is_rejected() {
+    let _console = console();
+    with_long_variable(|| {
+        let output = run("boot sd $long $long $long");
+        assert!(output.contains("Error: Kernel command line is longer than 255 bytes."), "{}", output);
+        assert!(!output.contains("Boot failed"), "{}", output);
+    });
+}
+
+#[test]
+fn commands_run_in_sequence() {
+    let _console = console();
+    let output = run("echo first; echo second");
+    let first = output.find("first\n").expect(&output);
+    let second = output.find("second\n").expect(&output);
+    assert!(first < second, "{}", output);
+}
+
+#[test]
+fn parse_error_runs_nothing() {
+    let _console = console();
+    let output = run("echo ran; echo \"unterminated");
+    assert!(output.contains("Error:"), "{}", output);
+    assert!(!output.contains("ran\n"), "{}", output);
+}
+
+#[test]
+fn expansion_overflow_is_reported_when_its_command_is_reached() {
+    let _console = console();
+    with_long_variable(|| {
+        // İlk geçişte `grown` boş; setenv çalıştıktan sonra echo'nun kelimeleri 512 baytı aşar.
+        let output = run("setenv grown $long$long; echo $grown $grown $grown; echo after");
+        assert!(output.contains("Error: expanded line too long at column 45."), "{}", output);
+        assert!(!output.contains("\nafter\n"), "{}", output);
+        let output = run("getenv grown");
+        assert!(output.contains(&"x".repeat(200)), "{}", output);
+    });
+    run("setenv grown");
+}
+
+#[test]
+fn tab_completes_a_unique_command_name() {
+    let _console = console();
+    let output = type_keys(b"ech\thello\r");
+    assert!(output.contains("\nhello\n"), "{}", output);
+}
+
+#[test]
+fn double_tab_lists_ambiguous_completions() {
+    let _console = console();
+    // İlk Tab yalnızca zil çalar; ikincisi adayları listeler ve satırı yeniden çizer. Ctrl-C satırı atar.
+    console_push_input(b"se\t\t\x03");
+    let output = read_until(|o| o.contains("^C") && o.ends_with("\n> "));
+    assert!(output.contains('\x07'), "{:?}", output);
+    assert!(output.contains("\r\nsetenv  secureboot  \r\n> se"), "{:?}", output);
+}
+
+#[test]
+fn tab_completes_command_arguments() {
+    let _console = console();
+    let output = type_keys(b"storage info e\t\r");
+    // "e" silinip "emic" ve ardından boşluk yazılır; komut eMMC bilgisini basar.
+    assert!(output.contains("emic\x1b[K \x1b[K"), "{:?}", output);
+    assert!(output.contains("eMMC 1.0 Info:"), "{}", output);
+}
+
+#[test]
+fn completion_that_does_not_fit_keeps_the_word() {
+    let _console = console();
+    // 125 boşluk + "ech" satırı doldurur; "echo"ya tamamlamak 129 bayt gerektirir.
+    let mut keys = vec![b' '; 125];
+    keys.extend_from_slice(b"ech\t\r");
+    let output = type_keys(&keys);
+    assert!(output.contains('\x07'), "{:?}", output);
+    assert!(output.contains("Error: Unknown command 'ech'."), "{}", output);
+}
+
+#[test]
+fn line_editing_keys_change_the_executed_line() {
+    let _console = console();
+    // "echo xyz" -> iki Backspace: "echo x" -> Ctrl+A ve beş kez sağ ok: imleç "x"in önünde -> "a": "echo ax".
+    let output = type_keys(b"echo xyz\x7f\x7f\x01\x1b[C\x1b[C\x1b[C\x1b[C\x1b[Ca\r");
+    assert!(output.contains("\nax\n"), "{}", output);
+}
+
+#[test]
+fn ctrl_c_discards_the_line() {
+    let _console = console();
+    let output = type_keys(b"echo discarded\x03");
+    assert!(output.contains("^C"), "{}", output);
+    let output = run("echo kept");
+    assert!(output.contains("kept\n") && !output.contains("discarded"), "{}", output);
+}
+
+// Geçmiş firmware örneğiyle birlikte testler arasında paylaşılır; her test kendi kayıtlarını ekleyip
+// yalnızca en yenilerine bakar.
+#[test]
+fn up_and_down_recall_history() {
+    let _console = console();
+    run("echo one");
+    run("echo two");
+    // Yukarı ok iki kez: "echo one"; aşağı ok (Ctrl-N) bir kez: "echo two".
+    let output = type_keys(b"\x1b[A\x1b[A\x0e\r");
+    assert!(output.contains("\ntwo\n") && !output.contains("\none\n"), "{}", output);
+    // Ctrl-P ve SS3 yukarı ok da aynı kaydı getirir: en yeni "echo two", bir öncesi "echo one".
+    let output = type_keys(b"\x10\x1bOA\r");
+    assert!(output.contains("\none\n"), "{}", output);
+}
+
+#[test]
+fn consecutive_identical_lines_are_stored_once() {
+    let _console = console();
+    run("echo first");
+    run("echo repeated");
+    run("echo repeated");
+    let output = type_keys(b"\x1b[A\x1b[A\r");
+    assert!(output.contains("\nfirst\n"), "{}", output);
+}
+
+#[test]
+fn history_keeps_only_the_newest_entries() {
+    let _console = console();
+    for i in 0..10 {
+        run(&format!("echo entry{}", i));
+    }
+    // Sekiz kayıt tutulur: en eskisi "echo entry2"; daha fazla yukarı ok onda kalır.
+    let output = type_keys(b"\x1b[A\x1b[A\x1b[A\x1b[A\x1b[A\x1b[A\x1b[A\x1b[A\x1b[A\x1b[A\r");
+    assert!(output.contains("\nentry2\n"), "{}", output);
+}
+
+#[test]
+fn down_past_the_newest_entry_restores_the_draft() {
+    let _console = console();
+    run("echo saved");
+    let output = type_keys(b"echo draft\x1b[A\x1b[B\r");
+    assert!(output.contains("\ndraft\n") && !output.contains("\nsaved\n"), "{}", output);
+}
+
+#[test]
+fn secureboot_policy_goes_through_the_variable() {
+    let _console = console();
+    let output = run("secureboot warn");
+    assert!(output.contains("Secure boot policy set to 'warn'"), "{}", output);
+    assert!(output.contains("Secure boot policy: warn"), "{}", output);
+    let output = run("getenv secureboot");
+    assert!(output.contains("warn\n"), "{}", output);
+    run("setenv secureboot");
+    let output = run("secureboot");
+    assert!(output.contains("Secure boot policy: enforce"), "{}", output);
+}
+
+// Depolama testleri eMMC imajında ayrılmış alanın (LBA 1024-2047) üstündeki blokları kullanır.
+#[test]
+fn storage_hex_write_reads_back() {
+    let _console = console();
+    let output = run("storage write emic 3000 hex de:ad:be:ef");
+    assert!(output.contains("Wrote 1 block(s) to emic LBA 3000..3000."), "{}", output);
+    // Bloğun kalanı sıfır kalır; aynı satırlar tek '*' ile geçilir.
+    let output = run("storage read emic 3000");
+    let dump = [
+        "00177000  de ad be ef 00 00 00 00  00 00 00 00 00 00 00 00  |................|",
+        "00177010  00 00 00 00 00 00 00 00  00 00 00 00 00 00 00 00  |................|",
+        "*",
+        "00177200",
+    ]
+    .join("\n");
+    assert!(output.contains(&dump), "{}", output);
+}
+
+#[test]
+fn storage_fill_covers_the_block_count() {
+    let _console = console();
+    let output = run("storage write emic 3008 fill 41_5a 2");
+    assert!(output.contains("Wrote 2 block(s) to emic LBA 3008..3009."), "{}", output);
+    let output = run("storage read emic 3008 3");
+    let dump = [
+        "00178000  41 5a 41 5a 41 5a 41 5a  41 5a 41 5a 41 5a 41 5a  |AZAZAZAZAZAZAZAZ|",
+        "*",
+        "00178400  00 00 00 00 00 00 00 00  00 00 00 00 00 00 00 00  |................|",
+        "*",
+        "00178600",
+    ]
+    .join("\n");
+    assert!(output.contains(&dump), "{}", output);
+}
+
+#[test]
+fn storage_write_to_the_reserved_area_needs_confirmation() {
+    let _console = console();
+    // Onay sorusuna "no" (ve ikinci denemede Ctrl-C) yanıtı verilir; hiçbir şey yazılmaz.
+    let output = type_keys(b"storage write emic 1500 fill ff\rno\r");
+    assert!(output.contains("overlaps the firmware-reserved area"), "{}", output);
+    assert!(output.contains("Type 'yes' to continue: no\n"), "{}", output);
+    assert!(output.contains("Aborted, nothing was written."), "{}", output);
+    let output = type_keys(b"storage write emic 0 fill ff\r\x03");
+    assert!(output.contains("overlaps LBA 0 (partition table)"), "{}", output);
+    assert!(output.contains("Aborted, nothing was written."), "{}", output);
+    let output = run("storage read emic 1500");
+    assert!(output.contains("000bb800  00 00 00 00"), "{}", output);
+    assert!(!output.contains(" ff "), "{}", output);
+}
+
+#[test]
+fn storage_write_rejects_bad_hex() {
+    let _console = console();
+    for data in ["abc", "zz"] {
+        let output = run(&format!("storage write emic 3016 hex {}", data));
+        assert!(output.contains(&format!("Error: '{}' is not a hex byte string", data)), "{}", output);
+    }
+}
+
+const SLOT_VARIABLES: [&str; 9] = [
+    "slot.enabled",
+    "slot.a.source", "slot.a.priority", "slot.a.tries", "slot.a.successful",
+    "slot.b.source", "slot.b.priority", "slot.b.tries", "slot.b.successful",
+];
+
+// Slot değişkenlerini varsayılanlarına döndürür (kaynaklar: boş eMMC'de bölüm tablosu yok).
+fn reset_slots() {
+    for name in SLOT_VARIABLES {
+        run(&format!("setenv {}", name));
+    }
+}
+
+// `slot status` çıktısındaki bir slot satırı: (öncelik, kalan deneme, boot edilebilir, sıradaki).
+fn slot_row(output: &str, slot: &str) -> (u8, u8, bool, bool) {
+    let line = output.lines().find(|l| l.trim_start().starts_with(&format!("{} ", slot))).expect(output);
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    (fields[2].parse().unwrap(), fields[3].parse().unwrap(), fields[5] == "yes", line.contains("<- next"))
+}
+
+#[test]
+fn slot_selection_prefers_priority_then_slot_a() {
+    let _console = console();
+    reset_slots();
+    run("setenv slot.b.priority 15");
+    let output = run("slot");
+    assert!(slot_row(&output, "a").3 && !slot_row(&output, "b").3, "{}", output);
+    run("setenv slot.a.priority 9");
+    let output = run("slot");
+    assert!(!slot_row(&output, "a").3 && slot_row(&output, "b").3, "{}", output);
+    reset_slots();
+}
+
+#[test]
+fn set_active_demotes_the_other_slot() {
+    let _console = console();
+    reset_slots();
+    run("setenv slot.b.tries 1");
+    run("slot set-active a");
+    let output = run("slot set-active b");
+    assert_eq!(slot_row(&output, "b"), (15, 3, true, true), "{}", output);
+    assert_eq!(slot_row(&output, "a").0, 14, "{}", output);
+    reset_slots();
+}
+
+#[test]
+fn failed_slot_boots_use_up_tries_then_roll_back() {
+    let _console = console();
+    reset_slots();
+    run("setenv slot.enabled 1");
+    run("slot set-active a");
+    run("slot mark-successful b");
+    for tries_left in (0..3).rev() {
+        let output = run("boot");
+        // Deneme hakkı imaj aranmadan önce düşülür; bölüm hatası slotu boot edilemez yapmaz.
+        let attempt = output.find(&format!("Slot a: priority 15, tries left {}", tries_left)).expect(&output);
+        let failure = output.find("Slot a failed").expect(&output);
+        assert!(attempt < failure, "{}", output);
+        assert!(output.contains("Slot b failed"), "{}", output);
+        let status = run("slot");
+        assert_eq!(slot_row(&status, "a"), (15, tries_left, tries_left > 0, tries_left > 0), "{}", status);
+        assert_eq!(slot_row(&status, "b"), (14, 3, true, tries_left == 0), "{}", status);
+    }
+    let output = run("boot");
+    assert!(!output.contains("Slot a:"), "{}", output);
+    reset_slots();
+}
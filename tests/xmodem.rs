@@ -0,0 +1,175 @@
+// XMODEM/YMODEM alma testleri: `sx`/`sb`'nin hatta gönderdiği bayt dizisi bellek içi konsolun giriş
+// kuyruğuna önceden konur, alıcı UART0 üzerinden çalıştırılır ve alıcının yanıtları (C/ACK/NAK/CAN)
+// çıktı arabelleğinden okunur. Konsol global olduğu için testler sırayla çalışır.
+
+use std::sync::{Mutex, MutexGuard, Once};
+
+use packetbox_firmware::crc::crc16_xmodem;
+use packetbox_firmware::hosted::{console_capture, console_push_input, console_read_byte, console_take_output};
+use packetbox_firmware::uart::{Uart0, UART0_BASE_ADDRESS, UART_CLOCK_HZ};
+use packetbox_firmware::xmodem::{self, Protocol, Transfer, TransferError, ACK, CAN, CRC_REQUEST, EOT, NAK, SOH, STX};
+
+static CAPTURE: Once = Once::new();
+static CONSOLE_LOCK: Mutex<()> = Mutex::new(());
+
+const CPMEOF: u8 = 0x1A; // Son paketin dolgusu
+
+fn console() -> MutexGuard<'static, ()> {
+    let guard = CONSOLE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    CAPTURE.call_once(console_capture);
+    while console_read_byte().is_some() {}
+    console_take_output();
+    guard
+}
+
+// Göndericinin tek bir paketi: SOH|STX, numara, tümleyeni, dolgulu veri, CRC-16 (büyük uçlu).
+fn packet(number: u8, payload: &[u8], len: usize, pad: u8) -> Vec<u8> {
+    let mut data = payload.to_vec();
+    data.resize(len, pad);
+    let mut bytes = vec![if len == 1024 { STX } else { SOH }, number, !number];
+    bytes.extend_from_slice(&data);
+    bytes.extend_from_slice(&crc16_xmodem(&data).to_be_bytes());
+    bytes
+}
+
+// Dosyayı `len` baytlık paketler halinde, 1'den numaralayarak gönderir (`sx`, `sx -k`).
+fn data_packets(file: &[u8], len: usize) -> Vec<Vec<u8>> {
+    file.chunks(len).enumerate().map(|(i, chunk)| packet(i as u8 + 1, chunk, len, CPMEOF)).collect()
+}
+
+// `sb`'nin dosya bilgisi paketi: "ad\0boyut mtime(sekizlik) mod(sekizlik) 0 kalan-dosya kalan-bayt".
+fn file_info(name: &str, size: usize) -> Vec<u8> {
+    let info = format!("{}\0{} 14710523612 100644 0 1 {}", name, size, size);
+    packet(0, info.as_bytes(), 128, 0)
+}
+
+// `sb` bitişi: EOT, (NAK sonrası) tekrar EOT ve boş ad taşıyan 0 numaralı paket.
+fn ymodem_end() -> Vec<u8> {
+    let mut bytes = vec![EOT, EOT];
+    bytes.extend(packet(0, &[], 128, 0));
+    bytes
+}
+
+fn receive(protocol: Protocol, stream: &[u8], buffer: &mut [u8]) -> (Result<Transfer, TransferError>, Vec<u8>) {
+    console_push_input(stream);
+    let mut uart = Uart0::new(UART0_BASE_ADDRESS, UART_CLOCK_HZ);
+    let result = unsafe { xmodem::receive(&mut uart, protocol, buffer) };
+    (result, console_take_output())
+}
+
+fn test_file(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i * 7 + i / 256) as u8).collect()
+}
+
+#[test]
+fn xmodem_128_byte_packets() {
+    let _console = console();
+    let file = test_file(300);
+    let mut stream: Vec<u8> = data_packets(&file, 128).concat();
+    stream.push(EOT);
+    let mut buffer = vec![0u8; 4096];
+    let (result, replies) = receive(Protocol::Xmodem, &stream, &mut buffer);
+    // XMODEM boyut taşımaz: son paketin dolgusu da alınır.
+    assert_eq!(result.unwrap().size, 384);
+    assert_eq!(&buffer[..300], &file[..]);
+    assert!(buffer[300..384].iter().all(|&b| b == CPMEOF));
+    assert_eq!(replies, [CRC_REQUEST, ACK, ACK, ACK, ACK]);
+}
+
+#[test]
+fn xmodem_1k_packets() {
+    let _console = console();
+    let file = test_file(2500);
+    let mut stream: Vec<u8> = data_packets(&file, 1024).concat();
+    stream.push(EOT);
+    let mut buffer = vec![0u8; 4096];
+    let (result, replies) = receive(Protocol::Xmodem, &stream, &mut buffer);
+    assert_eq!(result.unwrap().size, 3072);
+    assert_eq!(&buffer[..2500], &file[..]);
+    assert_eq!(replies, [CRC_REQUEST, ACK, ACK, ACK, ACK]);
+}
+
+#[test]
+fn lost_ack_repeats_packet_without_duplicating_data() {
+    let _console = console();
+    let file = test_file(256);
+    let packets = data_packets(&file, 128);
+    // 1. paketin ACK'i kayboldu: gönderici aynı paketi yeniden yollar.
+    let stream = [packets[0].clone(), packets[0].clone(), packets[1].clone(), vec![EOT]].concat();
+    let mut buffer = vec![0u8; 4096];
+    let (result, replies) = receive(Protocol::Xmodem, &stream, &mut buffer);
+    assert_eq!(result.unwrap().size, 256);
+    assert_eq!(&buffer[..256], &file[..]);
+    assert_eq!(replies, [CRC_REQUEST, ACK, ACK, ACK, ACK]);
+}
+
+#[test]
+fn can_can_cancels_the_transfer() {
+    let _console = console();
+    let file = test_file(256);
+    let stream = [data_packets(&file, 128)[0].clone(), vec![CAN, CAN]].concat();
+    let mut buffer = vec![0u8; 4096];
+    let (result, replies) = receive(Protocol::Xmodem, &stream, &mut buffer);
+    assert!(matches!(result, Err(TransferError::Cancelled)), "{:?}", result);
+    assert_eq!(replies, [CRC_REQUEST, ACK]);
+}
+
+#[test]
+fn ymodem_file_is_received_with_exact_size_and_name() {
+    let _console = console();
+    let file = test_file(1500);
+    let stream = [file_info("images/kernel.bin", file.len()), data_packets(&file, 1024).concat(), ymodem_end()].concat();
+    let mut buffer = vec![0u8; 4096];
+    let (result, replies) = receive(Protocol::Ymodem, &stream, &mut buffer);
+    let transfer = result.unwrap();
+    assert_eq!(transfer.size, 1500);
+    assert_eq!(transfer.name(), "kernel.bin");
+    assert_eq!(&buffer[..1500], &file[..]);
+    // Bilgi paketi ACK + veri için C; iki paket; EOT'ye NAK, tekrarına ACK; bitiş için C ve ACK.
+    assert_eq!(replies, [CRC_REQUEST, ACK, CRC_REQUEST, ACK, ACK, NAK, ACK, CRC_REQUEST, ACK]);
+}
+
+#[test]
+fn ymodem_file_larger_than_buffer_is_cancelled() {
+    let _console = console();
+    let file = test_file(5000);
+    let stream = [file_info("big.bin", file.len()), data_packets(&file, 1024).concat(), ymodem_end()].concat();
+    let mut buffer = vec![0u8; 4096];
+    let (result, replies) = receive(Protocol::Ymodem, &stream, &mut buffer);
+    assert!(matches!(result, Err(TransferError::FileTooLarge)), "{:?}", result);
+    // Bildirilen boyut yeterli: hiçbir veri paketi kabul edilmeden iptal edilir.
+    assert_eq!(replies, [CRC_REQUEST, CAN, CAN, CAN]);
+}
+
+#[test]
+fn ymodem_file_without_size_overflowing_buffer_is_cancelled() {
+    let _console = console();
+    let file = test_file(5000);
+    let stream = [packet(0, b"big.bin\0", 128, 0), data_packets(&file, 1024).concat(), ymodem_end()].concat();
+    let mut buffer = vec![0u8; 4096];
+    let (result, replies) = receive(Protocol::Ymodem, &stream, &mut buffer);
+    assert!(matches!(result, Err(TransferError::FileTooLarge)), "{:?}", result);
+    assert_eq!(replies, [CRC_REQUEST, ACK, CRC_REQUEST, ACK, ACK, ACK, ACK, CAN, CAN, CAN]);
+}
+
+#[test]
+fn truncated_ymodem_file_is_reported() {
+    let _console = console();
+    let file = test_file(3000);
+    // Gönderici 3000 bayt bildirip sadece ilk iki paketi (2048 bayt) gönderdi.
+    let stream = [file_info("short.bin", file.len()), data_packets(&file[..2048], 1024).concat(), ymodem_end()].concat();
+    let mut buffer = vec![0u8; 4096];
+    let (result, _) = receive(Protocol::Ymodem, &stream, &mut buffer);
+    assert!(matches!(result, Err(TransferError::Truncated)), "{:?}", result);
+}
+
+#[test]
+fn ymodem_name_without_terminator_is_rejected() {
+    let _console = console();
+    // Ad 128 baytlık paketin tamamını kaplıyor: arabelleğin paketten sonraki kısmı okunmamalı.
+    let stream = packet(0, &[b'a'; 128], 128, 0);
+    let mut buffer = vec![0u8; 4096];
+    let (result, replies) = receive(Protocol::Ymodem, &stream, &mut buffer);
+    assert!(matches!(result, Err(TransferError::InvalidHeader)), "{:?}", result);
+    assert_eq!(replies, [CRC_REQUEST, CAN, CAN, CAN]);
+}
@@ -0,0 +1,75 @@
+#![no_std]
+
+// Zaman kaynağı: CLINT'in serbest çalışan 64 bitlik `mtime` sayacı.
+// Host simülasyonunda sayaç yerine süreç başlangıcından beri geçen süre kullanılır.
+
+#[cfg(not(feature = "hosted"))]
+use crate::mmio::Mmio;
+use crate::mmio::RegisterBus;
+
+pub const CLINT_BASE: usize = 0x0200_0000; // SiFive S21 CLINT Base Adresi - VERİ SAYFASINDAN BULUN!
+pub const CLINT_MTIME: usize = 0xBFF8; // mtime Register Ofseti (alt 32 bit; üst 32 bit +4)
+// mtime sayacının frekansı (device tree'de timebase-frequency) - VERİ SAYFASINDAN BULUN!
+pub const TIMEBASE_FREQUENCY_HZ: u32 = 1_000_000;
+
+/// 64 bitlik mtime değerini 32 bitlik erişimlerle okur.
+/// Alt yarı okunurken üst yarı değişirse (taşma) okuma tekrarlanır.
+/// # Safety
+/// Gerçek donanım arka ucu ile yazmaçlara doğrudan erişir.
+pub unsafe fn read_mtime_with<B: RegisterBus>(bus: &mut B) -> u64 {
+    loop {
+        let high = bus.read32(CLINT_BASE + CLINT_MTIME + 4);
+        let low = bus.read32(CLINT_BASE + CLINT_MTIME);
+        if bus.read32(CLINT_BASE + CLINT_MTIME + 4) == high {
+            return ((high as u64) << 32) | low as u64;
+        }
+    }
+}
+
+/// Geçerli sayaç değeri (TIMEBASE_FREQUENCY_HZ birimiyle).
+#[cfg(not(feature = "hosted"))]
+pub fn ticks() -> u64 {
+    unsafe { read_mtime_with(&mut Mmio) }
+}
+
+/// Host simülasyonu: süreç başlangıcından beri geçen süre, aynı birimle.
+#[cfg(feature = "hosted")]
+pub fn ticks() -> u64 {
+    crate::hosted::elapsed_micros() * TIMEBASE_FREQUENCY_HZ as u64 / 1_000_000
+}
+
+pub fn millis() -> u64 {
+    ticks() / (TIMEBASE_FREQUENCY_HZ as u64 / 1000)
+}
+
+/// Belirli bir süre sonra dolan zaman sınırı.
+#[derive(Debug, Copy, Clone)]
+pub struct Deadline {
+    end: u64,
+}
+
+impl Deadline {
+    pub fn after_ms(ms: u64) -> Self {
+        Deadline { end: ticks() + ms * (TIMEBASE_FREQUENCY_HZ as u64 / 1000) }
+    }
+
+    pub fn expired(&self) -> bool {
+        ticks() >= self.end
+    }
+}
+
+/// Mikrosaniye çözünürlüğünde meşgul bekleme (en az `us` kadar bekler).
+pub fn delay_us(us: u64) {
+    let end = ticks() + (us * TIMEBASE_FREQUENCY_HZ as u64).div_ceil(1_000_000);
+    while ticks() < end {
+        core::hint::spin_loop();
+    }
+}
+
+/// Meşgul bekleme.
+pub fn delay_ms(ms: u64) {
+    let deadline = Deadline::after_ms(ms);
+    while !deadline.expired() {
+        core::hint::spin_loop();
+    }
+}
@@ -1,130 +1,549 @@
-#![no_std]
-
-use core::fmt::Write;
-
-// SiFive S21 UART0 Base Adresi - VERİ SAYFASINDAN BULUN!
-pub const UART0_BASE_ADDRESS: usize = 0x1000_0000; // Örnek adres - Doğrulayın!
-
-// UART0 register ofsetleri - VERİ SAYFASINDAN BULUN!
-pub const UART_TXDATA: usize = 0x00;
-pub const UART_RXDATA: usize = 0x04;
-pub const UART_TXCTRL: usize = 0x08;
-pub const UART_RXCTRL: usize = 0x0C;
-pub const UART_IE: usize = 0x10; // Interrupt Enable
-pub const UART_IP: usize = 0x14; // Interrupt Pending
-pub const UART_SCALECFG: usize = 0x18; // Baud Rate Divisor
-
-// UART kontrol bitleri (TXCTRL) - VERİ SAYFASINDAN BULUN!
-pub const UART_TXEN: u32 = 1 << 0; // Transmit Enable
-pub const UART_RXEN: u32 = 1 << 0; // Receive Enable (RXCTRL'de)
-
-
-// Baud Rate Bölücü Yapılandırma Değeri - SİSTEM SAATİNE GÖRE HESAPLAYIN!
-// SCALECFG = (Clock Frequency / Baud Rate) - 1
-// Örnek: 10 MHz saat ve 115200 baud rate için ~86
-pub const UART_BAUD_DIVISOR: u32 = 86; // Örnek değer - Doğrulayın!
-
-
-pub struct Uart0 {
-    base_address: usize,
-}
-
-impl Uart0 {
-    pub const fn new(base_address: usize) -> Self {
-        Uart0 { base_address }
-    }
-
-    /// UART0 donanımını başlatır.
-    /// # Safety
-    /// Donanım yazmaçlarına doğrudan eriştiği için "unsafe"dir.
-    pub unsafe fn init(&mut self) { // Result<(), Error> döndürebilir
-        // --- GERÇEK BAŞLATMA KODU BURAYA GELECEK ---
-        // SiFive S21 UART0 çevre birimini yapılandırın.
-
-        let txctrl_ptr = (self.base_address + UART_TXCTRL) as *mut u32;
-        let rxctrl_ptr = (self.base_address + UART_RXCTRL) as *mut u32;
-        let scalecfg_ptr = (self.base_address + UART_SCALECFG) as *mut u32;
-
-        // 1. Baud rate bölücüyü ayarla.
-        // BU DEĞER KULLANDIĞINIZ SİSTEM SAATİNE VE İSTEDİĞİNİZ BAUD RATE'E GÖRE HESAPLANMALIDIR!
-        scalecfg_ptr.write_volatile(UART_BAUD_DIVISOR);
-
-        // 2. İletim (TX) ve Alma (RX) birimlerini etkinleştir.
-        // TXCTRL register'ının formatını SiFive belgelerinden kontrol edin.
-        let mut tx_ctrl_val = txctrl_ptr.read_volatile();
-        tx_ctrl_val |= UART_TXEN; // TX etkinleştir
-        txctrl_ptr.write_volatile(tx_ctrl_val);
-
-        // RXCTRL register'ının formatını SiFive belgelerinden kontrol edin.
-        let mut rx_ctrl_val = rxctrl_ptr.read_volatile();
-        rx_ctrl_val |= UART_RXEN; // RX etkinleştir
-        rxctrl_ptr.write_volatile(rx_ctrl_val);
-
-
-        // 3. Diğer ayarlar (Data bits, Parity, Stop bits)
-        // Bunlar genellikle TXCTRL/RXCTRL yazmaçlarının diğer bitleriyle ayarlanır.
-        // SiFive belgelerinden bu yazmaçların formatını kontrol edin ve gerekirse ayarlayın.
-         tx_ctrl_val |= (1 << 1); // Varsayımsal 9 data bit ayari
-         txctrl_ptr.write_volatile(tx_ctrl_val);
-    }
-
-    /// UART'a bir bayt gönderir. İletim tamponu doluysa bekler (blocking).
-    /// # Safety
-    /// Donanım yazmacına doğrudan yazıldığı için "unsafe"dir.
-    pub unsafe fn putc(&mut self, byte: u8) {
-        let txdata_ptr = (self.base_address + UART_TXDATA) as *mut u32;
-
-        // TXDATA yazmacının dolu (full) olup olmadığını kontrol et.
-        // SiFive UART'ta TXDATA'nın bit 31'i dolu ise 1'dir. Boş olana kadar bekle.
-         while (txdata_ptr.read_volatile() & (1 << 31)) != 0 {
-             // Bekle
-         }
-
-        // Veriyi gönder (alt 8 bit kullanılır).
-        txdata_ptr.write_volatile(byte as u32);
-    }
-
-    /// UART'tan bir bayt okur, tampon boşsa hemen None döner (non-blocking).
-    /// # Safety
-    /// Donanım yazmacından doğrudan okunduğu için "unsafe"dir.
-    pub unsafe fn read_byte(&mut self) -> Option<u8> {
-        let rxdata_ptr = (self.base_address + UART_RXDATA) as *mut u32;
-
-        // RXDATA yazmacının boş (empty) olup olmadığını kontrol et (bit 31).
-        if (rxdata_ptr.read_volatile() & (1 << 31)) == 0 {
-            // Veri var, oku (okumak register'daki veriyi temizler) ve Some(byte) olarak döndür.
-            Some((rxdata_ptr.read_volatile() & 0xFF) as u8)
-        } else {
-            // Tampon boş, None döndür.
-            None
-        }
-    }
-
-    /// UART'tan bir bayt okur. Alma tamponu boşsa bekler (blocking).
-    /// # Safety
-    /// Donanım yazmacından doğrudan okunduğu için "unsafe"dir.
-     pub unsafe fn getc(&mut self) -> u8 {
-        let rxdata_ptr = (self.base_address + UART_RXDATA) as *mut u32;
-
-        // RXDATA yazmacının boş (empty) olup olmadığını kontrol et (bit 31).
-        while (rxdata_ptr.read_volatile() & (1 << 31)) != 0 {
-            // Bekle
-        }
-
-        // Veriyi oku (okumak register'daki veriyi temizler) ve alt 8 bitini döndür.
-        (rxdata_ptr.read_volatile() & 0xFF) as u8
-    }
-}
-
-impl Write for Uart0 {
-    fn write_str(&mut self, s: &str) -> core::fmt::Result {
-        unsafe {
-            for byte in s.as_bytes() {
-                self.putc(*byte);
-            }
-        }
-        Ok(())
-    }
-}
-
-pub static mut UART0_GLOBAL: Uart0 = Uart0::new(UART0_BASE_ADDRESS);
\ No newline at end of file
+#![no_std]
+
+use core::fmt::{self, Write};
+
+use crate::mmio::{Field, Mmio, RegisterBus};
+use crate::ringbuf::RingBuffer;
+
+// SiFive S21 UART0 Base Adresi - VERİ SAYFASINDAN BULUN!
+pub const UART0_BASE_ADDRESS: usize = 0x1000_0000; // Örnek adres - Doğrulayın!
+
+// UART0 register ofsetleri - VERİ SAYFASINDAN BULUN!
+pub const UART_TXDATA: usize = 0x00;
+pub const UART_RXDATA: usize = 0x04;
+pub const UART_TXCTRL: usize = 0x08;
+pub const UART_RXCTRL: usize = 0x0C;
+pub const UART_IE: usize = 0x10; // Interrupt Enable
+pub const UART_IP: usize = 0x14; // Interrupt Pending
+pub const UART_SCALECFG: usize = 0x18; // Baud Rate Divisor
+
+// UART kontrol bitleri (TXCTRL) - VERİ SAYFASINDAN BULUN!
+pub const UART_TXEN: u32 = 1 << 0; // Transmit Enable
+pub const UART_RXEN: u32 = 1 << 0; // Receive Enable (RXCTRL'de)
+
+// TXDATA/RXDATA durum ve veri alanları
+pub const UART_TXDATA_FULL: u32 = 1 << 31; // TX FIFO dolu
+pub const UART_RXDATA_EMPTY: u32 = 1 << 31; // RX FIFO boş
+pub const UART_DATA: Field = Field::new(0, 8); // Veri baytı
+
+// Kesme bitleri (IE/IP) ve su seviyesi alanları (TXCTRL/RXCTRL) - VERİ SAYFASINDAN BULUN!
+pub const UART_IE_TXWM: u32 = 1 << 0; // TX FIFO'daki bayt sayısı txcnt'nin altında
+pub const UART_IE_RXWM: u32 = 1 << 1; // RX FIFO'daki bayt sayısı rxcnt'nin üstünde
+pub const UART_TXCNT: Field = Field::new(16, 3);
+pub const UART_RXCNT: Field = Field::new(16, 3);
+pub const UART_TX_WATERMARK: u32 = 1; // TX FIFO boşalınca kesme
+pub const UART_RX_WATERMARK: u32 = 0; // RX FIFO'da en az bir bayt olunca kesme
+
+// Kesme modunda kullanılan halka tamponlar (2'nin kuvveti olmalı).
+pub const UART_RX_BUFFER_SIZE: usize = 256;
+pub const UART_TX_BUFFER_SIZE: usize = 1024;
+
+// Baud hızı bölücüsü: baud = saat / (div + 1), div SCALECFG'nin alt 16 bitidir.
+pub const UART_SCALECFG_DIV: Field = Field::new(0, 16);
+// UART0'ın giriş saati (çevre birimi saati) - SİSTEM SAATİNE GÖRE DOĞRULAYIN!
+pub const UART_CLOCK_HZ: u32 = 10_000_000;
+pub const UART_DEFAULT_BAUD: u32 = 115_200;
+// Kabul edilen en büyük baud hatası, yüzde birin yüzde biri (250 = %2.50).
+// Karşı tarafın da hatası olabileceği için 8N1 çerçevenin toleransının (~%5) yarısıdır.
+pub const UART_MAX_BAUD_ERROR_BP: u32 = 250;
+
+#[derive(Debug)]
+pub enum UartError {
+    InvalidBaudRate,        // 0 veya saatle üretilemeyecek kadar yüksek
+    DivisorOutOfRange(u64), // Gereken bölücü SCALECFG alanına sığmıyor (baud çok düşük)
+    BaudErrorTooHigh(i32),  // Elde edilen hız istenenden UART_MAX_BAUD_ERROR_BP'den fazla sapıyor
+}
+
+/// Bir baud hızı için hesaplanan bölücü ve elde edilen gerçek hız.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BaudConfig {
+    pub requested: u32,
+    pub divisor: u32,
+    pub actual: u32,
+    pub error_bp: i32, // (actual - requested) / requested, yüzde birin yüzde biri
+}
+
+impl BaudConfig {
+    /// Bölücüyü en yakın değere yuvarlayarak hesaplar ve sapmayı doğrular.
+    pub const fn compute(clock_hz: u32, baud: u32) -> Result<Self, UartError> {
+        if baud == 0 || baud > clock_hz {
+            return Err(UartError::InvalidBaudRate);
+        }
+        let divider = (clock_hz as u64 + baud as u64 / 2) / baud as u64; // div + 1
+        if divider - 1 > UART_SCALECFG_DIV.mask() as u64 {
+            return Err(UartError::DivisorOutOfRange(divider - 1));
+        }
+        let actual = clock_hz as u64 / divider;
+        let error_bp = ((actual as i64 - baud as i64) * 10_000 / baud as i64) as i32;
+        if error_bp.unsigned_abs() > UART_MAX_BAUD_ERROR_BP {
+            return Err(UartError::BaudErrorTooHigh(error_bp));
+        }
+        Ok(BaudConfig { requested: baud, divisor: (divider - 1) as u32, actual: actual as u32, error_bp })
+    }
+}
+
+impl fmt::Display for BaudConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.error_bp < 0 { "-" } else { "+" };
+        let error = self.error_bp.unsigned_abs();
+        write!(f, "{} baud (divisor {}, actual {}, error {}{}.{:02}%)", self.requested, self.divisor, self.actual, sign, error / 100, error % 100)
+    }
+}
+
+/// `console.baud` değeri için doğrulama: UART0 saatiyle kabul edilebilir hatayla üretilebilmeli.
+pub fn is_valid_baud(value: &str) -> bool {
+    value.parse().is_ok_and(|baud| BaudConfig::compute(UART_CLOCK_HZ, baud).is_ok())
+}
+
+
+// Yazmaç erişimi `RegisterBus` üzerinden yapılır; varsayılan arka uç gerçek MMIO'dur.
+// Testlerde `mmio::RecordingBus` verilerek yazılan yazmaç dizisi doğrulanabilir.
+//
+// `enable_interrupts` çağrıldıktan sonra UART kesme ile çalışır: RX kesmesi FIFO'daki baytları `rx`
+// tamponuna aktarır, `read_byte` tampondan okur; `putc` baytı `tx` tamponuna koyar ve TX kesmesi FIFO
+// boşaldıkça gönderir. Böylece uzun bir çıktı yazılırken gelen baytlar kaybolmaz. Tamponların
+// üreticisi/tüketicisi kesme işleyicisi ile ana akıştır (bkz. ringbuf.rs); ana akıştaki FIFO ve IE
+// erişimleri kesmeler kapalıyken yapılır.
+pub struct Uart0<B: RegisterBus = Mmio> {
+    base_address: usize,
+    bus: B,
+    clock_hz: u32,
+    baud: BaudConfig,
+    // Kesme modu alanları host simülasyonunda kullanılmaz, ama `handle_interrupt` testlerde
+    // `RecordingBus` ile çalıştırılabilsin diye her iki yapılandırmada da bulunur.
+    interrupts_enabled: bool,
+    rx: RingBuffer<UART_RX_BUFFER_SIZE>,
+    tx: RingBuffer<UART_TX_BUFFER_SIZE>,
+    // RX tamponu dolduğu için atılan bayt sayısı
+    rx_overruns: u32,
+}
+
+impl Uart0<Mmio> {
+    /// `clock_hz` UART'ın giriş saatidir; başlangıç hızı UART_DEFAULT_BAUD'dur.
+    pub const fn new(base_address: usize, clock_hz: u32) -> Self {
+        Self::with_bus(base_address, clock_hz, Mmio)
+    }
+}
+
+impl<B: RegisterBus> Uart0<B> {
+    /// Belirli bir yazmaç arka ucu ile UART oluşturur (örn. testler için `RecordingBus`).
+    /// Saat UART_DEFAULT_BAUD'u üretemiyorsa panikler (global için derleme zamanında).
+    pub const fn with_bus(base_address: usize, clock_hz: u32, bus: B) -> Self {
+        let baud = match BaudConfig::compute(clock_hz, UART_DEFAULT_BAUD) {
+            Ok(baud) => baud,
+            Err(_) => panic!("UART clock cannot produce the default baud rate"),
+        };
+        Uart0 {
+            base_address,
+            bus,
+            clock_hz,
+            baud,
+            interrupts_enabled: false,
+            rx: RingBuffer::new(),
+            tx: RingBuffer::new(),
+            rx_overruns: 0,
+        }
+    }
+
+    /// Yazmaç arka ucuna erişim (testlerde kaydedilen erişimleri incelemek için).
+    pub fn bus(&self) -> &B {
+        &self.bus
+    }
+
+    pub fn clock_hz(&self) -> u32 {
+        self.clock_hz
+    }
+
+    /// Geçerli baud yapılandırması.
+    pub fn baud_config(&self) -> BaudConfig {
+        self.baud
+    }
+
+    /// Baud hızını değiştirir. Önce bekleyen çıktının tamamı gönderilir, sonra bölücü yazılır;
+    /// hesaplanan hız kabul edilemezse hiçbir şey değişmez.
+    /// # Safety
+    /// Donanım yazmaçlarına doğrudan erişir.
+    pub unsafe fn set_baud(&mut self, baud: u32) -> Result<BaudConfig, UartError> {
+        let config = BaudConfig::compute(self.clock_hz, baud)?;
+        #[cfg(not(feature = "hosted"))]
+        {
+            self.flush();
+            self.bus.write_field(self.base_address + UART_SCALECFG, UART_SCALECFG_DIV, config.divisor);
+        }
+        self.baud = config;
+        Ok(config)
+    }
+
+    /// TX tamponu ve FIFO boşalana, son karakter de hattan çıkana kadar bekler.
+    /// # Safety
+    /// Donanım yazmaçlarına doğrudan erişir.
+    #[cfg(not(feature = "hosted"))]
+    pub unsafe fn flush(&mut self) {
+        while !self.tx.is_empty() {
+            core::hint::spin_loop(); // TX kesmesi boşaltır
+        }
+        self.bus.wait_until(self.base_address + UART_IP, |v| v & UART_IE_TXWM != 0);
+        // FIFO boşaldığında son karakter hâlâ kaydırma yazmacındadır (8N1: 10 bit).
+        crate::timer::delay_ms(10_000 / self.baud.actual as u64 + 1);
+    }
+
+    /// UART0 donanımını başlatır.
+    /// # Safety
+    /// Donanım yazmaçlarına doğrudan eriştiği için "unsafe"dir.
+    #[cfg(not(feature = "hosted"))]
+    pub unsafe fn init(&mut self) { // Result<(), Error> döndürebilir
+        self.configure();
+    }
+
+    /// `init`'in yazmaç dizisi: bölücü, TX/RX etkinleştirme ve su seviyeleri. Host simülasyonunda
+    /// `init` bunu çağırmaz, ama testler `RecordingBus` ile yazılan diziyi doğrulayabilsin diye derlenir.
+    /// # Safety
+    /// Donanım yazmaçlarına doğrudan eriştiği için "unsafe"dir.
+    pub unsafe fn configure(&mut self) {
+        // --- GERÇEK BAŞLATMA KODU BURAYA GELECEK ---
+        // SiFive S21 UART0 çevre birimini yapılandırın.
+
+        // 1. Baud rate bölücüyü ayarla (UART saatinden hesaplanmış, bkz. BaudConfig).
+        self.bus.write32(self.base_address + UART_SCALECFG, UART_SCALECFG_DIV.set(0, self.baud.divisor));
+
+        // 2. İletim (TX) ve Alma (RX) birimlerini etkinleştir.
+        // TXCTRL register'ının formatını SiFive belgelerinden kontrol edin.
+        let tx_ctrl_val = self.bus.read32(self.base_address + UART_TXCTRL) | UART_TXEN; // TX etkinleştir
+        self.bus.write32(self.base_address + UART_TXCTRL, tx_ctrl_val);
+
+        // RXCTRL register'ının formatını SiFive belgelerinden kontrol edin.
+        self.bus.set_bits(self.base_address + UART_RXCTRL, UART_RXEN); // RX etkinleştir
+
+
+        // 3. Diğer ayarlar (Data bits, Parity, Stop bits)
+        // SiFive UART'ı sabit 8 data bit, paritesiz çalışır; TXCTRL bit 1 (nstop) stop bit sayısını seçer.
+        // Sıfırlama değeri (0) 1 stop bit demektir, bu yüzden burada yazılacak bir şey yok.
+
+        // 4. Su seviyeleri: TXWM bekleyen biti FIFO'nun boşaldığını gösterir (kesme ve `flush` için).
+        self.bus.write_field(self.base_address + UART_TXCTRL, UART_TXCNT, UART_TX_WATERMARK);
+        self.bus.write_field(self.base_address + UART_RXCTRL, UART_RXCNT, UART_RX_WATERMARK);
+    }
+
+    /// Host simülasyonunda UART0 stdin/stdout'a bağlanır.
+    /// # Safety
+    /// Simülasyon dışında UART yazmaçları yapılandırılacağı için "unsafe"dir.
+    #[cfg(feature = "hosted")]
+    pub unsafe fn init(&mut self) {
+        crate::hosted::console_init();
+    }
+
+    /// Kesme modunu açar: RX kesmesini etkinleştirir (su seviyeleri `init`'te ayarlanır).
+    /// Kesmenin hart'a ulaşması için PLIC de yapılandırılmalıdır (`plic::init`).
+    /// # Safety
+    /// Donanım yazmaçlarına doğrudan erişir; kesme işleyicisi `handle_interrupt`'ı çağırmalıdır.
+    #[cfg(not(feature = "hosted"))]
+    pub unsafe fn enable_interrupts(&mut self) {
+        self.interrupts_enabled = true;
+        self.bus.set_bits(self.base_address + UART_IE, UART_IE_RXWM);
+    }
+
+    /// Yoklama (polling) moduna döner: UART kesmelerini kapatır ve TX tamponunda bekleyenleri gönderir.
+    /// Panik/istisna işleyicilerinde ve kontrol işletim sistemine devredilmeden önce çağrılır.
+    /// # Safety
+    /// Donanım yazmaçlarına doğrudan erişir.
+    #[cfg(not(feature = "hosted"))]
+    pub unsafe fn disable_interrupts(&mut self) {
+        riscv::interrupt::free(|| {
+            self.bus.clear_bits(self.base_address + UART_IE, UART_IE_RXWM | UART_IE_TXWM);
+            self.interrupts_enabled = false;
+        });
+        while let Some(byte) = self.tx.pop() {
+            self.putc_polled(byte);
+        }
+    }
+
+    /// Host simülasyonunda konsol zaten arka plan thread'i ile tamponlanır; kesme modu yoktur.
+    /// # Safety
+    /// Simülasyon dışında UART kesme yazmaçlarına yazılacağı için "unsafe"dir.
+    #[cfg(feature = "hosted")]
+    pub unsafe fn enable_interrupts(&mut self) {}
+
+    /// Host simülasyonunda kapatılacak kesme modu yoktur; hiçbir şey yapmaz.
+    /// # Safety
+    /// Simülasyon dışında UART kesme yazmaçlarına yazılacağı için "unsafe"dir.
+    #[cfg(feature = "hosted")]
+    pub unsafe fn disable_interrupts(&mut self) {}
+
+    /// UART kesme işleyicisi: RX FIFO'yu tampona aktarır, TX FIFO boşaldıysa tampondan doldurur.
+    /// # Safety
+    /// Sadece kesme bağlamından (veya kesmeler kapalıyken) çağrılmalıdır.
+    pub unsafe fn handle_interrupt(&mut self) {
+        if !self.interrupts_enabled {
+            return; // Yoklama moduna geçildi; tamponların tek tüketicisi artık ana akış
+        }
+        while let Some(byte) = self.read_fifo() {
+            if !self.rx.push(byte) {
+                self.rx_overruns = self.rx_overruns.wrapping_add(1);
+            }
+        }
+        if self.bus.read32(self.base_address + UART_IP) & UART_IE_TXWM != 0 {
+            self.pump_tx();
+        }
+    }
+
+    // TX FIFO dolana veya tampon boşalana kadar gönderir; tampon boşalınca TX kesmesi kapatılır.
+    unsafe fn pump_tx(&mut self) {
+        while self.bus.read32(self.base_address + UART_TXDATA) & UART_TXDATA_FULL == 0 {
+            match self.tx.pop() {
+                Some(byte) => self.bus.write32(self.base_address + UART_TXDATA, UART_DATA.set(0, byte as u32)),
+                None => {
+                    self.bus.clear_bits(self.base_address + UART_IE, UART_IE_TXWM);
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Kesme modunda RX tamponu dolduğu için atılan bayt sayısı (host simülasyonunda her zaman 0).
+    pub fn rx_overruns(&self) -> u32 {
+        self.rx_overruns
+    }
+
+    /// Okunmayı bekleyen bayt var mı (ana döngü WFI'dan önce bakar).
+    /// # Safety
+    /// Donanım yazmacından doğrudan okunabildiği için "unsafe"dir.
+    #[cfg(not(feature = "hosted"))]
+    pub unsafe fn has_input(&mut self) -> bool {
+        if self.interrupts_enabled {
+            !self.rx.is_empty()
+        } else {
+            false // Yoklama modunda FIFO okunmadan bilinemez; çağıran tekrar yoklamalıdır
+        }
+    }
+
+    /// UART'a bir bayt gönderir. Kesme modunda bayt TX tamponuna konur; tampon doluysa FIFO
+    /// doğrudan doldurularak yer açılır. Yoklama modunda FIFO'da yer olana kadar bekler (blocking).
+    /// # Safety
+    /// Donanım yazmacına doğrudan yazıldığı için "unsafe"dir.
+    #[cfg(not(feature = "hosted"))]
+    pub unsafe fn putc(&mut self, byte: u8) {
+        if !self.interrupts_enabled {
+            self.putc_polled(byte);
+            return;
+        }
+        while !self.tx.push(byte) {
+            riscv::interrupt::free(|| self.pump_tx());
+        }
+        riscv::interrupt::free(|| self.bus.set_bits(self.base_address + UART_IE, UART_IE_TXWM));
+    }
+
+    #[cfg(not(feature = "hosted"))]
+    unsafe fn putc_polled(&mut self, byte: u8) {
+        // TXDATA yazmacının dolu (full) olup olmadığını kontrol et.
+        // SiFive UART'ta TXDATA'nın bit 31'i dolu ise 1'dir. Boş olana kadar bekle.
+        self.bus.wait_until(self.base_address + UART_TXDATA, |v| v & UART_TXDATA_FULL == 0);
+
+        // Veriyi gönder (alt 8 bit kullanılır).
+        self.bus.write32(self.base_address + UART_TXDATA, UART_DATA.set(0, byte as u32));
+    }
+
+    /// Host simülasyonu: baytı konsola (stdout veya yakalama arabelleği) yazar.
+    /// # Safety
+    /// Simülasyon dışında TX yazmacına yazılacağı için "unsafe"dir.
+    #[cfg(feature = "hosted")]
+    pub unsafe fn putc(&mut self, byte: u8) {
+        crate::hosted::console_write_byte(byte);
+    }
+
+    /// UART'tan bir bayt okur, tampon boşsa hemen None döner (non-blocking).
+    /// Kesme modunda RX tamponundan, yoklama modunda doğrudan FIFO'dan okur.
+    /// # Safety
+    /// Donanım yazmacından doğrudan okunduğu için "unsafe"dir.
+    #[cfg(not(feature = "hosted"))]
+    pub unsafe fn read_byte(&mut self) -> Option<u8> {
+        if self.interrupts_enabled {
+            return self.rx.pop();
+        }
+        self.read_fifo()
+    }
+
+    unsafe fn read_fifo(&mut self) -> Option<u8> {
+        // RXDATA yazmacının boş (empty) olup olmadığını kontrol et (bit 31).
+        // Okuma FIFO'dan baytı çektiği için yazmaç tek sefer okunur.
+        let rxdata = self.bus.read32(self.base_address + UART_RXDATA);
+        if (rxdata & UART_RXDATA_EMPTY) == 0 {
+            // Veri var, Some(byte) olarak döndür.
+            Some(UART_DATA.get(rxdata) as u8)
+        } else {
+            // Tampon boş, None döndür.
+            None
+        }
+    }
+
+    /// Host simülasyonu: konsol giriş kuyruğundan bir bayt alır (non-blocking).
+    /// # Safety
+    /// Simülasyon dışında RX yazmacı okunacağı için "unsafe"dir.
+    #[cfg(feature = "hosted")]
+    pub unsafe fn read_byte(&mut self) -> Option<u8> {
+        crate::hosted::console_read_byte()
+    }
+
+    /// UART'tan bir bayt okur. Alma tamponu boşsa bekler (blocking).
+    /// # Safety
+    /// Donanım yazmacından doğrudan okunduğu için "unsafe"dir.
+    #[cfg(not(feature = "hosted"))]
+     pub unsafe fn getc(&mut self) -> u8 {
+        loop {
+            if let Some(byte) = self.read_byte() {
+                return byte;
+            }
+        }
+    }
+
+    /// Host simülasyonu: konsolda bayt olana kadar bekler.
+    /// Giriş kapanırsa (stdin EOF) simülasyon sonlandırılır.
+    /// # Safety
+    /// Simülasyon dışında RX yazmacı okunacağı için "unsafe"dir.
+    #[cfg(feature = "hosted")]
+    pub unsafe fn getc(&mut self) -> u8 {
+        loop {
+            if let Some(byte) = crate::hosted::console_read_byte() {
+                return byte;
+            }
+            if crate::hosted::console_closed() {
+                crate::hosted::exit(0);
+            }
+            std::thread::yield_now();
+        }
+    }
+}
+
+impl<B: RegisterBus> Write for Uart0<B> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        unsafe {
+            for byte in s.as_bytes() {
+                self.putc(*byte);
+            }
+        }
+        Ok(())
+    }
+}
+
+pub static mut UART0_GLOBAL: Uart0 = Uart0::new(UART0_BASE_ADDRESS, UART_CLOCK_HZ);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mmio::RecordingBus;
+
+    const BASE: usize = UART0_BASE_ADDRESS;
+
+    // RecordingBus'a RX/TX FIFO davranışı ekler: RXDATA okuması kuyruktan bayt çeker (boşsa EMPTY),
+    // TXDATA `tx_room` bayt kabul ettikten sonra FULL döner. Diğer yazmaçlar RecordingBus'a gider.
+    struct FifoBus {
+        registers: RecordingBus,
+        rx: std::collections::VecDeque<u8>,
+        tx_room: usize,
+        sent: Vec<u8>,
+    }
+
+    impl FifoBus {
+        fn new(rx: &[u8], tx_room: usize) -> Self {
+            FifoBus { registers: RecordingBus::new(), rx: rx.iter().copied().collect(), tx_room, sent: Vec::new() }
+        }
+    }
+
+    impl RegisterBus for FifoBus {
+        unsafe fn read32(&mut self, addr: usize) -> u32 {
+            match addr - BASE {
+                UART_RXDATA => self.rx.pop_front().map_or(UART_RXDATA_EMPTY, u32::from),
+                UART_TXDATA => if self.tx_room == 0 { UART_TXDATA_FULL } else { 0 },
+                _ => self.registers.read32(addr),
+            }
+        }
+
+        unsafe fn write32(&mut self, addr: usize, value: u32) {
+            if addr - BASE == UART_TXDATA {
+                self.sent.push(UART_DATA.get(value) as u8);
+                self.tx_room -= 1;
+            } else {
+                self.registers.write32(addr, value);
+            }
+        }
+    }
+
+    fn interrupt_uart(bus: FifoBus) -> Uart0<FifoBus> {
+        let mut uart = Uart0::with_bus(BASE, UART_CLOCK_HZ, bus);
+        uart.interrupts_enabled = true;
+        uart
+    }
+
+    #[test]
+    fn configure_writes_divisor_enables_and_watermarks_in_order() {
+        let mut uart = Uart0::with_bus(BASE, UART_CLOCK_HZ, RecordingBus::new());
+        unsafe { uart.configure() };
+        let writes: Vec<_> = uart.bus().writes().collect();
+        assert_eq!(writes, [
+            (BASE + UART_SCALECFG, 86), // 10 MHz / 115200 -> div + 1 = 87
+            (BASE + UART_TXCTRL, UART_TXEN),
+            (BASE + UART_RXCTRL, UART_RXEN),
+            (BASE + UART_TXCTRL, UART_TXEN | (UART_TX_WATERMARK << 16)),
+            (BASE + UART_RXCTRL, UART_RXEN | (UART_RX_WATERMARK << 16)),
+        ]);
+        assert!(!uart.bus().overflowed());
+    }
+
+    #[test]
+    fn configure_keeps_bits_already_set_in_the_control_registers() {
+        let mut bus = RecordingBus::new();
+        bus.preset(BASE + UART_RXCTRL, 1 << 4);
+        let mut uart = Uart0::with_bus(BASE, UART_CLOCK_HZ, bus);
+        unsafe { uart.configure() };
+        let last_rxctrl = uart.bus().writes().filter(|(addr, _)| *addr == BASE + UART_RXCTRL).last();
+        assert_eq!(last_rxctrl, Some((BASE + UART_RXCTRL, (1 << 4) | UART_RXEN)));
+    }
+
+    #[test]
+    fn baud_config_rejects_unreachable_rates() {
+        assert!(matches!(BaudConfig::compute(UART_CLOCK_HZ, 0), Err(UartError::InvalidBaudRate)));
+        assert!(matches!(BaudConfig::compute(UART_CLOCK_HZ, 100), Err(UartError::DivisorOutOfRange(_))));
+        assert!(matches!(BaudConfig::compute(UART_CLOCK_HZ, 3_000_000), Err(UartError::BaudErrorTooHigh(_))));
+        assert_eq!(BaudConfig::compute(UART_CLOCK_HZ, 115_200).unwrap().divisor, 86);
+    }
+
+    #[test]
+    fn interrupt_moves_rx_fifo_into_buffer_and_counts_overruns() {
+        let input: Vec<u8> = (0..UART_RX_BUFFER_SIZE + 3).map(|i| i as u8).collect();
+        let mut uart = interrupt_uart(FifoBus::new(&input, 0));
+        unsafe { uart.handle_interrupt() };
+        assert!(uart.bus().rx.is_empty()); // FIFO tamamen boşaltılır
+        assert_eq!(uart.rx_overruns(), 3);
+        let received: Vec<u8> = core::iter::from_fn(|| uart.rx.pop()).collect();
+        assert_eq!(received, input[..UART_RX_BUFFER_SIZE]);
+    }
+
+    #[test]
+    fn interrupt_refills_tx_fifo_and_disables_txwm_once_drained() {
+        let mut bus = FifoBus::new(&[], 2);
+        bus.registers.preset(BASE + UART_IP, UART_IE_TXWM);
+        bus.registers.preset(BASE + UART_IE, UART_IE_RXWM | UART_IE_TXWM);
+        let mut uart = interrupt_uart(bus);
+        for byte in b"abc" {
+            uart.tx.push(*byte);
+        }
+
+        // FIFO'da iki baytlık yer var: kesme açık kalır, kalan bayt sonraki kesmeyi bekler.
+        unsafe { uart.handle_interrupt() };
+        assert_eq!(uart.bus().sent, b"ab");
+        assert_eq!(uart.bus().registers.writes().count(), 0);
+
+        uart.bus.tx_room = 8;
+        unsafe { uart.handle_interrupt() };
+        assert_eq!(uart.bus().sent, b"abc");
+        let writes: Vec<_> = uart.bus().registers.writes().collect();
+        assert_eq!(writes, [(BASE + UART_IE, UART_IE_RXWM)]);
+    }
+
+    #[test]
+    fn interrupt_is_ignored_in_polling_mode() {
+        let mut uart = Uart0::with_bus(BASE, UART_CLOCK_HZ, FifoBus::new(b"x", 1));
+        unsafe { uart.handle_interrupt() };
+        assert_eq!(uart.bus().rx.len(), 1);
+        assert!(uart.rx.is_empty());
+    }
+}
@@ -0,0 +1,382 @@
+#![no_std]
+
+// Firmware'in kendini güncellemesi.
+// eMMC'nin ayrılmış bölgesinde (NVRAM'den sonra) iki firmware bankası vardır. Her bankanın ilk bloğu
+// tanımlayıcıdır, imaj (PacketBox başlığı + yük) ikinci bloktan başlar:
+//
+//   0x00  magic        [u8; 4]  "PBFW"
+//   0x04  version      u32
+//   0x08  sequence     u32      Her güncellemede bir artar
+//   0x0C  image_size   u32      Başlık + yük bayt sayısı
+//   0x10  image_crc32  u32      İmajın tamamı üzerinden CRC-32
+//   0x14  crc32        u32      İlk 20 bayt üzerinden CRC-32
+//
+// Açılışta ilk aşama yükleyici, tanımlayıcısı ve imaj CRC'si geçerli bankalardan sıra numarası en
+// yeni olanı çalıştırır. Sıra numarası taşabileceği için seri sayı aritmetiğiyle karşılaştırılır
+// (RFC 1982): `a.wrapping_sub(b) as i32 > 0` ise a daha yenidir. Güncelleme her zaman etkin OLMAYAN bankaya yazılır (hazırlık alanı): önce
+// tanımlayıcı silinir, imaj yazılıp geri okunarak doğrulanır, yeni tanımlayıcı en son yazılır. Bu
+// noktadan önce güç kesilirse veya bir adım başarısız olursa eski firmware etkin kalır.
+//
+// Kabul edilen imajlar IMAGE_FLAG_FIRMWARE taşımalı, yük CRC'si tutmalı ve güvenli boot politikası
+// `off` değilse güvenilir bir anahtarla imzalanmış olmalıdır (`warn` firmware için yeterli değildir).
+
+use crate::crc::{crc32, Crc32};
+use crate::image::{ImageError, ImageHeader};
+use crate::memory::FIRMWARE_RESERVED_END;
+use crate::partition;
+use crate::secureboot::{self, SecureBootError, SecureBootPolicy};
+use crate::storage::{BlockDevice, StorageError, BLOCK_SIZE, EMMC_FIRMWARE_BANK_BLOCKS, EMMC_FIRMWARE_START_LBA};
+use crate::uart::Uart0;
+use crate::xmodem::{self, Protocol, TransferError};
+
+pub const FIRMWARE_BANK_COUNT: u64 = 2;
+// Tanımlayıcı bloğundan sonra bankaya sığan en büyük imaj.
+pub const FIRMWARE_MAX_IMAGE_BYTES: usize = (EMMC_FIRMWARE_BANK_BLOCKS as usize - 1) * BLOCK_SIZE;
+const DESCRIPTOR_MAGIC: [u8; 4] = *b"PBFW";
+const DESCRIPTOR_VERSION: u32 = 1;
+
+// Alınan imaj doğrulanıp yazılana kadar kullanılabilir RAM'in başında tutulur.
+const STAGING_RAM_ADDRESS: usize = FIRMWARE_RESERVED_END;
+
+#[derive(Debug)]
+pub enum UpdateError {
+    Storage(StorageError),
+    NotAvailable,     // eMMC yok veya bankalar için yeterli blok yok
+    RegionInUse,      // Bankalar bir bölümle çakışıyor
+    Transfer(TransferError),
+    Image(ImageError),
+    NotFirmware,      // İmaj IMAGE_FLAG_FIRMWARE taşımıyor
+    TooLarge,         // İmaj bankaya sığmıyor
+    SecureBoot(SecureBootError),
+    VerifyFailed,     // Geri okunan veri yazılanla aynı değil
+}
+
+impl From<StorageError> for UpdateError {
+    fn from(err: StorageError) -> Self { UpdateError::Storage(err) }
+}
+
+impl From<TransferError> for UpdateError {
+    fn from(err: TransferError) -> Self { UpdateError::Transfer(err) }
+}
+
+impl From<ImageError> for UpdateError {
+    fn from(err: ImageError) -> Self { UpdateError::Image(err) }
+}
+
+impl From<SecureBootError> for UpdateError {
+    fn from(err: SecureBootError) -> Self { UpdateError::SecureBoot(err) }
+}
+
+/// Geçerli bir bankanın tanımlayıcısı.
+#[derive(Debug, Copy, Clone)]
+pub struct BankInfo {
+    pub sequence: u32,
+    pub image_size: u32,
+    pub image_crc32: u32,
+}
+
+/// Tamamlanan güncelleme.
+#[derive(Debug, Copy, Clone)]
+pub struct Installed {
+    pub bank: u64,
+    pub sequence: u32,
+    pub header: ImageHeader,
+}
+
+fn le_u32(b: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([b[offset], b[offset + 1], b[offset + 2], b[offset + 3]])
+}
+
+fn bank_start_lba(bank: u64) -> u64 {
+    EMMC_FIRMWARE_START_LBA + bank * EMMC_FIRMWARE_BANK_BLOCKS
+}
+
+fn read_descriptor<D: BlockDevice + ?Sized>(device: &mut D, bank: u64) -> Result<Option<BankInfo>, UpdateError> {
+    let mut block = [0u8; BLOCK_SIZE];
+    device.read_block(bank_start_lba(bank), &mut block)?;
+    if block[0..4] != DESCRIPTOR_MAGIC || le_u32(&block, 4) != DESCRIPTOR_VERSION || crc32(&block[0..20]) != le_u32(&block, 20) {
+        return Ok(None);
+    }
+    let info = BankInfo { sequence: le_u32(&block, 8), image_size: le_u32(&block, 12), image_crc32: le_u32(&block, 16) };
+    if info.image_size as usize > FIRMWARE_MAX_IMAGE_BYTES {
+        return Ok(None);
+    }
+    Ok(Some(info))
+}
+
+fn write_descriptor<D: BlockDevice + ?Sized>(device: &mut D, bank: u64, info: Option<BankInfo>) -> Result<(), UpdateError> {
+    let mut block = [0u8; BLOCK_SIZE];
+    if let Some(info) = info {
+        block[0..4].copy_from_slice(&DESCRIPTOR_MAGIC);
+        block[4..8].copy_from_slice(&DESCRIPTOR_VERSION.to_le_bytes());
+        block[8..12].copy_from_slice(&info.sequence.to_le_bytes());
+        block[12..16].copy_from_slice(&info.image_size.to_le_bytes());
+        block[16..20].copy_from_slice(&info.image_crc32.to_le_bytes());
+        let crc = crc32(&block[0..20]);
+        block[20..24].copy_from_slice(&crc.to_le_bytes());
+    }
+    device.write_block(bank_start_lba(bank), &block)?;
+    Ok(())
+}
+
+// Bankadaki imajın CRC'sini tanımlayıcıyla karşılaştırır.
+fn image_matches<D: BlockDevice + ?Sized>(device: &mut D, bank: u64, info: &BankInfo) -> Result<bool, UpdateError> {
+    let mut block = [0u8; BLOCK_SIZE];
+    let mut crc = Crc32::new();
+    let mut remaining = info.image_size as usize;
+    let mut lba = bank_start_lba(bank) + 1;
+    while remaining > 0 {
+        device.read_block(lba, &mut block)?;
+        let take = core::cmp::min(remaining, BLOCK_SIZE);
+        crc.update(&block[..take]);
+        remaining -= take;
+        lba += 1;
+    }
+    Ok(crc.finish() == info.image_crc32)
+}
+
+/// Bankaların durumu: tanımlayıcısı ve imaj CRC'si geçerli olanlar Some.
+pub fn read_banks<D: BlockDevice + ?Sized>(device: &mut D) -> Result<[Option<BankInfo>; FIRMWARE_BANK_COUNT as usize], UpdateError> {
+    check_region_free(device)?;
+    let mut banks = [None; FIRMWARE_BANK_COUNT as usize];
+    for bank in 0..FIRMWARE_BANK_COUNT {
+        if let Some(info) = read_descriptor(device, bank)? {
+            if image_matches(device, bank, &info)? {
+                banks[bank as usize] = Some(info);
+            }
+        }
+    }
+    Ok(banks)
+}
+
+// Sıra numarası taşmasına dayanıklı karşılaştırma: `a`, `b`'den sonra mı yazıldı.
+fn is_newer(a: u32, b: u32) -> bool {
+    a.wrapping_sub(b) as i32 > 0
+}
+
+/// Açılışta çalıştırılacak banka: geçerli bankalardan sıra numarası en yeni olan (eşitlikte ilki).
+pub fn active_bank(banks: &[Option<BankInfo>]) -> Option<u64> {
+    let mut active: Option<(u64, u32)> = None;
+    for (bank, info) in banks.iter().enumerate().filter_map(|(i, b)| b.map(|info| (i as u64, info))) {
+        if active.is_none_or(|(_, sequence)| is_newer(info.sequence, sequence)) {
+            active = Some((bank, info.sequence));
+        }
+    }
+    active.map(|(bank, _)| bank)
+}
+
+/// Alınan imajı doğrular: başlık, firmware bayrağı, boyut, yük CRC'si ve imza.
+/// # Safety
+/// Global güvenli boot politikasını okur.
+pub unsafe fn verify_image(image: &[u8]) -> Result<ImageHeader, UpdateError> {
+    let header = ImageHeader::parse(image)?;
+    if !header.is_firmware() {
+        return Err(UpdateError::NotFirmware);
+    }
+    let header_size = header.header_size as usize;
+    let total = header_size + header.payload_size();
+    if total > FIRMWARE_MAX_IMAGE_BYTES {
+        return Err(UpdateError::TooLarge);
+    }
+    if image.len() < total {
+        return Err(ImageError::Truncated.into());
+    }
+    let payload = &image[header_size..total];
+    if crc32(payload) != header.payload_crc32 {
+        return Err(ImageError::PayloadCrcMismatch.into());
+    }
+    if secureboot::get_policy() == SecureBootPolicy::Off {
+        log!("Secure boot: off, firmware image not verified.");
+    } else {
+        let key = secureboot::verify(&header, &image[..header_size], payload)?;
+        log!("Secure boot: firmware image verified with key {} ({}).", key.id, key.name);
+    }
+    Ok(header)
+}
+
+/// Doğrulanmış imajı etkin olmayan bankaya yazar ve başarılıysa etkin yapar.
+/// `image` başlık + yükten oluşmalıdır (XMODEM dolgusu olmadan).
+pub fn install<D: BlockDevice + ?Sized>(device: &mut D, image: &[u8]) -> Result<(u64, u32), UpdateError> {
+    if image.len() > FIRMWARE_MAX_IMAGE_BYTES {
+        return Err(UpdateError::TooLarge);
+    }
+    let banks = read_banks(device)?;
+    let (target, sequence) = match active_bank(&banks).and_then(|active| banks[active as usize].map(|info| (active, info))) {
+        Some((active, info)) => ((active + 1) % FIRMWARE_BANK_COUNT, info.sequence.wrapping_add(1)),
+        None => (0, 1),
+    };
+
+    // Yazma sırasında hedef bankanın yarım kalmış içerikle geçerli sayılmaması için önce tanımlayıcısı silinir.
+    write_descriptor(device, target, None)?;
+    let mut block = [0u8; BLOCK_SIZE];
+    let mut readback = [0u8; BLOCK_SIZE];
+    for (i, chunk) in image.chunks(BLOCK_SIZE).enumerate() {
+        block.fill(0);
+        block[..chunk.len()].copy_from_slice(chunk);
+        let lba = bank_start_lba(target) + 1 + i as u64;
+        device.write_block(lba, &block)?;
+        device.read_block(lba, &mut readback)?;
+        if readback != block {
+            return Err(UpdateError::VerifyFailed);
+        }
+    }
+
+    let info = BankInfo { sequence, image_size: image.len() as u32, image_crc32: crc32(image) };
+    write_descriptor(device, target, Some(info))?;
+    match read_descriptor(device, target)? {
+        Some(written) if written.sequence == sequence && image_matches(device, target, &written)? => Ok((target, sequence)),
+        _ => Err(UpdateError::VerifyFailed),
+    }
+}
+
+/// Bankalarla çakışan bir bölüm varsa güncellemeyi reddeder (bkz. nvram.rs).
+fn check_region_free<D: BlockDevice + ?Sized>(device: &mut D) -> Result<(), UpdateError> {
+    let region_end = bank_start_lba(FIRMWARE_BANK_COUNT);
+    if device.block_count().is_none_or(|count| count < region_end) {
+        return Err(UpdateError::NotAvailable);
+    }
+    let table = match partition::read_partition_table(device) {
+        Ok(table) => table,
+        Err(_) => return Ok(()),
+    };
+    if table.iter().any(|p| p.start_lba < region_end && p.start_lba + p.block_count > EMMC_FIRMWARE_START_LBA) {
+        return Err(UpdateError::RegionInUse);
+    }
+    Ok(())
+}
+
+/// Global eMMC'deki bankaların durumu.
+/// # Safety
+/// Global eMMC aygıtına erişir.
+pub unsafe fn bank_status() -> Result<[Option<BankInfo>; FIRMWARE_BANK_COUNT as usize], UpdateError> {
+    let device = crate::storage::device_by_name("emic").map_err(|_| UpdateError::NotAvailable)?;
+    read_banks(device)
+}
+
+/// Firmware imajını UART üzerinden alır, doğrular ve global eMMC'ye kurar.
+/// Aktarım bitene kadar UART'a hiçbir şey yazılmaz; çağıran, göndericiyi başlatma talimatını önceden yazmalıdır.
+/// # Safety
+/// UART'a, kullanılabilir RAM'in başına ve global eMMC aygıtına erişir.
+pub unsafe fn receive_and_install(uart: &mut Uart0, protocol: Protocol) -> Result<Installed, UpdateError> {
+    let device = crate::storage::device_by_name("emic").map_err(|_| UpdateError::NotAvailable)?;
+    check_region_free(device)?;
+
+    let staging_ptr = crate::memory::phys_to_mut_ptr(STAGING_RAM_ADDRESS, FIRMWARE_MAX_IMAGE_BYTES).map_err(|_| UpdateError::NotAvailable)?;
+    let staging = core::slice::from_raw_parts_mut(staging_ptr, FIRMWARE_MAX_IMAGE_BYTES);
+    let transfer = xmodem::receive(uart, protocol, staging)?;
+    log!("Received {} bytes{}{}.", transfer.size, if transfer.name().is_empty() { "" } else { " from " }, transfer.name());
+
+    let header = verify_image(&staging[..transfer.size])?;
+    let image = &staging[..header.header_size as usize + header.payload_size()];
+    let (bank, sequence) = install(device, image)?;
+    Ok(Installed { bank, sequence, header })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::image::{IMAGE_FLAG_FIRMWARE, IMAGE_HEADER_MIN_SIZE, IMAGE_MAGIC, IMAGE_VERSION};
+    use crate::mbr::{MBR_PARTITION_TABLE_OFFSET, MBR_SIGNATURE, MBR_SIGNATURE_OFFSET};
+    use crate::memory::LPDDR1_BASE_ADDRESS;
+    use crate::storage::RamDisk;
+
+    // Bankaları içeren en küçük eMMC.
+    const DISK_BLOCKS: usize = (EMMC_FIRMWARE_START_LBA + FIRMWARE_BANK_COUNT * EMMC_FIRMWARE_BANK_BLOCKS) as usize;
+
+    fn disk() -> Vec<u8> {
+        vec![0u8; DISK_BLOCKS * BLOCK_SIZE]
+    }
+
+    // Firmware bayraklı (veya `flags` ile değiştirilmiş) bir imaj: 64 baytlık başlık + yük.
+    fn image(flags: u32, payload: &[u8]) -> Vec<u8> {
+        let mut image = vec![0u8; IMAGE_HEADER_MIN_SIZE];
+        image[0..4].copy_from_slice(&IMAGE_MAGIC);
+        image[4..6].copy_from_slice(&IMAGE_VERSION.to_le_bytes());
+        image[6..8].copy_from_slice(&(IMAGE_HEADER_MIN_SIZE as u16).to_le_bytes());
+        image[12..16].copy_from_slice(&flags.to_le_bytes());
+        image[16..20].copy_from_slice(&(payload.len() as u32).to_le_bytes());
+        image[20..24].copy_from_slice(&crc32(payload).to_le_bytes());
+        let load_address = if flags & IMAGE_FLAG_FIRMWARE != 0 { LPDDR1_BASE_ADDRESS } else { FIRMWARE_RESERVED_END };
+        image[24..32].copy_from_slice(&(load_address as u64).to_le_bytes());
+        let crc = crc32(&image);
+        image[8..12].copy_from_slice(&crc.to_le_bytes());
+        image.extend_from_slice(payload);
+        image
+    }
+
+    fn sequences(disk: &mut [u8]) -> Vec<Option<u32>> {
+        read_banks(&mut RamDisk::new(disk)).unwrap().iter().map(|b| b.map(|info| info.sequence)).collect()
+    }
+
+    #[test]
+    fn updates_alternate_between_banks() {
+        let mut disk = disk();
+        let first = image(IMAGE_FLAG_FIRMWARE, &[0x11; 700]);
+        let second = image(IMAGE_FLAG_FIRMWARE, &[0x22; 300]);
+        assert_eq!(install(&mut RamDisk::new(&mut disk), &first).unwrap(), (0, 1));
+        assert_eq!(sequences(&mut disk), [Some(1), None]);
+        assert_eq!(install(&mut RamDisk::new(&mut disk), &second).unwrap(), (1, 2));
+        assert_eq!(sequences(&mut disk), [Some(1), Some(2)]);
+        // Üçüncü güncelleme artık etkin olmayan bankanın (0) üzerine yazılır.
+        assert_eq!(install(&mut RamDisk::new(&mut disk), &first).unwrap(), (0, 3));
+        let banks = read_banks(&mut RamDisk::new(&mut disk)).unwrap();
+        assert_eq!(active_bank(&banks), Some(0));
+    }
+
+    #[test]
+    fn interrupted_update_leaves_the_old_bank_active() {
+        let mut disk = disk();
+        install(&mut RamDisk::new(&mut disk), &image(IMAGE_FLAG_FIRMWARE, &[0x11; 700])).unwrap();
+        let update = image(IMAGE_FLAG_FIRMWARE, &[0x22; 2000]);
+        // Tanımlayıcı silme + 5 imaj bloğu; güç yeni tanımlayıcıdan önce kesilir (her noktada).
+        for limit in 0..=5 {
+            let mut torn = disk.clone();
+            let mut device = RamDisk::new(&mut torn);
+            device.set_write_limit(Some(limit));
+            assert!(matches!(install(&mut device, &update), Err(UpdateError::Storage(StorageError::WriteError))));
+            let banks = read_banks(&mut RamDisk::new(&mut torn)).unwrap();
+            assert_eq!(active_bank(&banks), Some(0), "power cut after {} writes", limit);
+            assert_eq!(banks[0].unwrap().sequence, 1);
+        }
+    }
+
+    #[test]
+    fn banks_overlapping_a_partition_are_refused() {
+        let mut disk = disk();
+        let entry = MBR_PARTITION_TABLE_OFFSET; // 1500..1600: ikinci bankanın içinde
+        disk[entry + 4] = 0x83;
+        disk[entry + 8..entry + 12].copy_from_slice(&1500u32.to_le_bytes());
+        disk[entry + 12..entry + 16].copy_from_slice(&100u32.to_le_bytes());
+        disk[MBR_SIGNATURE_OFFSET..MBR_SIGNATURE_OFFSET + 2].copy_from_slice(&MBR_SIGNATURE);
+        let result = install(&mut RamDisk::new(&mut disk), &image(IMAGE_FLAG_FIRMWARE, &[1; 10]));
+        assert!(matches!(result, Err(UpdateError::RegionInUse)));
+        assert!(matches!(install(&mut RamDisk::new(&mut [0u8; 64 * BLOCK_SIZE]), &[0; 10]), Err(UpdateError::NotAvailable)));
+    }
+
+    #[test]
+    fn non_firmware_and_oversized_images_are_refused() {
+        let not_firmware = image(0, &[1; 10]);
+        assert!(matches!(unsafe { verify_image(&not_firmware) }, Err(UpdateError::NotFirmware)));
+        let too_large = image(IMAGE_FLAG_FIRMWARE, &vec![1; FIRMWARE_MAX_IMAGE_BYTES]);
+        assert!(matches!(unsafe { verify_image(&too_large) }, Err(UpdateError::TooLarge)));
+        assert!(matches!(install(&mut RamDisk::new(&mut disk()), &too_large), Err(UpdateError::TooLarge)));
+    }
+
+    #[test]
+    fn newest_bank_survives_sequence_wrap_around() {
+        let bank = |sequence| Some(BankInfo { sequence, image_size: 0, image_crc32: 0 });
+        assert_eq!(active_bank(&[bank(u32::MAX), bank(0)]), Some(1));
+        assert_eq!(active_bank(&[bank(1), bank(u32::MAX - 1)]), Some(0));
+        assert_eq!(active_bank(&[None, bank(5)]), Some(1));
+        assert_eq!(active_bank(&[None, None]), None);
+
+        // Sıra numarası u32::MAX olan bankadan sonraki güncelleme 0 alır ve etkin olur.
+        let mut disk = disk();
+        let mut device = RamDisk::new(&mut disk);
+        install(&mut device, &image(IMAGE_FLAG_FIRMWARE, &[1; 10])).unwrap();
+        let info = read_banks(&mut device).unwrap()[0].unwrap();
+        write_descriptor(&mut device, 0, Some(BankInfo { sequence: u32::MAX, ..info })).unwrap();
+        assert_eq!(install(&mut device, &image(IMAGE_FLAG_FIRMWARE, &[2; 10])).unwrap(), (1, 0));
+        assert_eq!(active_bank(&read_banks(&mut device).unwrap()), Some(1));
+    }
+}
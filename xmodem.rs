@@ -0,0 +1,291 @@
+#![no_std]
+
+// UART üzerinden dosya alma: XMODEM (128/1K, CRC-16) ve YMODEM (tek dosya).
+// Firmware alıcıdır; gönderici terminal programıdır (örn. `sb`, `sx`, minicom, Tera Term).
+//
+//   Paket: SOH|STX  no  ~no  veri[128|1024]  crc_hi  crc_lo
+//   Alıcı 'C' göndererek CRC modunda başlatır; her paket ACK veya NAK ile yanıtlanır.
+//   EOT aktarımı bitirir; art arda iki CAN iptal eder.
+//   YMODEM'de 0 numaralı paket dosya adını ve ondalık boyutunu taşır; boş ad grubu (batch) bitirir.
+//
+// Aktarım sırasında UART'a başka bir şey (log!) yazılmamalıdır, protokolü bozar.
+
+use crate::crc::crc16_xmodem;
+use crate::timer::Deadline;
+use crate::uart::Uart0;
+
+pub const SOH: u8 = 0x01;
+pub const STX: u8 = 0x02;
+pub const EOT: u8 = 0x04;
+pub const ACK: u8 = 0x06;
+pub const NAK: u8 = 0x15;
+pub const CAN: u8 = 0x18; // Kullanıcı da Ctrl-X ile iptal edebilir
+pub const CRC_REQUEST: u8 = b'C';
+
+const SHORT_PACKET_LEN: usize = 128;
+const LONG_PACKET_LEN: usize = 1024;
+
+// Göndericinin başlaması için beklenen süre: her saniye 'C' gönderilir.
+pub const START_TIMEOUT_SECS: u32 = 60;
+// Paket başlangıcı için bekleme ve paket içi bayt zaman aşımı.
+const PACKET_TIMEOUT_MS: u64 = 10_000;
+const BYTE_TIMEOUT_MS: u64 = 1_000;
+// Aynı paket için art arda hata sınırı.
+const MAX_ERRORS: u32 = 10;
+pub const MAX_FILE_NAME_LEN: usize = 64;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Protocol {
+    Xmodem,
+    Ymodem,
+}
+
+impl Protocol {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Protocol::Xmodem => "XMODEM",
+            Protocol::Ymodem => "YMODEM",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum TransferError {
+    Timeout,        // Gönderici başlamadı veya yanıt vermeyi kesti
+    Cancelled,      // Gönderici veya kullanıcı iptal etti (CAN CAN)
+    TooManyErrors,  // Aynı paket MAX_ERRORS kez bozuk geldi
+    OutOfSequence,  // Beklenmeyen paket numarası, senkron kayboldu
+    FileTooLarge,   // Dosya hedef alana sığmıyor
+    InvalidHeader,  // YMODEM dosya bilgisi paketi çözümlenemedi
+    NoFile,         // YMODEM grubu dosya içermiyor
+    MultipleFiles,  // YMODEM grubunda birden fazla dosya var
+    Truncated,      // Gönderici bildirdiği boyuttan az veri gönderip bitirdi
+}
+
+/// Tamamlanan aktarımın bilgisi.
+#[derive(Debug)]
+pub struct Transfer {
+    // YMODEM'de bildirilen boyut; XMODEM'de son paketin dolgusu (0x1A) dahil alınan bayt sayısı.
+    pub size: usize,
+    name: [u8; MAX_FILE_NAME_LEN],
+    name_len: usize,
+}
+
+impl Transfer {
+    /// YMODEM dosya adı (XMODEM'de boş).
+    pub fn name(&self) -> &str {
+        core::str::from_utf8(&self.name[..self.name_len]).unwrap_or("")
+    }
+}
+
+enum Packet {
+    Data { number: u8, len: usize },
+    EndOfTransmission,
+    Cancel,
+}
+
+enum PacketError {
+    Timeout,
+    Corrupt,
+}
+
+unsafe fn read_byte_timeout(uart: &mut Uart0, timeout_ms: u64) -> Option<u8> {
+    let deadline = Deadline::after_ms(timeout_ms);
+    loop {
+        if let Some(byte) = uart.read_byte() {
+            return Some(byte);
+        }
+        if deadline.expired() {
+            return None;
+        }
+        #[cfg(feature = "hosted")]
+        std::thread::yield_now();
+    }
+}
+
+// Hattaki bozuk paketin kalanını atar (1 saniye sessizlik olana kadar).
+unsafe fn purge(uart: &mut Uart0) {
+    while read_byte_timeout(uart, BYTE_TIMEOUT_MS).is_some() {}
+}
+
+unsafe fn cancel(uart: &mut Uart0) {
+    for _ in 0..3 {
+        uart.putc(CAN);
+    }
+    purge(uart);
+}
+
+unsafe fn receive_packet(uart: &mut Uart0, data: &mut [u8; LONG_PACKET_LEN], timeout_ms: u64) -> Result<Packet, PacketError> {
+    let len = match read_byte_timeout(uart, timeout_ms).ok_or(PacketError::Timeout)? {
+        SOH => SHORT_PACKET_LEN,
+        STX => LONG_PACKET_LEN,
+        EOT => return Ok(Packet::EndOfTransmission),
+        CAN => {
+            // Tek bir CAN hat gürültüsü olabilir; iptal için ikincisi beklenir.
+            return match read_byte_timeout(uart, BYTE_TIMEOUT_MS) {
+                Some(CAN) => Ok(Packet::Cancel),
+                _ => Err(PacketError::Corrupt),
+            };
+        }
+        _ => return Err(PacketError::Corrupt),
+    };
+    let number = read_byte_timeout(uart, BYTE_TIMEOUT_MS).ok_or(PacketError::Timeout)?;
+    let complement = read_byte_timeout(uart, BYTE_TIMEOUT_MS).ok_or(PacketError::Timeout)?;
+    for byte in data[..len].iter_mut() {
+        *byte = read_byte_timeout(uart, BYTE_TIMEOUT_MS).ok_or(PacketError::Timeout)?;
+    }
+    let crc_high = read_byte_timeout(uart, BYTE_TIMEOUT_MS).ok_or(PacketError::Timeout)?;
+    let crc_low = read_byte_timeout(uart, BYTE_TIMEOUT_MS).ok_or(PacketError::Timeout)?;
+    if number != !complement || crc16_xmodem(&data[..len]) != u16::from_be_bytes([crc_high, crc_low]) {
+        return Err(PacketError::Corrupt);
+    }
+    Ok(Packet::Data { number, len })
+}
+
+// Göndericiyi 'C' ile başlatır ve ilk paketi alır.
+unsafe fn start(uart: &mut Uart0, data: &mut [u8; LONG_PACKET_LEN]) -> Result<Packet, TransferError> {
+    for _ in 0..START_TIMEOUT_SECS {
+        uart.putc(CRC_REQUEST);
+        match receive_packet(uart, data, 1000) {
+            Ok(packet) => return Ok(packet),
+            Err(PacketError::Timeout) => {}
+            Err(PacketError::Corrupt) => purge(uart),
+        }
+    }
+    Err(TransferError::Timeout)
+}
+
+// YMODEM dosya bilgisi paketi: "ad\0boyut [mtime mode ...]\0". Boş ad grubun sonudur.
+fn parse_file_info(data: &[u8], transfer: &mut Transfer) -> Result<Option<usize>, TransferError> {
+    let name_len = data.iter().position(|&b| b == 0).ok_or(TransferError::InvalidHeader)?;
+    if name_len == 0 {
+        return Err(TransferError::NoFile);
+    }
+    // Yol bileşenleri atlanır, sığmayan ad kesilir.
+    let name = &data[..name_len];
+    let base = name.iter().rposition(|&b| b == b'/').map_or(name, |i| &name[i + 1..]);
+    transfer.name_len = core::cmp::min(base.len(), MAX_FILE_NAME_LEN);
+    transfer.name[..transfer.name_len].copy_from_slice(&base[..transfer.name_len]);
+
+    let rest = &data[name_len + 1..];
+    let size_len = rest.iter().position(|&b| b == b' ' || b == 0).unwrap_or(rest.len());
+    if size_len == 0 {
+        return Ok(None); // Boyut bildirilmemiş (isteğe bağlı)
+    }
+    let size = core::str::from_utf8(&rest[..size_len]).ok().and_then(|s| s.parse().ok()).ok_or(TransferError::InvalidHeader)?;
+    Ok(Some(size))
+}
+
+/// Bir dosyayı `buffer`'a alır. Dosya sığmazsa aktarım iptal edilir.
+/// # Safety
+/// UART'a doğrudan erişir; aktarım sürerken başka bir şey UART'a yazmamalıdır.
+pub unsafe fn receive(uart: &mut Uart0, protocol: Protocol, buffer: &mut [u8]) -> Result<Transfer, TransferError> {
+    let mut data = [0u8; LONG_PACKET_LEN];
+    let mut transfer = Transfer { size: 0, name: [0; MAX_FILE_NAME_LEN], name_len: 0 };
+    let mut declared_size = None;
+
+    let mut packet = start(uart, &mut data)?;
+    if protocol == Protocol::Ymodem {
+        let header_len = match packet {
+            Packet::Data { number: 0, len } => len,
+            Packet::Cancel => return Err(TransferError::Cancelled),
+            _ => {
+                cancel(uart);
+                return Err(TransferError::InvalidHeader);
+            }
+        };
+        // Sadece alınan paket çözümlenir; arabelleğin kalanı önceki verilerden kalmış olabilir.
+        match parse_file_info(&data[..header_len], &mut transfer) {
+            Ok(size) => declared_size = size,
+            Err(TransferError::NoFile) => {
+                uart.putc(ACK);
+                return Err(TransferError::NoFile);
+            }
+            Err(e) => {
+                cancel(uart);
+                return Err(e);
+            }
+        }
+        if declared_size.is_some_and(|size| size > buffer.len()) {
+            cancel(uart);
+            return Err(TransferError::FileTooLarge);
+        }
+        uart.putc(ACK);
+        packet = start(uart, &mut data)?;
+    }
+
+    let mut expected: u8 = 1;
+    let mut received = 0usize;
+    let mut errors = 0;
+    loop {
+        match packet {
+            Packet::Data { number, len } if number == expected => {
+                // YMODEM'de bildirilen boyutun ötesi son paketin dolgusudur.
+                let limit = declared_size.unwrap_or(usize::MAX);
+                let take = core::cmp::min(len, limit.saturating_sub(received));
+                if received + take > buffer.len() {
+                    cancel(uart);
+                    return Err(TransferError::FileTooLarge);
+                }
+                buffer[received..received + take].copy_from_slice(&data[..take]);
+                received += take;
+                expected = expected.wrapping_add(1);
+                errors = 0;
+                uart.putc(ACK);
+            }
+            // ACK'imiz kaybolduysa gönderici aynı paketi tekrarlar.
+            Packet::Data { number, .. } if number == expected.wrapping_sub(1) => uart.putc(ACK),
+            Packet::Data { .. } => {
+                cancel(uart);
+                return Err(TransferError::OutOfSequence);
+            }
+            Packet::EndOfTransmission => {
+                if protocol == Protocol::Ymodem {
+                    // YMODEM: ilk EOT NAK'lanır, tekrarı onaylanır.
+                    uart.putc(NAK);
+                    if !matches!(receive_packet(uart, &mut data, PACKET_TIMEOUT_MS), Ok(Packet::EndOfTransmission)) {
+                        cancel(uart);
+                        return Err(TransferError::OutOfSequence);
+                    }
+                }
+                uart.putc(ACK);
+                break;
+            }
+            Packet::Cancel => return Err(TransferError::Cancelled),
+        }
+
+        packet = loop {
+            match receive_packet(uart, &mut data, PACKET_TIMEOUT_MS) {
+                Ok(packet) => break packet,
+                Err(e) => {
+                    errors += 1;
+                    if errors >= MAX_ERRORS {
+                        cancel(uart);
+                        return Err(TransferError::TooManyErrors);
+                    }
+                    if let PacketError::Corrupt = e {
+                        purge(uart);
+                    }
+                    uart.putc(NAK);
+                }
+            }
+        };
+    }
+
+    if protocol == Protocol::Ymodem {
+        // Grubun sonu: boş dosya bilgisi paketi beklenir.
+        match start(uart, &mut data)? {
+            Packet::Data { number: 0, .. } if data[0] == 0 => uart.putc(ACK),
+            _ => {
+                cancel(uart);
+                return Err(TransferError::MultipleFiles);
+            }
+        }
+    }
+
+    transfer.size = declared_size.unwrap_or(received);
+    if transfer.size > received {
+        return Err(TransferError::Truncated);
+    }
+    Ok(transfer)
+}